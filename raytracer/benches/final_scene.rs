@@ -0,0 +1,54 @@
+// cargo bench --bench final_scene
+// 用同样的设置渲染 final_scene, 对比顶层的扁平 BVH (SAH) 和原来用 Bvhnode 按中位数划分建的树
+use raytracer::{render, Bvhnode, Bvhsplit, Hittablelist, Object, RenderSettings, Scene};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const RUNS: usize = 3;
+
+fn settings() -> RenderSettings {
+    let mut settings = RenderSettings::new(160, 160);
+    settings.samples_per_pixel = 8;
+    settings.threads = 1;
+    settings.seed = Some(42);
+    settings
+}
+
+fn main() {
+    // 场景文件里贴图和模型的路径是相对仓库根目录的
+    std::env::set_current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/..")).unwrap();
+
+    for &(name, flat, split) in [
+        ("flat bvh", true, Bvhsplit::Sah),
+        ("bvhnode tree", false, Bvhsplit::Median),
+    ]
+    .iter()
+    {
+        // 对比的基线整个按中位数划分建树, 网格内部的 BVH 也一样
+        let path = "scenes/final_scene.json";
+        let mut scene = Scene::load_with(path, settings().aspect_ratio(), split).unwrap();
+        if !flat {
+            let mut objects = Hittablelist::default_new();
+            for object in &scene.world.objects {
+                objects.add(object.copy());
+            }
+            let (time0, time1) = (scene.camera.time0, scene.camera.time1);
+            let tree = Bvhnode::build(objects, time0, time1, split).unwrap();
+            scene.world = Arc::new(Hittablelist::new(Object::Bvhnode(tree)));
+        }
+
+        let mut best = Duration::from_secs(u64::MAX);
+        for _ in 0..RUNS {
+            let begin_time = Instant::now();
+            render(&scene, settings());
+            let elapsed = begin_time.elapsed();
+            if elapsed < best {
+                best = elapsed;
+            }
+        }
+        println!(
+            "final_scene {:>12}: {:>10.2?} (best of {}, 160x160, 8 spp, 1 thread)",
+            name, best, RUNS
+        );
+    }
+}
@@ -43,6 +43,34 @@ impl Aabb {
         self.maximum.copy()
     }
 
+    pub fn area(&self) -> f64 {
+        let d = self.maximum.copy() - self.minimum.copy();
+        2.0 * (d.x() * d.y() + d.y() * d.z() + d.z() * d.x())
+    }
+
+    pub fn centroid(&self) -> Point3 {
+        (self.minimum.copy() + self.maximum.copy()) * 0.5
+    }
+
+    // 射线方向的倒数提前算好, 给扁平 BVH 遍历用
+    pub fn hit_inv(&self, origin: &Point3, inv_dir: &Vec3, t_min: f64, t_max: f64) -> bool {
+        let mut t_min = t_min;
+        let mut t_max = t_max;
+        for a in 0..3 {
+            let mut t0 = (self.minimum[a] - origin[a]) * inv_dir[a];
+            let mut t1 = (self.maximum[a] - origin[a]) * inv_dir[a];
+            if inv_dir[a] < 0.0 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+            t_min = func::fmax(t0, t_min);
+            t_max = func::fmin(t1, t_max);
+            if t_max < t_min {
+                return false;
+            }
+        }
+        true
+    }
+
     pub fn surrounding_box(box0: &Aabb, box1: &Aabb) -> Aabb {
         let small = Point3::new(
             func::fmin(box0.min().x(), box1.min().x()),
@@ -60,6 +88,9 @@ impl Aabb {
 
 impl Hit for Aabb {
     fn hit(&self, r: &Ray, t_min: f64, t_max: f64, rec: &mut Hitrecord) -> bool {
+        // 三个轴的区间要依次求交, 不能在循环里重新绑定
+        let mut t_min = t_min;
+        let mut t_max = t_max;
         for a in 0..3 {
             let invd = 1.0 / r.direction()[a];
             let mut t0 = (self.min()[a] - r.origin()[a]) * invd;
@@ -69,8 +100,8 @@ impl Hit for Aabb {
                 t0 = t1;
                 t1 = tmp;
             }
-            let t_min = func::fmax(t0, t_min);
-            let t_max = func::fmin(t1, t_max);
+            t_min = func::fmax(t0, t_min);
+            t_max = func::fmin(t1, t_max);
             if t_max <= t_min {
                 return false;
             }
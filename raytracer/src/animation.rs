@@ -0,0 +1,228 @@
+#![allow(unused_variables)]
+pub use crate::aabb::Aabb;
+pub use crate::hittable::Boundingbox;
+pub use crate::hittable::Hit;
+pub use crate::hittable::Hitrecord;
+pub use crate::hittable_list::Object;
+pub use crate::matrix::Matrix4;
+pub use crate::ray::Ray;
+pub use crate::vec3::Point3;
+pub use crate::vec3::Vec3;
+use std::sync::Arc;
+
+const BOX_SAMPLES: usize = 64; // 求包围盒时每两个关键帧之间取几个姿态
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Interpolation {
+    Linear,
+    // 三次 Hermite: 中间关键帧的切线取前后两帧的差 (Catmull-Rom), 首尾切线为 0, 起停时缓入缓出
+    Smooth,
+}
+
+// times 严格递增, value(i) 是第 i 个关键帧的值; 第一帧之前、最后一帧之后保持不动
+pub fn interpolate<F: Fn(usize) -> Vec3>(
+    times: &[f64],
+    time: f64,
+    interpolation: Interpolation,
+    value: F,
+) -> Vec3 {
+    let n = times.len();
+    if n == 1 || time <= times[0] {
+        return value(0);
+    }
+    if time >= times[n - 1] {
+        return value(n - 1);
+    }
+    let i = times.iter().rposition(|&k| k <= time).unwrap();
+    let span = times[i + 1] - times[i];
+    let s = (time - times[i]) / span;
+    let p0 = value(i);
+    let p1 = value(i + 1);
+    match interpolation {
+        Interpolation::Linear => p0 + (p1 - p0) * s,
+        Interpolation::Smooth => {
+            // 切线按这一段的时长缩放到 s ∈ [0, 1] 上
+            let tangent = |j: usize| {
+                if j == 0 || j == n - 1 {
+                    Vec3::default_new()
+                } else {
+                    (value(j + 1) - value(j - 1)) / (times[j + 1] - times[j - 1]) * span
+                }
+            };
+            let s2 = s * s;
+            let s3 = s2 * s;
+            p0 * (2.0 * s3 - 3.0 * s2 + 1.0)
+                + tangent(i) * (s3 - 2.0 * s2 + s)
+                + p1 * (3.0 * s2 - 2.0 * s3)
+                + tangent(i + 1) * (s3 - s2)
+        }
+    }
+}
+
+// 某一时刻物体的姿态: 先缩放, 再依次绕 x、y、z 轴转 rotate (角度), 最后平移
+pub struct Keyframe {
+    pub time: f64,
+    pub translate: Vec3,
+    pub rotate: Vec3,
+    pub scale: Vec3,
+}
+
+// 按光线的时间在关键帧之间插值变换的物体, 同一帧内的运动就是运动模糊;
+// 位置按时间变化, 不作为光源采样 (光源采样不知道时间), 发光的部分只靠材质采样打到
+pub struct Animated {
+    ptr: Arc<Object>,
+    keys: Arc<Vec<Keyframe>>,
+    times: Arc<Vec<f64>>,
+    interpolation: Interpolation,
+    ends: Arc<[Option<(Matrix4, Matrix4)>; 2]>, // 第一帧之前、最后一帧之后姿态不变, 提前算好
+}
+
+impl Animated {
+    // keys 不能为空, 时间严格递增
+    pub fn new(p: Object, keys: Vec<Keyframe>, interpolation: Interpolation) -> Animated {
+        let first = &keys[0];
+        let last = &keys[keys.len() - 1];
+        let ends = [
+            pose(&first.translate, &first.rotate, &first.scale),
+            pose(&last.translate, &last.rotate, &last.scale),
+        ];
+        Animated {
+            ptr: Arc::new(p),
+            times: Arc::new(keys.iter().map(|k| k.time).collect()),
+            keys: Arc::new(keys),
+            interpolation,
+            ends: Arc::new(ends),
+        }
+    }
+
+    pub fn copy(&self) -> Animated {
+        Animated {
+            ptr: self.ptr.clone(),
+            keys: self.keys.clone(),
+            times: self.times.clone(),
+            interpolation: self.interpolation,
+            ends: self.ends.clone(),
+        }
+    }
+
+    // time 时刻物体空间 -> 世界空间的矩阵
+    pub fn matrix_at(&self, time: f64) -> Matrix4 {
+        let (translate, rotate, scale) = self.decompose_at(time);
+        compose(&translate, &rotate, &scale)
+    }
+
+    // time 时刻的矩阵和它的逆, 某个轴缩放到 0 时没有逆
+    pub fn pose_at(&self, time: f64) -> Option<(Matrix4, Matrix4)> {
+        if time <= self.times[0] {
+            return self.ends[0];
+        }
+        if time >= self.times[self.times.len() - 1] {
+            return self.ends[1];
+        }
+        let (translate, rotate, scale) = self.decompose_at(time);
+        pose(&translate, &rotate, &scale)
+    }
+
+    fn decompose_at(&self, time: f64) -> (Vec3, Vec3, Vec3) {
+        let lerp = |f: fn(&Keyframe) -> Vec3| {
+            interpolate(&self.times, time, self.interpolation, |i| f(&self.keys[i]))
+        };
+        (lerp(|k| k.translate), lerp(|k| k.rotate), lerp(|k| k.scale))
+    }
+}
+
+fn compose(translate: &Vec3, rotate: &Vec3, scale: &Vec3) -> Matrix4 {
+    Matrix4::translate(translate)
+        * Matrix4::rotate_z(rotate.z())
+        * Matrix4::rotate_y(rotate.y())
+        * Matrix4::rotate_x(rotate.x())
+        * Matrix4::scale(scale)
+}
+
+// 逆矩阵按分解的顺序反过来拼, 不用每条光线都做一次消元
+fn pose(translate: &Vec3, rotate: &Vec3, scale: &Vec3) -> Option<(Matrix4, Matrix4)> {
+    if scale.x() == 0.0 || scale.y() == 0.0 || scale.z() == 0.0 {
+        return None;
+    }
+    let inverse = Matrix4::scale(&Vec3::new(
+        1.0 / scale.x(),
+        1.0 / scale.y(),
+        1.0 / scale.z(),
+    )) * Matrix4::rotate_x(-rotate.x())
+        * Matrix4::rotate_y(-rotate.y())
+        * Matrix4::rotate_z(-rotate.z())
+        * Matrix4::translate(&-*translate);
+    Some((compose(translate, rotate, scale), inverse))
+}
+
+impl Hit for Animated {
+    fn hit(&self, r: &Ray, t_min: f64, t_max: f64, rec: &mut Hitrecord) -> bool {
+        // 缩放到 0 的那一刻看不见
+        let (matrix, inverse) = match self.pose_at(r.time()) {
+            Some(pose) => pose,
+            None => return false,
+        };
+        let object_r = Ray::new(
+            &inverse.point(&r.origin()),
+            &inverse.vector(&r.direction()),
+            r.time(),
+        );
+        if !self.ptr.hit(&object_r, t_min, t_max, rec) {
+            return false;
+        }
+        rec.p = matrix.point(&rec.p);
+        rec.normal = inverse.transpose().vector(&rec.normal).unit_vector();
+        true
+    }
+}
+
+impl Boundingbox for Animated {
+    fn boundingbox(&self, time0: f64, time1: f64, output_box: &mut Aabb) -> bool {
+        let mut bbox = Aabb::default_new();
+        if !self.ptr.boundingbox(time0, time1, &mut bbox) {
+            return false;
+        }
+
+        // 关键帧范围之外姿态不变, 只需要在两者重叠的时间里取样
+        let first = self.times[0];
+        let last = self.times[self.times.len() - 1];
+        let begin = time0.max(first).min(last);
+        let end = time1.min(last).max(begin);
+        let steps = if end > begin {
+            BOX_SAMPLES * self.times.len()
+        } else {
+            1
+        };
+
+        // 逐个姿态变换包围盒的八个角; 两次取样之间角点走过的路不会离两端超过相邻取样的距离, 按最大值外扩
+        let mut min = Point3::new(f64::INFINITY, f64::INFINITY, f64::INFINITY);
+        let mut max = -min;
+        let ends = [bbox.min(), bbox.max()];
+        let mut previous: Vec<Point3> = Vec::new();
+        let mut pad: f64 = 0.0;
+        for step in 0..=steps {
+            let matrix = self.matrix_at(begin + (end - begin) * step as f64 / steps as f64);
+            let mut corners = Vec::with_capacity(8);
+            for i in 0..8 {
+                let corner = Point3::new(
+                    ends[i & 1].x(),
+                    ends[(i >> 1) & 1].y(),
+                    ends[(i >> 2) & 1].z(),
+                );
+                let p = matrix.point(&corner);
+                for c in 0..3 {
+                    min[c] = min[c].min(p[c]);
+                    max[c] = max[c].max(p[c]);
+                }
+                corners.push(p);
+            }
+            for (a, b) in previous.iter().zip(corners.iter()) {
+                pad = pad.max((*a - *b).length());
+            }
+            previous = corners;
+        }
+        let pad = Vec3::new(pad, pad, pad);
+        *output_box = Aabb::new(&(min - pad), &(max + pad));
+        true
+    }
+}
@@ -6,14 +6,13 @@ pub use crate::hittable::Hitrecord;
 pub use crate::hittable_list::Hittablelist;
 pub use crate::hittable_list::Object;
 pub use crate::material::Material;
+pub use crate::quad::Quad;
 pub use crate::ray::Ray;
-pub use crate::rect::XYrect;
-pub use crate::rect::XZrect;
-pub use crate::rect::YZrect;
 pub use crate::sphere::Boundingbox;
 pub use crate::vec3::Color;
 pub use crate::vec3::Point3;
 pub use crate::vec3::Vec3;
+use std::sync::Arc;
 
 pub struct Boxx {
     box_min: Point3,
@@ -30,59 +29,27 @@ impl Boxx {
         }
     }
 
-    pub fn new(p0: &Point3, p1: &Point3, ptr: &Option<Box<Material>>) -> Boxx {
+    pub fn new(p0: &Point3, p1: &Point3, ptr: &Option<Arc<Material>>) -> Boxx {
         let box_min = p0.copy();
         let box_max = p1.copy();
 
+        // 六个面都用平行四边形, 边的顺序让 u x v 朝外, 封闭物体才分得清里外 (csg、体积雾要用)
+        let dx = Vec3::new(p1.x() - p0.x(), 0.0, 0.0);
+        let dy = Vec3::new(0.0, p1.y() - p0.y(), 0.0);
+        let dz = Vec3::new(0.0, 0.0, p1.z() - p0.z());
         let mut sides = Hittablelist::default_new();
-        sides.add(Object::XYrect(XYrect::new(
-            &ptr,
-            p0.x(),
-            p1.x(),
-            p0.y(),
-            p1.y(),
-            p1.z(),
-        )));
-        sides.add(Object::XYrect(XYrect::new(
-            &ptr,
-            p0.x(),
-            p1.x(),
-            p0.y(),
-            p1.y(),
-            p0.z(),
-        )));
-        sides.add(Object::XZrect(XZrect::new(
-            &ptr,
-            p0.x(),
-            p1.x(),
-            p0.z(),
-            p1.z(),
-            p1.y(),
-        )));
-        sides.add(Object::XZrect(XZrect::new(
-            &ptr,
-            p0.x(),
-            p1.x(),
-            p0.z(),
-            p1.z(),
-            p0.y(),
-        )));
-        sides.add(Object::YZrect(YZrect::new(
-            &ptr,
-            p0.y(),
-            p1.y(),
-            p0.z(),
-            p1.z(),
-            p1.x(),
-        )));
-        sides.add(Object::YZrect(YZrect::new(
-            &ptr,
-            p0.y(),
-            p1.y(),
-            p0.z(),
-            p1.z(),
-            p0.x(),
-        )));
+        for (q, u, v) in [
+            (Point3::new(p0.x(), p0.y(), p1.z()), dx, dy),  // 前 +z
+            (Point3::new(p1.x(), p0.y(), p1.z()), -dz, dy), // 右 +x
+            (Point3::new(p1.x(), p0.y(), p0.z()), -dx, dy), // 后 -z
+            (Point3::new(p0.x(), p0.y(), p0.z()), dz, dy),  // 左 -x
+            (Point3::new(p0.x(), p1.y(), p1.z()), dx, -dz), // 上 +y
+            (Point3::new(p0.x(), p0.y(), p0.z()), dx, dz),  // 下 -y
+        ]
+        .iter()
+        {
+            sides.add(Object::Quad(Quad::new(q, u, v, &ptr)));
+        }
 
         Boxx {
             box_min,
@@ -91,6 +58,10 @@ impl Boxx {
         }
     }
 
+    pub fn sides(&self) -> &Hittablelist {
+        &self.sides
+    }
+
     pub fn copy(&self) -> Boxx {
         Boxx {
             box_min: self.box_min.copy(),
@@ -2,6 +2,7 @@
 pub use crate::aabb::Aabb;
 pub use crate::camera::Camera;
 pub use crate::dielectric::Dielectric;
+pub use crate::func;
 pub use crate::hittable::Hit;
 pub use crate::hittable::Hitrecord;
 pub use crate::hittable_list::Hittablelist;
@@ -19,6 +20,122 @@ pub use crate::vec3::Color;
 pub use crate::vec3::Point3;
 pub use crate::vec3::Vec3;
 pub use std::cmp::Ordering;
+pub use std::time::Duration;
+
+const SAH_BINS: usize = 12;
+
+// 建树方式: 随机轴中位数划分, 或者分桶的表面积启发式 (SAH)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Bvhsplit {
+    Median,
+    Sah,
+}
+
+// Bvhnode 的叶子是挂在节点下面的物体, Flatbvh 的叶子是一段物体区间
+#[derive(Clone, Copy, Debug)]
+pub struct Bvhstats {
+    pub node_count: usize, // 所有节点, 包括叶子
+    pub leaf_count: usize,
+    pub depth: usize, // 从根到最深的叶子经过的节点数
+    pub build_time: Duration,
+}
+
+// 分桶 SAH 找到的划分: 中心点落在 axis 轴第 split 个桶之前的物体分到左边
+pub struct Sahsplit {
+    pub axis: usize,
+    pub cmin: f64,
+    pub extent: f64,
+    pub split: usize,
+    pub cost: f64, // 左右 面积 * 个数 之和
+}
+
+impl Sahsplit {
+    // 每个轴把中心点分到 SAH_BINS 个桶里, 在桶边界中找 面积 * 个数 之和最小的划分,
+    // 所有中心点重合时返回 None
+    pub fn find(boxes: &[Aabb]) -> Option<Sahsplit> {
+        let mut best: Option<Sahsplit> = None;
+        for axis in 0..3 {
+            let mut cmin = f64::INFINITY;
+            let mut cmax = f64::NEG_INFINITY;
+            for b in boxes {
+                cmin = func::fmin(cmin, b.centroid()[axis]);
+                cmax = func::fmax(cmax, b.centroid()[axis]);
+            }
+            let extent = cmax - cmin;
+            if extent <= 1e-12 {
+                continue;
+            }
+
+            let mut counts = vec![0_usize; SAH_BINS];
+            let mut bin_boxes: Vec<Option<Aabb>> = (0..SAH_BINS).map(|_| None).collect();
+            for b in boxes {
+                let i = Sahsplit::bin_index(b.centroid()[axis], cmin, extent);
+                counts[i] += 1;
+                bin_boxes[i] = Some(match &bin_boxes[i] {
+                    Some(bin_box) => Aabb::surrounding_box(&bin_box, &b),
+                    None => b.copy(),
+                });
+            }
+
+            // 从右往左累积, right_area[i] 是 i.. 这些桶的包围盒面积
+            let mut right_area = vec![0.0; SAH_BINS];
+            let mut right_count = vec![0_usize; SAH_BINS];
+            let mut acc: Option<Aabb> = None;
+            let mut count = 0;
+            for i in (1..SAH_BINS).rev() {
+                if let Some(bin_box) = &bin_boxes[i] {
+                    acc = Some(match &acc {
+                        Some(acc_box) => Aabb::surrounding_box(&acc_box, &bin_box),
+                        None => bin_box.copy(),
+                    });
+                }
+                count += counts[i];
+                right_count[i] = count;
+                right_area[i] = acc.as_ref().map_or(0.0, |b| b.area());
+            }
+
+            let mut acc: Option<Aabb> = None;
+            let mut count = 0;
+            for split in 1..SAH_BINS {
+                if let Some(bin_box) = &bin_boxes[split - 1] {
+                    acc = Some(match &acc {
+                        Some(acc_box) => Aabb::surrounding_box(&acc_box, &bin_box),
+                        None => bin_box.copy(),
+                    });
+                }
+                count += counts[split - 1];
+                if count == 0 || right_count[split] == 0 {
+                    continue;
+                }
+                let left_area = acc.as_ref().map_or(0.0, |b| b.area());
+                let cost = left_area * count as f64 + right_area[split] * right_count[split] as f64;
+                if best.as_ref().map_or(true, |best| cost < best.cost) {
+                    best = Some(Sahsplit {
+                        axis,
+                        cmin,
+                        extent,
+                        split,
+                        cost,
+                    });
+                }
+            }
+        }
+        best
+    }
+
+    pub fn goes_left(&self, b: &Aabb) -> bool {
+        Sahsplit::bin_index(b.centroid()[self.axis], self.cmin, self.extent) < self.split
+    }
+
+    fn bin_index(c: f64, cmin: f64, extent: f64) -> usize {
+        let i = ((c - cmin) / extent * SAH_BINS as f64) as usize;
+        if i >= SAH_BINS {
+            SAH_BINS - 1
+        } else {
+            i
+        }
+    }
+}
 
 pub struct Bvhnode {
     pub left: Option<Box<Object>>,
@@ -27,117 +144,148 @@ pub struct Bvhnode {
 }
 
 impl Bvhnode {
-    /*
-        pub fn default_new() -> Bvhnode {
-            Bvhnode {
-                left: None,
-                right: None,
-                boxx: Aabb::default_new(),
-            }
+    pub fn default_new() -> Bvhnode {
+        Bvhnode {
+            left: None,
+            right: None,
+            boxx: Aabb::default_new(),
         }
-    */
+    }
+
     pub fn copy(&self) -> Bvhnode {
         Bvhnode {
             boxx: self.boxx.copy(),
-            left: self
-                .left
-                .as_ref()
-                .map(|in_left| Box::new(in_left.copy()))
-                .map(|in_left| Box::new(in_left.copy())),
+            left: self.left.as_ref().map(|in_left| Box::new(in_left.copy())),
             right: self
                 .right
                 .as_ref()
-                .map(|in_right| Box::new(in_right.copy()))
                 .map(|in_right| Box::new(in_right.copy())),
         }
     }
 
-    pub fn new_from_list(list: &mut Hittablelist, time0: f64, time1: f64) -> Bvhnode {
-        //let len = list.objects.len();
-        Bvhnode::new_from_vec(&mut list.objects, time0, time1)
-    }
-    /*
-        pub fn new_from_vec(
-            src_objects: &mut Vec<Object>,
-            start: usize,
-            end: usize,
-            time0: f64,
-            time1: f64,
-        ) -> Bvhnode {
-            let mut myleft: Option<Box<Object>> = None;
-            let mut myright: Option<Box<Object>> = None;
-            let mut objects: &mut Vec<Object> = src_objects;
-
-            let axis = rand::random_int_between(0, 2);
-            let mut temp_x = Aabb::default_new();
-            let mut temp_y = Aabb::default_new();
-            let comparator = |x: &Object, y: &Object| {
-                x.boundingbox(time0, time1, &mut temp_x);
-                y.boundingbox(time0, time1, &mut temp_y);
-                f64::partial_cmp(
-                    &(temp_x.min()[axis as usize]),
-                    &(temp_y.min()[axis as usize]),
-                )
-                .unwrap()
-            };
-            let object_span = objects.len() - 1;
-
-            if object_span == 1 {
-                myleft = Some(Box::new(objects[start].copy()));
-                myright = Some(Box::new(objects[start].copy()));
-            } else if object_span == 2 {
-                if Bvhnode::box_compare(&objects[start], &objects[start + 1], axis) {
-                    myleft = Some(Box::new(objects[start].copy()));
-                    myright = Some(Box::new(objects[start + 1].copy()));
-                } else {
-                    myleft = Some(Box::new(objects[start + 1].copy()));
-                    myright = Some(Box::new(objects[start].copy()));
-                }
-            } else {
-                objects.sort_unstable_by(comparator);
-                let mut left_vec = objects;
-                let mut right_vec = left_vec.split_off(object_span / 2);
-
-                let mid = start + object_span / 2;
-                myleft = Some(Box::new(Object::Bvhnode(Bvhnode::new_from_vec(
-                    &mut left_vec,
-                    start,
-                    mid,
-                    time0,
-                    time1,
-                ))));
-                myright = Some(Box::new(Object::Bvhnode(Bvhnode::new_from_vec(
-                    &mut right_vec,
-                    mid,
-                    end,
-                    time0,
-                    time1,
-                ))));
+    // 空列表得到一个打不到任何东西的空节点; 没有包围盒的物体放不进树里, 直接报错
+    pub fn build(
+        mut list: Hittablelist,
+        time0: f64,
+        time1: f64,
+        split: Bvhsplit,
+    ) -> Result<Bvhnode, String> {
+        let mut boxes = Vec::with_capacity(list.objects.len());
+        for (i, object) in list.objects.iter().enumerate() {
+            let mut output_box = Aabb::default_new();
+            if !object.boundingbox(time0, time1, &mut output_box) {
+                return Err(format!("object #{} has no bounding box", i));
             }
+            boxes.push(output_box);
+        }
+        if list.objects.is_empty() {
+            return Ok(Bvhnode::default_new());
+        }
+        Ok(match split {
+            Bvhsplit::Median => Bvhnode::new_from_list(&mut list, time0, time1),
+            Bvhsplit::Sah => Bvhnode::sah_from_vec(list.objects, boxes),
+        })
+    }
 
-            let mut box_left = Aabb::default_new();
-            let mut box_right = Aabb::default_new();
+    fn sah_child(mut objects: Vec<Object>, boxes: Vec<Aabb>) -> Object {
+        if objects.len() == 1 {
+            objects.pop().unwrap()
+        } else {
+            Object::Bvhnode(Bvhnode::sah_from_vec(objects, boxes))
+        }
+    }
 
-            let mut flag_l = false;
-            let mut flag_r = false;
+    fn sah_from_vec(mut objects: Vec<Object>, mut boxes: Vec<Aabb>) -> Bvhnode {
+        let object_span = objects.len();
+        let mut boxx = boxes[0].copy();
+        for b in &boxes[1..] {
+            boxx = Aabb::surrounding_box(&boxx, &b);
+        }
 
-            if let Some(left) = &myleft {
-                flag_l = left.boundingbox(time0, time1, &mut box_left);
-            }
-            if let Some(right) = &myright {
-                flag_r = right.boundingbox(time0, time1, &mut box_right);
+        if object_span == 1 {
+            return Bvhnode {
+                left: objects.pop().map(Box::new),
+                right: None,
+                boxx,
+            };
+        }
+        if object_span == 2 {
+            let right = objects.pop().map(Box::new);
+            let left = objects.pop().map(Box::new);
+            return Bvhnode { left, right, boxx };
+        }
+
+        let best = Sahsplit::find(&boxes);
+
+        let mut left_objects = Vec::new();
+        let mut left_boxes = Vec::new();
+        let mut right_objects = Vec::new();
+        let mut right_boxes = Vec::new();
+        match best {
+            Some(best) => {
+                for (object, b) in objects.into_iter().zip(boxes.into_iter()) {
+                    if best.goes_left(&b) {
+                        left_objects.push(object);
+                        left_boxes.push(b);
+                    } else {
+                        right_objects.push(object);
+                        right_boxes.push(b);
+                    }
+                }
             }
-            if !flag_l || !flag_r {
-                eprint!("No bounding box in bvh_node constructor.");
+            None => {
+                // 所有中心点重合, 只能对半分
+                right_objects = objects.split_off(object_span / 2);
+                right_boxes = boxes.split_off(object_span / 2);
+                left_objects = objects;
+                left_boxes = boxes;
             }
+        }
 
-            Bvhnode {
-                left: myleft,
-                right: myright,
-                boxx: Aabb::surrounding_box(&box_left, &box_right),
+        Bvhnode {
+            left: Some(Box::new(Bvhnode::sah_child(left_objects, left_boxes))),
+            right: Some(Box::new(Bvhnode::sah_child(right_objects, right_boxes))),
+            boxx,
+        }
+    }
+
+    pub fn stats(&self) -> Bvhstats {
+        let mut stats = Bvhstats {
+            node_count: 0,
+            leaf_count: 0,
+            depth: 0,
+            build_time: Duration::from_secs(0),
+        };
+        self.accumulate_stats(1, &mut stats);
+        stats
+    }
+
+    fn accumulate_stats(&self, depth: usize, stats: &mut Bvhstats) {
+        stats.node_count += 1;
+        if depth > stats.depth {
+            stats.depth = depth;
+        }
+        for child in [&self.left, &self.right].iter() {
+            match child {
+                Some(child) => match &**child {
+                    Object::Bvhnode(bvhnode) => bvhnode.accumulate_stats(depth + 1, stats),
+                    _ => {
+                        stats.node_count += 1;
+                        stats.leaf_count += 1;
+                        if depth + 1 > stats.depth {
+                            stats.depth = depth + 1;
+                        }
+                    }
+                },
+                None => {}
             }
         }
-    */
+    }
+
+    pub fn new_from_list(list: &mut Hittablelist, time0: f64, time1: f64) -> Bvhnode {
+        Bvhnode::new_from_vec(&mut list.objects, time0, time1)
+    }
+
     pub fn new_from_vec(src_objects: &mut Vec<Object>, time0: f64, time1: f64) -> Bvhnode {
         let mut myleft: Option<Box<Object>> = None;
         let mut myright: Option<Box<Object>> = None;
@@ -174,7 +322,6 @@ impl Bvhnode {
             let mut left_vec = objects;
             let mut right_vec = left_vec.split_off(object_span / 2);
 
-            //let mid = object_span / 2;
             myleft = Some(Box::new(Object::Bvhnode(Bvhnode::new_from_vec(
                 &mut left_vec,
                 time0,
@@ -213,16 +360,6 @@ impl Bvhnode {
     pub fn box_compare(a: &Object, b: &Object, axis: i32) -> bool {
         let mut box_a = Aabb::default_new();
         let mut box_b = Aabb::default_new();
-        /*
-        let mut flag_a=false;
-        let mut flag_b=false;
-        if let in_a = Some(a) {
-            flag_a=in_a.boundingbox(0.0,0.0,box_a);
-        }
-        if let in_b = Some(b) {
-            flag_b=in_b.boundingbox(0.0,0.0,box_b);
-        }
-        */
         let flag_a = a.boundingbox(0.0, 0.0, &mut box_a);
         let flag_b = b.boundingbox(0.0, 0.0, &mut box_b);
         if !flag_a || !flag_b {
@@ -243,6 +380,15 @@ impl Bvhnode {
     pub fn box_z_compare(a: &Object, b: &Object) -> bool {
         Bvhnode::box_compare(&a, &b, 2)
     }
+
+    pub fn collect_lights(&self, lights: &mut Hittablelist) {
+        if let Some(left) = &self.left {
+            left.collect_lights(lights);
+        }
+        if let Some(right) = &self.right {
+            right.collect_lights(lights);
+        }
+    }
 }
 
 impl Hit for Bvhnode {
@@ -271,6 +417,6 @@ impl Hit for Bvhnode {
 impl Boundingbox for Bvhnode {
     fn boundingbox(&self, time0: f64, time1: f64, output_box: &mut Aabb) -> bool {
         *output_box = self.boxx.copy();
-        true
+        self.left.is_some()
     }
 }
@@ -1,10 +1,13 @@
 #![allow(dead_code)]
 #![allow(clippy::too_many_arguments)]
+pub use crate::animation::interpolate;
+pub use crate::animation::Interpolation;
 pub use crate::rand;
 pub use crate::ray::Ray;
 pub use crate::vec3::Color;
 pub use crate::vec3::Point3;
 pub use crate::vec3::Vec3;
+use std::sync::Arc;
 
 pub struct Camera {
     pub origin: Point3,
@@ -17,6 +20,106 @@ pub struct Camera {
     pub lens_radius: f64,
     pub time0: f64,
     pub time1: f64,
+    pub track: Option<Arc<Cameratrack>>, // 有关键帧时每条光线按自己的时间重新摆相机
+}
+
+// 某一时刻相机的参数
+pub struct Camerakey {
+    pub time: f64,
+    pub lookfrom: Point3,
+    pub lookat: Point3,
+    pub vup: Vec3,
+    pub vfov: f64,
+    pub aperture: f64,
+    pub focus_dist: f64,
+}
+
+pub struct Cameratrack {
+    keys: Vec<Camerakey>,
+    times: Vec<f64>,
+    interpolation: Interpolation,
+    aspect_ratio: f64,
+    ends: [Camera; 2], // 第一帧之前、最后一帧之后相机不动, 提前摆好
+}
+
+impl Cameratrack {
+    // keys 不能为空, 时间严格递增
+    pub fn new(
+        keys: Vec<Camerakey>,
+        interpolation: Interpolation,
+        aspect_ratio: f64,
+    ) -> Cameratrack {
+        let ends = [
+            key_camera(&keys[0], aspect_ratio),
+            key_camera(&keys[keys.len() - 1], aspect_ratio),
+        ];
+        Cameratrack {
+            times: keys.iter().map(|k| k.time).collect(),
+            keys,
+            interpolation,
+            aspect_ratio,
+            ends,
+        }
+    }
+
+    // time 时刻的相机, 快门只开在这一瞬间
+    pub fn camera_at(&self, time: f64) -> Camera {
+        if time <= self.times[0] || time >= self.times[self.times.len() - 1] {
+            let mut camera = self.end_at(time).copy();
+            camera.time0 = time;
+            camera.time1 = time;
+            return camera;
+        }
+        let lerp = |f: fn(&Camerakey) -> Vec3| {
+            interpolate(&self.times, time, self.interpolation, |i| f(&self.keys[i]))
+        };
+        let lookfrom = lerp(|k| k.lookfrom);
+        let lookat = lerp(|k| k.lookat);
+        let vup = lerp(|k| k.vup);
+        // vfov、光圈、对焦距离放在一起插值
+        let lens = lerp(|k| Vec3::new(k.vfov, k.aperture, k.focus_dist));
+        Camera::new(
+            &lookfrom,
+            &lookat,
+            &vup,
+            lens.x(),
+            self.aspect_ratio,
+            lens.y().max(0.0),
+            lens.z(),
+            time,
+            time,
+        )
+    }
+
+    // 关键帧范围外的光线直接用摆好的相机, 不用每条都重新插值
+    fn get_ray(&self, time: f64, s: f64, t: f64) -> Ray {
+        if time <= self.times[0] || time >= self.times[self.times.len() - 1] {
+            return self.end_at(time).ray_at(s, t, time);
+        }
+        self.camera_at(time).ray_at(s, t, time)
+    }
+
+    fn end_at(&self, time: f64) -> &Camera {
+        if time <= self.times[0] {
+            &self.ends[0]
+        } else {
+            &self.ends[1]
+        }
+    }
+}
+
+fn key_camera(key: &Camerakey, aspect_ratio: f64) -> Camera {
+    Camera::new(
+        &key.lookfrom,
+        &key.lookat,
+        &key.vup,
+        key.vfov,
+        aspect_ratio,
+        key.aperture.max(0.0),
+        key.focus_dist,
+        key.time,
+        key.time,
+    )
 }
 
 impl Camera {
@@ -75,9 +178,15 @@ impl Camera {
             lens_radius: aperture / 2.0,
             time0: _time0,
             time1: _time1,
+            track: None,
         }
     }
 
+    pub fn with_track(mut self, track: Cameratrack) -> Camera {
+        self.track = Some(Arc::new(track));
+        self
+    }
+
     pub fn copy(&self) -> Camera {
         Camera {
             origin: self.origin.copy(),
@@ -90,10 +199,19 @@ impl Camera {
             lens_radius: self.lens_radius,
             time0: self.time0,
             time1: self.time1,
+            track: self.track.clone(),
         }
     }
 
     pub fn get_ray(&self, s: f64, t: f64) -> Ray {
+        let time = rand::random_double_between(self.time0, self.time1);
+        if let Some(track) = &self.track {
+            return track.get_ray(time, s, t);
+        }
+        self.ray_at(s, t, time)
+    }
+
+    fn ray_at(&self, s: f64, t: f64, time: f64) -> Ray {
         let rd = Vec3::random_in_unit_disk() * self.lens_radius;
         let offset = self.u.copy() * rd.x() + self.v.copy() * rd.y();
 
@@ -104,7 +222,7 @@ impl Camera {
                 + self.vertical.copy() * t
                 - self.origin.copy()
                 - offset.copy()),
-            rand::random_double_between(self.time0, self.time1),
+            time,
         )
     }
 }
@@ -5,10 +5,11 @@ pub use crate::texture::Value;
 pub use crate::vec3::Color;
 pub use crate::vec3::Point3;
 pub use crate::vec3::Vec3;
+use std::sync::Arc;
 
 pub struct Checkertexture {
-    pub odd: Option<Box<Texture>>,
-    pub even: Option<Box<Texture>>,
+    pub odd: Option<Arc<Texture>>,
+    pub even: Option<Arc<Texture>>,
 }
 
 impl Checkertexture {
@@ -20,27 +21,21 @@ impl Checkertexture {
     }
 
     pub fn new_from_ptr(
-        _even: &Option<Box<Texture>>,
-        _odd: &Option<Box<Texture>>,
+        _even: &Option<Arc<Texture>>,
+        _odd: &Option<Arc<Texture>>,
     ) -> Checkertexture {
         Checkertexture {
-            even: _even
-                .as_ref()
-                .map(|in_even| Box::new(in_even.copy()))
-                .map(|in_even| Box::new(in_even.copy())),
-            odd: _odd
-                .as_ref()
-                .map(|in_odd| Box::new(in_odd.copy()))
-                .map(|in_odd| Box::new(in_odd.copy())),
+            even: _even.clone(),
+            odd: _odd.clone(),
         }
     }
 
     pub fn new_from_color(c1: &Color, c2: &Color) -> Checkertexture {
         Checkertexture {
-            even: Some(Box::new(Texture::Solidcolor(Solidcolor::new_from_color(
+            even: Some(Arc::new(Texture::Solidcolor(Solidcolor::new_from_color(
                 &c1,
             )))),
-            odd: Some(Box::new(Texture::Solidcolor(Solidcolor::new_from_color(
+            odd: Some(Arc::new(Texture::Solidcolor(Solidcolor::new_from_color(
                 &c2,
             )))),
         }
@@ -48,16 +43,8 @@ impl Checkertexture {
 
     pub fn copy(&self) -> Checkertexture {
         Checkertexture {
-            even: self
-                .even
-                .as_ref()
-                .map(|in_even| Box::new(in_even.copy()))
-                .map(|in_even| Box::new(in_even.copy())),
-            odd: self
-                .odd
-                .as_ref()
-                .map(|in_odd| Box::new(in_odd.copy()))
-                .map(|in_odd| Box::new(in_odd.copy())),
+            even: self.even.clone(),
+            odd: self.odd.clone(),
         }
     }
 }
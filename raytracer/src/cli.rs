@@ -0,0 +1,292 @@
+use clap::{App, Arg};
+use raytracer::{Bvhsplit, RenderSettings};
+use std::path::Path;
+
+pub enum Outputformat {
+    Jpeg(u8),
+    Png,
+}
+
+pub struct Settings {
+    pub scene: String,
+    pub bvh: Bvhsplit,
+    pub aspect_ratio: f64,
+    pub output: String,
+    pub format: Outputformat,
+    pub render: RenderSettings,
+    pub frames: Option<(u32, u32)>, // 要渲染的动画帧, 首尾都包括
+}
+
+fn parse_number<T>(name: &str, value: &str) -> Result<T, String>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    value
+        .parse::<T>()
+        .map_err(|e| format!("--{} expects a number, got `{}` ({})", name, value, e))
+}
+
+fn parse_positive<T>(name: &str, value: &str) -> Result<T, String>
+where
+    T: std::str::FromStr + PartialOrd + Default,
+    T::Err: std::fmt::Display,
+{
+    let n: T = parse_number(name, value)?;
+    if n <= T::default() {
+        return Err(format!("--{} must be greater than 0", name));
+    }
+    Ok(n)
+}
+
+// "16:9" 或者 "1.7778"
+fn parse_aspect_ratio(value: &str) -> Result<f64, String> {
+    let ratio = match value.find(':') {
+        Some(pos) => {
+            let w: f64 = parse_number("aspect-ratio", &value[..pos])?;
+            let h: f64 = parse_number("aspect-ratio", &value[pos + 1..])?;
+            w / h
+        }
+        None => parse_number("aspect-ratio", value)?,
+    };
+    if !ratio.is_finite() || ratio <= 0.0 {
+        return Err(format!("--aspect-ratio must be positive, got `{}`", value));
+    }
+    Ok(ratio)
+}
+
+// "12..47" 或者单独一帧 "12"
+fn parse_frames(value: &str) -> Result<(u32, u32), String> {
+    let (first, last) = match value.find("..") {
+        Some(pos) => (
+            parse_number::<u32>("frames", &value[..pos])?,
+            parse_number::<u32>("frames", &value[pos + 2..])?,
+        ),
+        None => {
+            let frame = parse_number::<u32>("frames", value)?;
+            (frame, frame)
+        }
+    };
+    if first > last {
+        return Err(format!("--frames range `{}` is empty", value));
+    }
+    Ok((first, last))
+}
+
+impl Settings {
+    pub fn from_args() -> Result<Settings, String> {
+        let matches = App::new("raytracer")
+            .version(env!("CARGO_PKG_VERSION"))
+            .about("rainforest's Ray Tracer")
+            .arg(
+                Arg::with_name("scene")
+                    .help("Scene file to render")
+                    .default_value("scenes/myworld.json"),
+            )
+            .arg(
+                Arg::with_name("width")
+                    .short("w")
+                    .long("width")
+                    .takes_value(true)
+                    .default_value("800")
+                    .help("Image width in pixels"),
+            )
+            .arg(
+                Arg::with_name("height")
+                    .long("height")
+                    .takes_value(true)
+                    .conflicts_with("aspect-ratio")
+                    .help("Image height in pixels [default: width / aspect ratio]"),
+            )
+            .arg(
+                Arg::with_name("aspect-ratio")
+                    .short("a")
+                    .long("aspect-ratio")
+                    .takes_value(true)
+                    .help("Aspect ratio as W:H or a number [default: 16:9]"),
+            )
+            .arg(
+                Arg::with_name("spp")
+                    .short("s")
+                    .long("spp")
+                    .takes_value(true)
+                    .default_value("5000")
+                    .help("Samples per pixel"),
+            )
+            .arg(
+                Arg::with_name("depth")
+                    .short("d")
+                    .long("depth")
+                    .takes_value(true)
+                    .default_value("100")
+                    .help("Maximum ray bounce depth, only a safety bound"),
+            )
+            .arg(
+                Arg::with_name("rr-depth")
+                    .long("rr-depth")
+                    .takes_value(true)
+                    .default_value("5")
+                    .help("Bounces before Russian roulette may end a path"),
+            )
+            .arg(
+                Arg::with_name("threads")
+                    .short("j")
+                    .long("threads")
+                    .takes_value(true)
+                    .default_value("9")
+                    .help("Number of render threads"),
+            )
+            .arg(
+                Arg::with_name("output")
+                    .short("o")
+                    .long("output")
+                    .takes_value(true)
+                    .default_value("output/output.jpg")
+                    .help("Output image path"),
+            )
+            .arg(
+                Arg::with_name("format")
+                    .short("f")
+                    .long("format")
+                    .takes_value(true)
+                    .possible_values(&["jpeg", "png"])
+                    .help("Output format [default: from the output extension]"),
+            )
+            .arg(
+                Arg::with_name("quality")
+                    .short("q")
+                    .long("quality")
+                    .takes_value(true)
+                    .help("JPEG quality from 1 to 100 [default: 100]"),
+            )
+            .arg(
+                Arg::with_name("bvh")
+                    .long("bvh")
+                    .takes_value(true)
+                    .possible_values(&["sah", "median"])
+                    .default_value("sah")
+                    .help("BVH builder: binned surface area heuristic or median split"),
+            )
+            .arg(
+                Arg::with_name("frames")
+                    .long("frames")
+                    .takes_value(true)
+                    .help("Render frames N..M of the scene's animation to numbered files, e.g. output/spin_####.png"),
+            )
+            .arg(
+                Arg::with_name("seed")
+                    .long("seed")
+                    .takes_value(true)
+                    .help("Random seed, makes renders reproducible"),
+            )
+            .get_matches();
+
+        let image_width = parse_positive("width", matches.value_of("width").unwrap())?;
+        let (image_height, aspect_ratio) = match matches.value_of("height") {
+            Some(height) => {
+                let image_height = parse_positive("height", height)?;
+                (image_height, image_width as f64 / image_height as f64)
+            }
+            None => {
+                let aspect_ratio = match matches.value_of("aspect-ratio") {
+                    Some(ratio) => parse_aspect_ratio(ratio)?,
+                    None => 16.0 / 9.0,
+                };
+                let image_height = ((image_width as f64) / aspect_ratio) as u32;
+                if image_height == 0 {
+                    return Err(format!(
+                        "a width of {} with aspect ratio {} gives an empty image",
+                        image_width, aspect_ratio
+                    ));
+                }
+                (image_height, aspect_ratio)
+            }
+        };
+
+        let samples_per_pixel = parse_positive("spp", matches.value_of("spp").unwrap())?;
+        let max_depth: i32 = parse_positive("depth", matches.value_of("depth").unwrap())?;
+        let rr_depth: i32 = parse_positive("rr-depth", matches.value_of("rr-depth").unwrap())?;
+        let threads: usize = parse_positive("threads", matches.value_of("threads").unwrap())?;
+        if threads > image_height as usize {
+            return Err(format!(
+                "--threads ({}) cannot exceed the image height ({})",
+                threads, image_height
+            ));
+        }
+
+        let output = matches.value_of("output").unwrap().to_string();
+        let extension = Path::new(&output)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase());
+        let format_name = match (matches.value_of("format"), extension.as_deref()) {
+            (Some(format), Some("jpg")) | (Some(format), Some("jpeg")) if format != "jpeg" => {
+                return Err(format!(
+                    "--format {} does not match the output file `{}`",
+                    format, output
+                ));
+            }
+            (Some(format), Some("png")) if format != "png" => {
+                return Err(format!(
+                    "--format {} does not match the output file `{}`",
+                    format, output
+                ));
+            }
+            (Some(format), _) => format.to_string(),
+            (None, Some("jpg")) | (None, Some("jpeg")) => String::from("jpeg"),
+            (None, Some("png")) => String::from("png"),
+            (None, _) => {
+                return Err(format!(
+                    "cannot tell the image format of `{}`, use --format jpeg|png",
+                    output
+                ));
+            }
+        };
+        let format = if format_name == "png" {
+            if matches.is_present("quality") {
+                return Err(String::from("--quality only applies to JPEG output"));
+            }
+            Outputformat::Png
+        } else {
+            let quality = match matches.value_of("quality") {
+                Some(q) => parse_number::<u8>("quality", q)?,
+                None => 100,
+            };
+            if quality == 0 || quality > 100 {
+                return Err(String::from("--quality must be between 1 and 100"));
+            }
+            Outputformat::Jpeg(quality)
+        };
+
+        let frames = match matches.value_of("frames") {
+            Some(f) => Some(parse_frames(f)?),
+            None => None,
+        };
+
+        let seed = match matches.value_of("seed") {
+            Some(s) => Some(parse_number("seed", s)?),
+            None => None,
+        };
+
+        Ok(Settings {
+            scene: matches.value_of("scene").unwrap().to_string(),
+            bvh: match matches.value_of("bvh") {
+                Some("median") => Bvhsplit::Median,
+                _ => Bvhsplit::Sah,
+            },
+            aspect_ratio,
+            output,
+            format,
+            render: RenderSettings {
+                image_width,
+                image_height,
+                samples_per_pixel,
+                max_depth,
+                rr_depth,
+                threads,
+                seed,
+            },
+            frames,
+        })
+    }
+}
@@ -0,0 +1,136 @@
+#![allow(unused_variables, clippy::many_single_char_names)]
+pub use crate::aabb::Aabb;
+pub use crate::disk::Disk;
+pub use crate::hittable::Boundingbox;
+pub use crate::hittable::Hit;
+pub use crate::hittable::Hitrecord;
+pub use crate::material::Material;
+pub use crate::ray::Ray;
+pub use crate::vec3::Point3;
+pub use crate::vec3::Vec3;
+use std::sync::Arc;
+
+// 沿 +y 轴的圆锥, center 是底面圆心, 顶点在 center + (0, height, 0); capped 为 false 时底面是空的
+pub struct Cone {
+    pub center: Point3,
+    pub radius: f64,
+    pub height: f64,
+    pub capped: bool,
+    pub mp: Option<Arc<Material>>,
+}
+
+impl Cone {
+    pub fn new(
+        center: &Point3,
+        radius: f64,
+        height: f64,
+        capped: bool,
+        mat: &Option<Arc<Material>>,
+    ) -> Cone {
+        Cone {
+            center: center.copy(),
+            radius,
+            height,
+            capped,
+            mp: mat.clone(),
+        }
+    }
+
+    pub fn copy(&self) -> Cone {
+        Cone::new(
+            &self.center,
+            self.radius,
+            self.height,
+            self.capped,
+            &self.mp,
+        )
+    }
+}
+
+impl Hit for Cone {
+    fn hit(&self, r: &Ray, t_min: f64, t_max: f64, rec: &mut Hitrecord) -> bool {
+        let oc = r.origin() - self.center;
+        let dir = r.direction();
+        let mut closest = t_max;
+        let mut found = None; // (t, 外法线, u, v)
+
+        // 侧面 x^2 + z^2 = k^2 (height - y)^2, 只要 0 <= y <= height 的那一半
+        let k2 = (self.radius / self.height).powi(2);
+        let to_apex = self.height - oc.y();
+        let a = dir.x() * dir.x() + dir.z() * dir.z() - k2 * dir.y() * dir.y();
+        let half_b = oc.x() * dir.x() + oc.z() * dir.z() + k2 * to_apex * dir.y();
+        let c = oc.x() * oc.x() + oc.z() * oc.z() - k2 * to_apex * to_apex;
+        let roots = if a.abs() < 1e-12 {
+            // 光线和母线平行, 只有一个交点
+            vec![-c / (2.0 * half_b)]
+        } else {
+            let discriminant = half_b.powf(2.0) - (a * c);
+            if discriminant < 0.0 {
+                Vec::new()
+            } else {
+                let sqrtd = discriminant.sqrt();
+                let (t0, t1) = ((-half_b - sqrtd) / a, (-half_b + sqrtd) / a);
+                vec![t0.min(t1), t0.max(t1)]
+            }
+        };
+        for &t in roots.iter() {
+            let point = oc + dir * t;
+            if t >= t_min && t <= closest && point.y() >= 0.0 && point.y() <= self.height {
+                let normal = Vec3::new(point.x(), k2 * (self.height - point.y()), point.z());
+                // 顶点上法线没有定义, 取 +y
+                let normal = if normal.length_squared() > 0.0 {
+                    normal.unit_vector()
+                } else {
+                    Vec3::new(0.0, 1.0, 0.0)
+                };
+                closest = t;
+                found = Some((
+                    t,
+                    normal,
+                    Disk::get_angle_u(point.x(), point.z()),
+                    point.y() / self.height,
+                ));
+                break;
+            }
+        }
+
+        if self.capped {
+            let t = -oc.y() / dir.y();
+            if t >= t_min && t <= closest {
+                let point = oc + dir * t;
+                let distance_squared = point.x() * point.x() + point.z() * point.z();
+                if distance_squared <= self.radius * self.radius {
+                    found = Some((
+                        t,
+                        Vec3::new(0.0, -1.0, 0.0),
+                        Disk::get_angle_u(point.x(), point.z()),
+                        distance_squared.sqrt() / self.radius,
+                    ));
+                }
+            }
+        }
+
+        match found {
+            Some((t, outward_normal, u, v)) => {
+                rec.t = t;
+                rec.p = r.at(t);
+                rec.set_face_normal(&r, &outward_normal);
+                rec.u = u;
+                rec.v = v;
+                rec.mat_ptr = self.mp.clone();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl Boundingbox for Cone {
+    fn boundingbox(&self, time0: f64, time1: f64, output_box: &mut Aabb) -> bool {
+        *output_box = Aabb::new(
+            &(self.center - Vec3::new(self.radius, 0.0, self.radius)),
+            &(self.center + Vec3::new(self.radius, self.height, self.radius)),
+        );
+        true
+    }
+}
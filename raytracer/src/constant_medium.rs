@@ -1,9 +1,11 @@
 #![allow(unused_variables)]
 #![allow(unused_assignments)]
 pub use crate::aabb::Aabb;
+pub use crate::density::Density;
 pub use crate::func;
 pub use crate::hittable::Hit;
 pub use crate::hittable::Hitrecord;
+pub use crate::hittable::Intervals;
 pub use crate::hittable_list::Hittablelist;
 pub use crate::hittable_list::Object;
 use crate::isotropic::Isotropic;
@@ -15,11 +17,15 @@ pub use crate::texture::Texture;
 pub use crate::vec3::Color;
 pub use crate::vec3::Point3;
 pub use crate::vec3::Vec3;
+use std::sync::Arc;
 
+// 名字沿用书里的; 密度可以是任意的场, 边界可以不是凸的
+// albedo 是散射反照率: 每次碰撞散射出去 albedo 那部分, 其余的被吸收
 pub struct ConstantMedium {
     boundary: Option<Box<Object>>,
-    phase_function: Option<Box<Material>>,
-    neg_inv_density: f64,
+    phase_function: Option<Arc<Material>>,
+    density: Arc<Density>,
+    majorant: f64,
 }
 
 impl ConstantMedium {
@@ -27,108 +33,111 @@ impl ConstantMedium {
         ConstantMedium {
             boundary: None,
             phase_function: None,
-            neg_inv_density: 0.0,
+            density: Arc::new(Density::Constant(0.0)),
+            majorant: 0.0,
         }
     }
 
     pub fn new_from_ptr(
         b: &Option<Box<Object>>,
         d: f64,
-        a: &Option<Box<Texture>>,
+        a: &Option<Arc<Texture>>,
     ) -> ConstantMedium {
         ConstantMedium {
-            boundary: b
-                .as_ref()
-                .map(|in_b| Box::new(in_b.copy()))
-                .map(|in_b| Box::new(in_b.copy())),
-            phase_function: Some(Box::new(Material::Isotropic(Isotropic::new_from_ptr(&a)))),
-            neg_inv_density: -1.0 / d,
+            boundary: b.as_ref().map(|in_b| Box::new(in_b.copy())),
+            phase_function: Some(Arc::new(Material::Isotropic(Isotropic::new_from_ptr(&a)))),
+            density: Arc::new(Density::Constant(d)),
+            majorant: d,
+        }
+    }
+
+    pub fn new_with_density(
+        b: &Option<Box<Object>>,
+        density: Density,
+        a: &Option<Arc<Texture>>,
+    ) -> ConstantMedium {
+        ConstantMedium::new_with_phase(b, density, Material::Isotropic(Isotropic::new_from_ptr(&a)))
+    }
+
+    // phase 是碰撞点上用的相函数材质, 比如 Isotropic 或 Henyeygreenstein
+    pub fn new_with_phase(
+        b: &Option<Box<Object>>,
+        density: Density,
+        phase: Material,
+    ) -> ConstantMedium {
+        ConstantMedium {
+            boundary: b.as_ref().map(|in_b| Box::new(in_b.copy())),
+            phase_function: Some(Arc::new(phase)),
+            majorant: density.majorant(),
+            density: Arc::new(density),
         }
     }
 
     pub fn new_from_color(b: &Option<Box<Object>>, d: f64, c: &Color) -> ConstantMedium {
         ConstantMedium {
-            boundary: b
-                .as_ref()
-                .map(|in_b| Box::new(in_b.copy()))
-                .map(|in_b| Box::new(in_b.copy())),
-            phase_function: Some(Box::new(Material::Isotropic(Isotropic::new_from_color(&c)))),
-            neg_inv_density: -1.0 / d,
+            boundary: b.as_ref().map(|in_b| Box::new(in_b.copy())),
+            phase_function: Some(Arc::new(Material::Isotropic(Isotropic::new_from_color(&c)))),
+            density: Arc::new(Density::Constant(d)),
+            majorant: d,
         }
     }
 
     pub fn copy(&self) -> ConstantMedium {
         ConstantMedium {
-            boundary: self
-                .boundary
-                .as_ref()
-                .map(|in_b| Box::new(in_b.copy()))
-                .map(|in_b| Box::new(in_b.copy())),
-            phase_function: self
-                .phase_function
-                .as_ref()
-                .map(|in_a| Box::new(in_a.copy()))
-                .map(|in_a| Box::new(in_a.copy())),
-            neg_inv_density: self.neg_inv_density,
+            boundary: self.boundary.as_ref().map(|in_b| Box::new(in_b.copy())),
+            phase_function: self.phase_function.clone(),
+            density: self.density.clone(),
+            majorant: self.majorant,
         }
     }
 }
 
 impl Hit for ConstantMedium {
     fn hit(&self, r: &Ray, t_min: f64, t_max: f64, rec: &mut Hitrecord) -> bool {
-        let enable_debug = false;
-        let debugging = enable_debug && rand::random_double() < 0.00001;
-
-        let mut rec1 = Hitrecord::default_new();
-        let mut rec2 = Hitrecord::default_new();
-
-        if let Some(in_b) = &self.boundary {
-            if !in_b.hit(&r, -std::f64::INFINITY, std::f64::INFINITY, &mut rec1) {
-                return false;
-            }
-            if !in_b.hit(&r, rec1.t + 0.0001, std::f64::INFINITY, &mut rec2) {
-                return false;
-            }
-        }
-        if debugging {
-            eprint!("\nt_min={}, t_max={}", rec1.t, rec2.t);
-        }
-
-        if rec1.t < t_min {
-            rec1.t = t_min;
-        }
-        if rec2.t > t_max {
-            rec2.t = t_max;
-        }
-
-        if rec1.t >= rec2.t {
+        if self.majorant <= 0.0 {
             return false;
         }
+        let spans = match &self.boundary {
+            Some(in_b) => in_b.intervals(&r, t_min),
+            None => return false,
+        };
+
+        // delta tracking: 按上界 majorant 的指数分布走, 在每个候选点以 密度 / majorant 的概率真碰撞,
+        // 否则当作虚碰撞接着走; 密度处处等于上界时就是原来的指数采样
+        // 边界不是凸的时光线会穿过好几段, 每段分别走; 指数分布无记忆, 和整条连起来采样是一样的
+        // 阴影光线也走这里, 打到介质里的碰撞点就算被挡住, 平均下来就是透射率
+        let constant = matches!(*self.density, Density::Constant(_));
+        let step = -1.0 / (self.majorant * r.direction().length());
+        for span in spans {
+            let t0 = span.enter.t.max(t_min);
+            let t1 = span.exit.t.min(t_max);
+            if t0 >= t_max {
+                break;
+            }
+            if t0 >= t1 {
+                continue;
+            }
 
-        if rec1.t < 0.0 {
-            rec1.t = 0.0;
-        }
-
-        let ray_length = r.direction().length();
-        let distance_inside_boundary = (rec2.t - rec1.t) * ray_length;
-        let hit_distance = self.neg_inv_density * (rand::random_double().ln());
-
-        if hit_distance > distance_inside_boundary {
-            return false;
+            let mut t = t0;
+            loop {
+                t += step * rand::random_double().ln();
+                if t >= t1 {
+                    break;
+                }
+                let p = r.at(t);
+                if !constant && rand::random_double() * self.majorant >= self.density.value(&p) {
+                    continue;
+                }
+
+                rec.t = t;
+                rec.p = p;
+                rec.normal = Vec3::new(1.0, 0.0, 0.0);
+                rec.front_face = true;
+                rec.mat_ptr = self.phase_function.clone();
+                return true;
+            }
         }
-
-        rec.t = rec1.t + hit_distance / ray_length;
-        rec.p = r.at(rec.t);
-
-        rec.normal = Vec3::new(1.0, 0.0, 0.0);
-        rec.front_face = true;
-        rec.mat_ptr = self
-            .phase_function
-            .as_ref()
-            .map(|in_p| Box::new(in_p.copy()))
-            .map(|in_p| Box::new(in_p.copy()));
-
-        true
+        false
     }
 }
 
@@ -0,0 +1,154 @@
+#![allow(unused_variables)]
+pub use crate::aabb::Aabb;
+pub use crate::hittable::Boundingbox;
+pub use crate::hittable::Hit;
+pub use crate::hittable::Hitrecord;
+pub use crate::hittable::Intervals;
+pub use crate::hittable::Span;
+pub use crate::hittable_list::Object;
+pub use crate::ray::Ray;
+pub use crate::vec3::Point3;
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Csgop {
+    Union,
+    Intersection,
+    Difference, // left 减去 right
+}
+
+impl Csgop {
+    fn inside(self, in_left: bool, in_right: bool) -> bool {
+        match self {
+            Csgop::Union => in_left || in_right,
+            Csgop::Intersection => in_left && in_right,
+            Csgop::Difference => in_left && !in_right,
+        }
+    }
+}
+
+// 两个封闭物体的布尔运算; 求交时先分别求出光线在两边里面的区间, 再按 op 合并
+pub struct Csg {
+    pub op: Csgop,
+    left: Arc<Object>,
+    right: Arc<Object>,
+}
+
+impl Csg {
+    pub fn new(op: Csgop, left: Object, right: Object) -> Csg {
+        Csg {
+            op,
+            left: Arc::new(left),
+            right: Arc::new(right),
+        }
+    }
+
+    pub fn copy(&self) -> Csg {
+        Csg {
+            op: self.op,
+            left: self.left.clone(),
+            right: self.right.clone(),
+        }
+    }
+}
+
+impl Intervals for Csg {
+    fn intervals(&self, r: &Ray, t_min: f64) -> Vec<Span> {
+        // 两边的边界按 t 排好, 扫一遍, 记录当前在不在左右两边里面
+        let mut events = Vec::new(); // (交点, 是不是左边的, 是不是进入)
+        let mut push = |spans: Vec<Span>, is_left: bool| {
+            for span in spans {
+                events.push((span.enter, is_left, true));
+                events.push((span.exit, is_left, false));
+            }
+        };
+        push(self.left.intervals(r, t_min), true);
+        push(self.right.intervals(r, t_min), false);
+        events.sort_by(|a, b| a.0.t.partial_cmp(&b.0.t).unwrap_or(Ordering::Equal));
+
+        let mut res = Vec::new();
+        let mut enter: Option<Hitrecord> = None;
+        let mut in_left = false;
+        let mut in_right = false;
+        for (mut rec, is_left, is_enter) in events {
+            if is_left {
+                in_left = is_enter;
+            } else {
+                in_right = is_enter;
+            }
+            let inside = self.op.inside(in_left, in_right);
+            // 交点的法线总是迎着光线, 只要改 front_face; 差集里 right 的出口就成了结果的入口
+            if inside && enter.is_none() {
+                rec.front_face = true;
+                enter = Some(rec);
+            } else if !inside {
+                if let Some(enter) = enter.take() {
+                    rec.front_face = false;
+                    res.push(Span { enter, exit: rec });
+                }
+            }
+        }
+        res
+    }
+}
+
+impl Hit for Csg {
+    fn hit(&self, r: &Ray, t_min: f64, t_max: f64, rec: &mut Hitrecord) -> bool {
+        for span in self.intervals(r, t_min) {
+            let boundary = if span.enter.t >= t_min {
+                span.enter
+            } else {
+                span.exit
+            };
+            if boundary.t > t_max {
+                return false;
+            }
+            if boundary.t >= t_min {
+                *rec = boundary;
+                return true;
+            }
+        }
+        false
+    }
+}
+
+impl Boundingbox for Csg {
+    fn boundingbox(&self, time0: f64, time1: f64, output_box: &mut Aabb) -> bool {
+        let mut left_box = Aabb::default_new();
+        let mut right_box = Aabb::default_new();
+        let has_left = self.left.boundingbox(time0, time1, &mut left_box);
+        let has_right = self.right.boundingbox(time0, time1, &mut right_box);
+        match self.op {
+            Csgop::Union => {
+                if !has_left || !has_right {
+                    return false;
+                }
+                *output_box = Aabb::surrounding_box(&left_box, &right_box);
+            }
+            Csgop::Intersection => {
+                // 只在两个包围盒重叠的部分里
+                *output_box = match (has_left, has_right) {
+                    (true, true) => {
+                        let (a, b) = (left_box.min(), right_box.min());
+                        let (c, d) = (left_box.max(), right_box.max());
+                        Aabb::new(
+                            &Point3::new(a.x().max(b.x()), a.y().max(b.y()), a.z().max(b.z())),
+                            &Point3::new(c.x().min(d.x()), c.y().min(d.y()), c.z().min(d.z())),
+                        )
+                    }
+                    (true, false) => left_box,
+                    (false, true) => right_box,
+                    (false, false) => return false,
+                };
+            }
+            Csgop::Difference => {
+                if !has_left {
+                    return false;
+                }
+                *output_box = left_box;
+            }
+        }
+        true
+    }
+}
@@ -0,0 +1,122 @@
+#![allow(unused_variables, clippy::many_single_char_names)]
+pub use crate::aabb::Aabb;
+pub use crate::disk::Disk;
+pub use crate::hittable::Boundingbox;
+pub use crate::hittable::Hit;
+pub use crate::hittable::Hitrecord;
+pub use crate::material::Material;
+pub use crate::ray::Ray;
+pub use crate::vec3::Point3;
+pub use crate::vec3::Vec3;
+use std::sync::Arc;
+
+// 沿 +y 轴的圆柱, center 是底面圆心; capped 为 false 时两头是空的
+pub struct Cylinder {
+    pub center: Point3,
+    pub radius: f64,
+    pub height: f64,
+    pub capped: bool,
+    pub mp: Option<Arc<Material>>,
+}
+
+impl Cylinder {
+    pub fn new(
+        center: &Point3,
+        radius: f64,
+        height: f64,
+        capped: bool,
+        mat: &Option<Arc<Material>>,
+    ) -> Cylinder {
+        Cylinder {
+            center: center.copy(),
+            radius,
+            height,
+            capped,
+            mp: mat.clone(),
+        }
+    }
+
+    pub fn copy(&self) -> Cylinder {
+        Cylinder::new(
+            &self.center,
+            self.radius,
+            self.height,
+            self.capped,
+            &self.mp,
+        )
+    }
+}
+
+impl Hit for Cylinder {
+    fn hit(&self, r: &Ray, t_min: f64, t_max: f64, rec: &mut Hitrecord) -> bool {
+        let oc = r.origin() - self.center;
+        let dir = r.direction();
+        let mut closest = t_max;
+        let mut found = None; // (t, 外法线, u, v)
+
+        // 侧面 x^2 + z^2 = radius^2, 只要 0 <= y <= height 的部分
+        let a = dir.x() * dir.x() + dir.z() * dir.z();
+        let half_b = oc.x() * dir.x() + oc.z() * dir.z();
+        let c = oc.x() * oc.x() + oc.z() * oc.z() - self.radius * self.radius;
+        let discriminant = half_b.powf(2.0) - (a * c);
+        if a > 0.0 && discriminant >= 0.0 {
+            let sqrtd = discriminant.sqrt();
+            for &t in [(-half_b - sqrtd) / a, (-half_b + sqrtd) / a].iter() {
+                let point = oc + dir * t;
+                if t >= t_min && t <= closest && point.y() >= 0.0 && point.y() <= self.height {
+                    closest = t;
+                    found = Some((
+                        t,
+                        Vec3::new(point.x() / self.radius, 0.0, point.z() / self.radius),
+                        Disk::get_angle_u(point.x(), point.z()),
+                        point.y() / self.height,
+                    ));
+                    break;
+                }
+            }
+        }
+
+        if self.capped {
+            for &(y, ny) in [(0.0, -1.0), (self.height, 1.0)].iter() {
+                let t = (y - oc.y()) / dir.y();
+                if !(t >= t_min && t <= closest) {
+                    continue;
+                }
+                let point = oc + dir * t;
+                let distance_squared = point.x() * point.x() + point.z() * point.z();
+                if distance_squared <= self.radius * self.radius {
+                    closest = t;
+                    found = Some((
+                        t,
+                        Vec3::new(0.0, ny, 0.0),
+                        Disk::get_angle_u(point.x(), point.z()),
+                        distance_squared.sqrt() / self.radius,
+                    ));
+                }
+            }
+        }
+
+        match found {
+            Some((t, outward_normal, u, v)) => {
+                rec.t = t;
+                rec.p = r.at(t);
+                rec.set_face_normal(&r, &outward_normal);
+                rec.u = u;
+                rec.v = v;
+                rec.mat_ptr = self.mp.clone();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl Boundingbox for Cylinder {
+    fn boundingbox(&self, time0: f64, time1: f64, output_box: &mut Aabb) -> bool {
+        *output_box = Aabb::new(
+            &(self.center - Vec3::new(self.radius, 0.0, self.radius)),
+            &(self.center + Vec3::new(self.radius, self.height, self.radius)),
+        );
+        true
+    }
+}
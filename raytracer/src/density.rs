@@ -0,0 +1,107 @@
+#![allow(unused_variables)]
+pub use crate::perlin::Perlin;
+pub use crate::texture::Texture;
+pub use crate::texture::Value;
+pub use crate::vec3::Point3;
+pub use crate::vec3::Vec3;
+use std::sync::Arc;
+
+// 介质里每一点的消光系数 (单位长度上碰撞的概率密度), 都用世界坐标
+pub enum Density {
+    Constant(f64),
+    // Perlin turb 截到 [0, 1], 低于 threshold 的地方是空的, 剩下的拉伸回 [0, 1] 再乘 density
+    Noise {
+        noise: Arc<Perlin>,
+        scale: f64,
+        depth: i32,
+        threshold: f64,
+        density: f64,
+    },
+    // min..max 之间 nx * ny * nz 个体素, 值在体素中心, 中间三线性插值, 格子外面是 0
+    // values 按 x 最快、z 最慢排
+    Grid {
+        min: Point3,
+        max: Point3,
+        size: [usize; 3],
+        values: Arc<Vec<f64>>,
+    },
+    // 纹理颜色的亮度截到 [0, 1] 乘 density, 适合 noise / checker 这类立体纹理
+    Texture {
+        texture: Arc<Texture>,
+        density: f64,
+    },
+}
+
+impl Density {
+    pub fn value(&self, p: &Point3) -> f64 {
+        match self {
+            Density::Constant(d) => *d,
+            Density::Noise {
+                noise,
+                scale,
+                depth,
+                threshold,
+                density,
+            } => {
+                let turb = noise.turb(&(*p * *scale), *depth).min(1.0);
+                if turb <= *threshold {
+                    return 0.0;
+                }
+                density * (turb - threshold) / (1.0 - threshold)
+            }
+            Density::Grid {
+                min,
+                max,
+                size,
+                values,
+            } => {
+                let mut index = [0usize; 3];
+                let mut weight = [0.0; 3];
+                for a in 0..3 {
+                    if p[a] < min[a] || p[a] > max[a] {
+                        return 0.0;
+                    }
+                    // 换到以体素中心为整数点的坐标, 边上一圈贴着最外面的体素
+                    let n = size[a];
+                    let x = (p[a] - min[a]) / (max[a] - min[a]) * n as f64 - 0.5;
+                    let x = x.max(0.0).min((n - 1) as f64);
+                    let i = (x.floor() as usize).min(n.saturating_sub(2));
+                    index[a] = i;
+                    weight[a] = if n > 1 { x - i as f64 } else { 0.0 };
+                }
+                let at = |i: usize, j: usize, k: usize| -> f64 {
+                    let i = (index[0] + i).min(size[0] - 1);
+                    let j = (index[1] + j).min(size[1] - 1);
+                    let k = (index[2] + k).min(size[2] - 1);
+                    values[(k * size[1] + j) * size[0] + i]
+                };
+                let mut accum = 0.0;
+                for k in 0..2 {
+                    for j in 0..2 {
+                        for i in 0..2 {
+                            let w = (if i == 1 { weight[0] } else { 1.0 - weight[0] })
+                                * (if j == 1 { weight[1] } else { 1.0 - weight[1] })
+                                * (if k == 1 { weight[2] } else { 1.0 - weight[2] });
+                            accum += w * at(i, j, k);
+                        }
+                    }
+                }
+                accum
+            }
+            Density::Texture { texture, density } => {
+                let c = texture.value(0.0, 0.0, p);
+                let luminance = 0.2126 * c.x() + 0.7152 * c.y() + 0.0722 * c.z();
+                density * luminance.clamp(0.0, 1.0)
+            }
+        }
+    }
+
+    // 整个场的上界, delta tracking 按它采样候选碰撞点
+    pub fn majorant(&self) -> f64 {
+        match self {
+            Density::Constant(d) => *d,
+            Density::Noise { density, .. } | Density::Texture { density, .. } => *density,
+            Density::Grid { values, .. } => values.iter().cloned().fold(0.0, f64::max),
+        }
+    }
+}
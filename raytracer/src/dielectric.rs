@@ -7,6 +7,7 @@ pub use crate::hittable_list::Object;
 pub use crate::lambertian::Lambertian;
 pub use crate::material::Material;
 pub use crate::material::Scatter;
+pub use crate::material::Scatterrecord;
 pub use crate::metal::Metal;
 pub use crate::movingsphere::Movingsphere;
 pub use crate::ray::Ray;
@@ -20,14 +21,10 @@ pub struct Dielectric {
 }
 
 impl Scatter for Dielectric {
-    fn scatter(
-        &self,
-        r_in: &Ray,
-        rec: &Hitrecord,
-        attenuation: &mut Color,
-        scattered: &mut Ray,
-    ) -> bool {
-        *attenuation = Color::new(1.0, 1.0, 1.0);
+    fn scatter(&self, r_in: &Ray, rec: &Hitrecord, srec: &mut Scatterrecord) -> bool {
+        srec.attenuation = Color::new(1.0, 1.0, 1.0);
+        srec.pdf = 0.0;
+        srec.is_specular = true;
         let refraction_ratio = if rec.front_face {
             1.0 / self.ir
         } else {
@@ -51,9 +48,17 @@ impl Scatter for Dielectric {
             direction = Vec3::refract(&unit_direction.copy(), &rec.normal.copy(), refraction_ratio);
         }
 
-        *scattered = Ray::new(&rec.p.copy(), &direction, r_in.time());
+        srec.scattered = Ray::new(&rec.p.copy(), &direction, r_in.time());
         true
     }
+
+    fn scattering_pdf(&self, _r_in: &Ray, _rec: &Hitrecord, _scattered: &Ray) -> f64 {
+        0.0
+    }
+
+    fn eval(&self, _r_in: &Ray, _rec: &Hitrecord, _scattered: &Ray) -> Color {
+        Color::new(0.0, 0.0, 0.0)
+    }
 }
 
 impl Dielectric {
@@ -3,6 +3,7 @@ pub use crate::hittable::Hitrecord;
 use crate::material::Emitted;
 pub use crate::material::Material;
 pub use crate::material::Scatter;
+pub use crate::material::Scatterrecord;
 pub use crate::ray::Ray;
 pub use crate::solidcolor::Solidcolor;
 pub use crate::texture::Texture;
@@ -10,9 +11,10 @@ pub use crate::texture::Value;
 pub use crate::vec3::Color;
 pub use crate::vec3::Point3;
 pub use crate::vec3::Vec3;
+use std::sync::Arc;
 
 pub struct Diffuselight {
-    pub emit: Option<Box<Texture>>,
+    pub emit: Option<Arc<Texture>>,
 }
 
 impl Diffuselight {
@@ -20,18 +22,13 @@ impl Diffuselight {
         Diffuselight { emit: None }
     }
 
-    pub fn new(a: &Option<Box<Texture>>) -> Diffuselight {
-        Diffuselight {
-            emit: a
-                .as_ref()
-                .map(|in_a| Box::new(in_a.copy()))
-                .map(|in_a| Box::new(in_a.copy())),
-        }
+    pub fn new(a: &Option<Arc<Texture>>) -> Diffuselight {
+        Diffuselight { emit: a.clone() }
     }
 
     pub fn new_from_color(c: &Color) -> Diffuselight {
         Diffuselight {
-            emit: Some(Box::new(Texture::Solidcolor(Solidcolor::new_from_color(
+            emit: Some(Arc::new(Texture::Solidcolor(Solidcolor::new_from_color(
                 &c.copy(),
             )))),
         }
@@ -39,25 +36,23 @@ impl Diffuselight {
 
     pub fn copy(&self) -> Diffuselight {
         Diffuselight {
-            emit: self
-                .emit
-                .as_ref()
-                .map(|in_a| Box::new(in_a.copy()))
-                .map(|in_a| Box::new(in_a.copy())),
+            emit: self.emit.clone(),
         }
     }
 }
 
 impl Scatter for Diffuselight {
-    fn scatter(
-        &self,
-        r_in: &Ray,
-        rec: &Hitrecord,
-        attenuation: &mut Color,
-        scattered: &mut Ray,
-    ) -> bool {
+    fn scatter(&self, r_in: &Ray, rec: &Hitrecord, srec: &mut Scatterrecord) -> bool {
         false
     }
+
+    fn scattering_pdf(&self, r_in: &Ray, rec: &Hitrecord, scattered: &Ray) -> f64 {
+        0.0
+    }
+
+    fn eval(&self, r_in: &Ray, rec: &Hitrecord, scattered: &Ray) -> Color {
+        Color::new(0.0, 0.0, 0.0)
+    }
 }
 
 impl Emitted for Diffuselight {
@@ -0,0 +1,92 @@
+#![allow(unused_variables)]
+pub use crate::aabb::Aabb;
+pub use crate::hittable::Boundingbox;
+pub use crate::hittable::Hit;
+pub use crate::hittable::Hitrecord;
+pub use crate::hittable::Lightsample;
+pub use crate::material::Material;
+pub use crate::rand;
+pub use crate::ray::Ray;
+pub use crate::vec3::Point3;
+pub use crate::vec3::Vec3;
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+// 圆心在 center、法线朝 +y 的圆盘; 别的朝向用 transform 转
+pub struct Disk {
+    pub center: Point3,
+    pub radius: f64,
+    pub mp: Option<Arc<Material>>,
+}
+
+impl Disk {
+    pub fn new(center: &Point3, radius: f64, mat: &Option<Arc<Material>>) -> Disk {
+        Disk {
+            center: center.copy(),
+            radius,
+            mp: mat.clone(),
+        }
+    }
+
+    pub fn copy(&self) -> Disk {
+        Disk::new(&self.center, self.radius, &self.mp)
+    }
+
+    // 绕 y 轴的角度, 和球的 u 一样从 -x 方向起算; 圆柱、圆锥、圆环共用
+    pub fn get_angle_u(x: f64, z: f64) -> f64 {
+        ((-z).atan2(x) + PI) / (2.0 * PI)
+    }
+}
+
+impl Hit for Disk {
+    fn hit(&self, r: &Ray, t_min: f64, t_max: f64, rec: &mut Hitrecord) -> bool {
+        let t = (self.center.y() - r.origin().y()) / r.direction().y();
+        if !(t >= t_min && t <= t_max) {
+            return false;
+        }
+        let x = r.origin().x() + t * r.direction().x() - self.center.x();
+        let z = r.origin().z() + t * r.direction().z() - self.center.z();
+        let distance_squared = x * x + z * z;
+        if distance_squared > self.radius * self.radius {
+            return false;
+        }
+        rec.u = Disk::get_angle_u(x, z);
+        rec.v = distance_squared.sqrt() / self.radius;
+        rec.t = t;
+        rec.p = r.at(t);
+        rec.set_face_normal(&r, &Vec3::new(0.0, 1.0, 0.0));
+        rec.mat_ptr = self.mp.clone();
+        true
+    }
+}
+
+impl Boundingbox for Disk {
+    fn boundingbox(&self, time0: f64, time1: f64, output_box: &mut Aabb) -> bool {
+        let d = Vec3::new(self.radius, 0.0001, self.radius);
+        *output_box = Aabb::new(&(self.center - d), &(self.center + d));
+        true
+    }
+}
+
+impl Lightsample for Disk {
+    fn pdf_value(&self, o: &Point3, v: &Vec3) -> f64 {
+        let mut rec = Hitrecord::default_new();
+        if !self.hit(&Ray::new(&o, &v, 0.0), 0.001, f64::INFINITY, &mut rec) {
+            return 0.0;
+        }
+
+        let area = PI * self.radius * self.radius;
+        let distance_squared = rec.t * rec.t * v.length_squared();
+        let cosine = (v.y() / v.length()).abs();
+
+        distance_squared / (cosine * area)
+    }
+
+    fn random(&self, o: &Point3) -> Vec3 {
+        // 半径取 sqrt 才是按面积均匀
+        let r = self.radius * rand::random_double().sqrt();
+        let phi = 2.0 * PI * rand::random_double();
+        let random_point = self.center + Vec3::new(r * phi.cos(), 0.0, r * phi.sin());
+        random_point - o.copy()
+    }
+}
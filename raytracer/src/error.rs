@@ -0,0 +1,51 @@
+use std::fmt;
+
+// 加载素材和输出图片时的错误, 都带上出错的文件路径
+#[derive(Debug)]
+pub enum Error {
+    Io {
+        path: String,
+        source: std::io::Error,
+    },
+    Image {
+        path: String,
+        source: image::ImageError,
+    },
+    Obj {
+        path: String,
+        source: tobj::LoadError,
+    },
+    Gltf {
+        path: String,
+        message: String,
+    },
+    Ply {
+        path: String,
+        message: String,
+    },
+    Scene(String), // 场景文件内容有问题
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io { path, source } => write!(f, "`{}`: {}", path, source),
+            Error::Image { path, source } => write!(f, "image `{}`: {}", path, source),
+            Error::Obj { path, source } => write!(f, "obj file `{}`: {}", path, source),
+            Error::Gltf { path, message } => write!(f, "gltf file `{}`: {}", path, message),
+            Error::Ply { path, message } => write!(f, "ply file `{}`: {}", path, message),
+            Error::Scene(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            Error::Image { source, .. } => Some(source),
+            Error::Obj { source, .. } => Some(source),
+            Error::Gltf { .. } | Error::Ply { .. } | Error::Scene(_) => None,
+        }
+    }
+}
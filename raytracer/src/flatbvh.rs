@@ -0,0 +1,267 @@
+pub use crate::aabb::Aabb;
+pub use crate::bvhnode::Bvhsplit;
+pub use crate::bvhnode::Bvhstats;
+pub use crate::bvhnode::Sahsplit;
+pub use crate::hittable::Boundingbox;
+pub use crate::hittable::Hit;
+pub use crate::hittable::Hitrecord;
+pub use crate::hittable_list::Object;
+pub use crate::rand;
+pub use crate::ray::Ray;
+pub use crate::vec3::Vec3;
+pub use std::time::Duration;
+
+const MAX_LEAF_SIZE: usize = 4;
+const TRAVERSAL_COST: f64 = 0.125; // 相对于一次物体求交
+
+pub struct Flatnode {
+    pub boxx: Aabb,
+    pub offset: usize, // 叶子: 第一个物体的下标; 内部节点: 右孩子的下标, 左孩子紧跟在自己后面
+    pub count: usize,  // 叶子里的物体个数, 内部节点为 0
+    pub axis: usize,   // 内部节点的划分轴, 遍历时先走离射线起点近的孩子
+}
+
+// 扁平的 BVH: 节点按深度优先顺序存在一个数组里, 叶子是 objects 里的一段连续区间
+pub struct Flatbvh {
+    pub nodes: Vec<Flatnode>,
+    pub bounded: usize, // objects[..bounded] 在树里, 后面的没有包围盒, 逐个求交
+}
+
+impl Flatnode {
+    pub fn copy(&self) -> Flatnode {
+        Flatnode {
+            boxx: self.boxx.copy(),
+            offset: self.offset,
+            count: self.count,
+            axis: self.axis,
+        }
+    }
+}
+
+impl Flatbvh {
+    // 建树的同时把 objects 重排成叶子的顺序
+    pub fn build(objects: &mut Vec<Object>, time0: f64, time1: f64, split: Bvhsplit) -> Flatbvh {
+        let mut indices = Vec::new();
+        let mut boxes = Vec::new();
+        let mut unbounded = Vec::new();
+        for (i, object) in objects.iter().enumerate() {
+            let mut output_box = Aabb::default_new();
+            if object.boundingbox(time0, time1, &mut output_box) {
+                indices.push(i);
+                boxes.push(output_box);
+            } else {
+                unbounded.push(i);
+            }
+        }
+
+        let mut nodes = Vec::new();
+        let mut order = Vec::new();
+        if !indices.is_empty() {
+            Flatbvh::build_node(&mut nodes, &mut order, indices, boxes, split);
+        }
+        let bounded = order.len();
+        order.append(&mut unbounded);
+
+        let mut slots: Vec<Option<Object>> = objects.drain(..).map(Some).collect();
+        for i in order {
+            objects.push(slots[i].take().unwrap());
+        }
+
+        Flatbvh { nodes, bounded }
+    }
+
+    fn build_node(
+        nodes: &mut Vec<Flatnode>,
+        order: &mut Vec<usize>,
+        mut indices: Vec<usize>,
+        mut boxes: Vec<Aabb>,
+        split: Bvhsplit,
+    ) -> usize {
+        let mut boxx = boxes[0].copy();
+        for b in &boxes[1..] {
+            boxx = Aabb::surrounding_box(&boxx, &b);
+        }
+        let index = nodes.len();
+        let object_span = indices.len();
+
+        let best = if object_span > 1 && split == Bvhsplit::Sah {
+            Sahsplit::find(&boxes)
+        } else {
+            None
+        };
+        // SAH: 物体够少并且划分不划算时做成叶子; 中位数划分和 Bvhnode 一样最多两个物体一片叶子
+        let area = boxx.area();
+        let make_leaf = match split {
+            Bvhsplit::Median => object_span <= 2,
+            Bvhsplit::Sah => {
+                object_span == 1
+                    || (object_span <= MAX_LEAF_SIZE
+                        && match &best {
+                            Some(best) if area > 0.0 => {
+                                object_span as f64 <= TRAVERSAL_COST + best.cost / area
+                            }
+                            _ => true,
+                        })
+            }
+        };
+        if make_leaf {
+            nodes.push(Flatnode {
+                boxx,
+                offset: order.len(),
+                count: object_span,
+                axis: 0,
+            });
+            order.append(&mut indices);
+            return index;
+        }
+
+        nodes.push(Flatnode {
+            boxx,
+            offset: 0,
+            count: 0,
+            axis: 0,
+        });
+        let mut left_indices = Vec::new();
+        let mut left_boxes = Vec::new();
+        let mut right_indices = Vec::new();
+        let mut right_boxes = Vec::new();
+        let axis = match best {
+            Some(best) => {
+                for (i, b) in indices.into_iter().zip(boxes) {
+                    if best.goes_left(&b) {
+                        left_indices.push(i);
+                        left_boxes.push(b);
+                    } else {
+                        right_indices.push(i);
+                        right_boxes.push(b);
+                    }
+                }
+                best.axis
+            }
+            None if split == Bvhsplit::Median => {
+                // 随机选一个轴, 按包围盒下界排序后对半分
+                let axis = rand::random_int_between(0, 2) as usize;
+                let mut pairs: Vec<(usize, Aabb)> = indices.into_iter().zip(boxes).collect();
+                pairs.sort_by(|a, b| a.1.min()[axis].partial_cmp(&b.1.min()[axis]).unwrap());
+                let right = pairs.split_off(object_span / 2);
+                for (i, b) in pairs {
+                    left_indices.push(i);
+                    left_boxes.push(b);
+                }
+                for (i, b) in right {
+                    right_indices.push(i);
+                    right_boxes.push(b);
+                }
+                axis
+            }
+            None => {
+                // 所有中心点重合, 只能对半分
+                right_indices = indices.split_off(object_span / 2);
+                right_boxes = boxes.split_off(object_span / 2);
+                left_indices = indices;
+                left_boxes = boxes;
+                0
+            }
+        };
+
+        Flatbvh::build_node(nodes, order, left_indices, left_boxes, split);
+        let right = Flatbvh::build_node(nodes, order, right_indices, right_boxes, split);
+        nodes[index].offset = right;
+        nodes[index].axis = axis;
+        index
+    }
+
+    pub fn copy(&self) -> Flatbvh {
+        Flatbvh {
+            nodes: self.nodes.iter().map(|node| node.copy()).collect(),
+            bounded: self.bounded,
+        }
+    }
+
+    pub fn hit(
+        &self,
+        objects: &[Object],
+        r: &Ray,
+        t_min: f64,
+        t_max: f64,
+        rec: &mut Hitrecord,
+    ) -> bool {
+        let mut hit_anything = false;
+        let mut closest_so_far = t_max;
+
+        if !self.nodes.is_empty() {
+            let origin = r.origin();
+            let direction = r.direction();
+            let inv_dir = Vec3::new(
+                1.0 / direction.x(),
+                1.0 / direction.y(),
+                1.0 / direction.z(),
+            );
+            let dir_is_neg = [inv_dir.x() < 0.0, inv_dir.y() < 0.0, inv_dir.z() < 0.0];
+
+            let mut stack: Vec<usize> = Vec::with_capacity(64);
+            let mut node_index = 0;
+            loop {
+                let node = &self.nodes[node_index];
+                if node.boxx.hit_inv(&origin, &inv_dir, t_min, closest_so_far) {
+                    if node.count > 0 {
+                        for object in &objects[node.offset..node.offset + node.count] {
+                            if object.hit(&r, t_min, closest_so_far, rec) {
+                                hit_anything = true;
+                                closest_so_far = rec.t;
+                            }
+                        }
+                    } else if dir_is_neg[node.axis] {
+                        stack.push(node_index + 1);
+                        node_index = node.offset;
+                        continue;
+                    } else {
+                        stack.push(node.offset);
+                        node_index += 1;
+                        continue;
+                    }
+                }
+                match stack.pop() {
+                    Some(next) => node_index = next,
+                    None => break,
+                }
+            }
+        }
+
+        for object in &objects[self.bounded..] {
+            if object.hit(&r, t_min, closest_so_far, rec) {
+                hit_anything = true;
+                closest_so_far = rec.t;
+            }
+        }
+
+        hit_anything
+    }
+
+    pub fn stats(&self) -> Bvhstats {
+        let mut stats = Bvhstats {
+            node_count: 0,
+            leaf_count: 0,
+            depth: 0,
+            build_time: Duration::from_secs(0),
+        };
+        if self.nodes.is_empty() {
+            return stats;
+        }
+        let mut stack = vec![(0, 1)];
+        while let Some((node_index, depth)) = stack.pop() {
+            let node = &self.nodes[node_index];
+            if depth > stats.depth {
+                stats.depth = depth;
+            }
+            stats.node_count += 1;
+            if node.count > 0 {
+                stats.leaf_count += 1;
+            } else {
+                stack.push((node_index + 1, depth + 1));
+                stack.push((node.offset, depth + 1));
+            }
+        }
+        stats
+    }
+}
@@ -0,0 +1,758 @@
+pub use crate::bvhnode::Bvhnode;
+pub use crate::bvhnode::Bvhsplit;
+pub use crate::dielectric::Dielectric;
+pub use crate::diffuse_light::Diffuselight;
+pub use crate::error::Error;
+pub use crate::hittable_list::Hittablelist;
+pub use crate::hittable_list::Object;
+pub use crate::lambertian::Lambertian;
+pub use crate::material::Material;
+pub use crate::matrix::Matrix4;
+pub use crate::metal::Metal;
+pub use crate::obj::Obj;
+pub use crate::obj_texture::Objtexture;
+pub use crate::texture::Texture;
+pub use crate::triangle::Triangle;
+pub use crate::vec3::Color;
+pub use crate::vec3::Point3;
+pub use crate::vec3::Vec3;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+// glTF 里的第一个透视相机, 坐标是 glTF 文件自己的世界坐标
+pub struct Gltfcamera {
+    pub lookfrom: Point3,
+    pub lookat: Point3,
+    pub vup: Vec3,
+    pub vfov: f64, // 角度
+}
+
+// 一个 .gltf / .glb 文件: 各节点上的网格按节点变换摆好, 合成一个三角形网格
+pub struct Gltf {
+    pub mesh: Obj,
+    pub camera: Option<Gltfcamera>,
+    pub triangle_count: usize,
+}
+
+// 下面只声明用得到的字段, 其余字段 (extras、动画、蒙皮等) 直接忽略
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Document {
+    scene: Option<usize>,
+    #[serde(default)]
+    scenes: Vec<Scenedesc>,
+    #[serde(default)]
+    nodes: Vec<Node>,
+    #[serde(default)]
+    meshes: Vec<Mesh>,
+    #[serde(default)]
+    accessors: Vec<Accessor>,
+    #[serde(default)]
+    buffer_views: Vec<Bufferview>,
+    #[serde(default)]
+    buffers: Vec<Buffer>,
+    #[serde(default)]
+    materials: Vec<Gltfmaterial>,
+    #[serde(default)]
+    textures: Vec<Texturedesc>,
+    #[serde(default)]
+    images: Vec<Imagedesc>,
+    #[serde(default)]
+    cameras: Vec<Cameradesc>,
+}
+
+#[derive(Deserialize)]
+struct Scenedesc {
+    #[serde(default)]
+    nodes: Vec<usize>,
+}
+
+#[derive(Deserialize)]
+struct Node {
+    #[serde(default)]
+    children: Vec<usize>,
+    mesh: Option<usize>,
+    camera: Option<usize>,
+    matrix: Option<[f64; 16]>, // 按列存
+    #[serde(default)]
+    translation: [f64; 3],
+    #[serde(default = "default_rotation")]
+    rotation: [f64; 4], // 四元数 (x, y, z, w)
+    #[serde(default = "default_scale")]
+    scale: [f64; 3],
+}
+
+#[derive(Deserialize)]
+struct Mesh {
+    primitives: Vec<Primitive>,
+}
+
+#[derive(Deserialize)]
+struct Primitive {
+    attributes: HashMap<String, usize>,
+    indices: Option<usize>,
+    material: Option<usize>,
+    #[serde(default = "default_mode")]
+    mode: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Accessor {
+    buffer_view: Option<usize>,
+    #[serde(default)]
+    byte_offset: usize,
+    component_type: u32,
+    #[serde(default)]
+    normalized: bool,
+    count: usize,
+    #[serde(rename = "type")]
+    kind: String,
+    sparse: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Bufferview {
+    buffer: usize,
+    #[serde(default)]
+    byte_offset: usize,
+    byte_length: usize,
+    byte_stride: Option<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Buffer {
+    uri: Option<String>,
+    byte_length: usize,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Gltfmaterial {
+    #[serde(default)]
+    pbr_metallic_roughness: Pbr,
+    #[serde(default)]
+    emissive_factor: [f64; 3],
+    #[serde(default)]
+    extensions: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Pbr {
+    #[serde(default = "default_base_color")]
+    base_color_factor: [f64; 4],
+    base_color_texture: Option<Textureinfo>,
+    #[serde(default = "default_factor")]
+    metallic_factor: f64,
+    #[serde(default = "default_factor")]
+    roughness_factor: f64,
+}
+
+impl Default for Pbr {
+    fn default() -> Pbr {
+        Pbr {
+            base_color_factor: default_base_color(),
+            base_color_texture: None,
+            metallic_factor: default_factor(),
+            roughness_factor: default_factor(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Textureinfo {
+    index: usize,
+}
+
+#[derive(Deserialize)]
+struct Texturedesc {
+    source: Option<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Imagedesc {
+    uri: Option<String>,
+    buffer_view: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct Cameradesc {
+    perspective: Option<Perspective>,
+}
+
+#[derive(Deserialize)]
+struct Perspective {
+    yfov: f64, // 弧度
+}
+
+fn default_rotation() -> [f64; 4] {
+    [0.0, 0.0, 0.0, 1.0]
+}
+
+fn default_scale() -> [f64; 3] {
+    [1.0, 1.0, 1.0]
+}
+
+fn default_mode() -> u32 {
+    4
+}
+
+fn default_base_color() -> [f64; 4] {
+    [1.0, 1.0, 1.0, 1.0]
+}
+
+fn default_factor() -> f64 {
+    1.0
+}
+
+fn vec3(a: &[f64; 3]) -> Vec3 {
+    Vec3::new(a[0], a[1], a[2])
+}
+
+fn u32_at(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        data[offset],
+        data[offset + 1],
+        data[offset + 2],
+        data[offset + 3],
+    ])
+}
+
+// GLB: 12 字节文件头, 后面每块是 长度 + 类型 + 数据, 第一块是 JSON, 第二块 (可选) 是二进制 buffer
+fn split_glb(data: &[u8]) -> Result<(&[u8], Option<&[u8]>), String> {
+    let mut json = None;
+    let mut bin = None;
+    let mut offset = 12;
+    while offset + 8 <= data.len() {
+        let length = u32_at(data, offset) as usize;
+        let kind = u32_at(data, offset + 4);
+        let start = offset + 8;
+        let end = start + length;
+        if end > data.len() {
+            return Err(String::from("truncated GLB chunk"));
+        }
+        match kind {
+            0x4E4F_534A => json = Some(&data[start..end]), // "JSON"
+            0x004E_4942 => bin = Some(&data[start..end]),  // "BIN\0"
+            _ => {}
+        }
+        offset = end;
+    }
+    match json {
+        Some(json) => Ok((json, bin)),
+        None => Err(String::from("GLB has no JSON chunk")),
+    }
+}
+
+fn base64_decode(text: &str) -> Result<Vec<u8>, String> {
+    let mut res = Vec::with_capacity(text.len() / 4 * 3);
+    let mut acc = 0u32;
+    let mut bits = 0;
+    for c in text.bytes() {
+        let v = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            b'=' => break,
+            _ => return Err(String::from("invalid base64 data")),
+        };
+        acc = (acc << 6) | v as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            res.push((acc >> bits) as u8);
+            acc &= (1 << bits) - 1;
+        }
+    }
+    Ok(res)
+}
+
+// Blender 导出的文件名里空格之类会写成 %20
+fn percent_decode(uri: &str) -> String {
+    let bytes = uri.as_bytes();
+    let mut res = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or("");
+            if let Ok(c) = u8::from_str_radix(hex, 16) {
+                res.push(c);
+                i += 3;
+                continue;
+            }
+        }
+        res.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&res).into_owned()
+}
+
+// data URI 里是 base64, 否则是相对 glTF 文件所在目录的路径
+fn read_uri(uri: &str, dir: &Path) -> Result<Vec<u8>, String> {
+    if uri.starts_with("data:") {
+        return match uri.find(";base64,") {
+            Some(i) => base64_decode(&uri[i + 8..]),
+            None => Err(String::from("only base64 data URIs are supported")),
+        };
+    }
+    let path = dir.join(percent_decode(uri));
+    fs::read(&path).map_err(|e| format!("`{}`: {}", path.display(), e))
+}
+
+struct Loader<'a> {
+    doc: &'a Document,
+    dir: &'a Path,
+    buffers: Vec<Vec<u8>>,
+    mat: Option<Arc<Material>>, // 不为 None 时覆盖文件里所有材质
+    default_mat: Option<Arc<Material>>,
+    materials: HashMap<usize, Option<Arc<Material>>>,
+    textures: HashMap<usize, Arc<Texture>>,
+    objects: Hittablelist,
+    camera: Option<Gltfcamera>,
+}
+
+impl<'a> Loader<'a> {
+    fn buffer_view(&self, index: usize) -> Result<(&[u8], Option<usize>), String> {
+        let view = self
+            .doc
+            .buffer_views
+            .get(index)
+            .ok_or_else(|| format!("bufferView {} does not exist", index))?;
+        let buffer = self
+            .buffers
+            .get(view.buffer)
+            .ok_or_else(|| format!("buffer {} does not exist", view.buffer))?;
+        let end = view.byte_offset + view.byte_length;
+        if end > buffer.len() {
+            return Err(format!("bufferView {} is out of bounds", index));
+        }
+        Ok((&buffer[view.byte_offset..end], view.byte_stride))
+    }
+
+    // 读出 accessor 里的所有分量, 都转成 f64; 返回的数组里每个元素占 n 个连续分量
+    fn accessor(&self, index: usize) -> Result<(Vec<f64>, usize), String> {
+        let accessor = self
+            .doc
+            .accessors
+            .get(index)
+            .ok_or_else(|| format!("accessor {} does not exist", index))?;
+        if accessor.sparse.is_some() {
+            return Err(format!(
+                "accessor {}: sparse accessors are not supported",
+                index
+            ));
+        }
+        let n = match accessor.kind.as_str() {
+            "SCALAR" => 1,
+            "VEC2" => 2,
+            "VEC3" => 3,
+            "VEC4" => 4,
+            kind => return Err(format!("accessor {}: unsupported type {}", index, kind)),
+        };
+        let size = match accessor.component_type {
+            5120 | 5121 => 1,
+            5122 | 5123 => 2,
+            5125 | 5126 => 4,
+            kind => {
+                return Err(format!(
+                    "accessor {}: unsupported component type {}",
+                    index, kind
+                ))
+            }
+        };
+        // 没有 bufferView 的 accessor 全是 0
+        let view = match accessor.buffer_view {
+            Some(view) => view,
+            None => return Ok((vec![0.0; accessor.count * n], n)),
+        };
+        let (data, stride) = self.buffer_view(view)?;
+        let stride = stride.unwrap_or(n * size);
+        if accessor.count > 0
+            && accessor.byte_offset + (accessor.count - 1) * stride + n * size > data.len()
+        {
+            return Err(format!("accessor {} is out of bounds", index));
+        }
+
+        let normalize = |x: f64, max: f64| {
+            if accessor.normalized {
+                (x / max).max(-1.0)
+            } else {
+                x
+            }
+        };
+        let mut res = Vec::with_capacity(accessor.count * n);
+        for i in 0..accessor.count {
+            for c in 0..n {
+                let b = &data[accessor.byte_offset + i * stride + c * size..];
+                res.push(match accessor.component_type {
+                    5120 => normalize(b[0] as i8 as f64, 127.0),
+                    5121 => normalize(b[0] as f64, 255.0),
+                    5122 => normalize(i16::from_le_bytes([b[0], b[1]]) as f64, 32767.0),
+                    5123 => normalize(u16::from_le_bytes([b[0], b[1]]) as f64, 65535.0),
+                    5125 => u32_at(b, 0) as f64,
+                    _ => f32::from_bits(u32_at(b, 0)) as f64,
+                });
+            }
+        }
+        Ok((res, n))
+    }
+
+    // 顶点属性, 要求分量个数是 n、元素个数和 POSITION 一样
+    fn attribute(
+        &self,
+        primitive: &Primitive,
+        name: &str,
+        n: usize,
+        count: usize,
+    ) -> Result<Option<Vec<f64>>, String> {
+        let index = match primitive.attributes.get(name) {
+            Some(&index) => index,
+            None => return Ok(None),
+        };
+        let (data, m) = self.accessor(index)?;
+        if m != n || data.len() != count * n {
+            return Err(format!("{} does not match POSITION", name));
+        }
+        Ok(Some(data))
+    }
+
+    fn node(&mut self, index: usize, parent: &Matrix4, depth: usize) -> Result<(), String> {
+        let doc = self.doc;
+        let node = doc
+            .nodes
+            .get(index)
+            .ok_or_else(|| format!("node {} does not exist", index))?;
+        if depth > doc.nodes.len() {
+            return Err(format!("node {} is part of a cycle", index));
+        }
+        let local = match &node.matrix {
+            Some(m) => {
+                let mut res = Matrix4::identity();
+                for i in 0..4 {
+                    for j in 0..4 {
+                        res.m[i][j] = m[j * 4 + i];
+                    }
+                }
+                res
+            }
+            None => {
+                Matrix4::translate(&vec3(&node.translation))
+                    * Matrix4::rotate_quaternion(&node.rotation)
+                    * Matrix4::scale(&vec3(&node.scale))
+            }
+        };
+        let world = *parent * local;
+
+        if let Some(mesh) = node.mesh {
+            self.mesh(mesh, &world)
+                .map_err(|e| format!("mesh {}: {}", mesh, e))?;
+        }
+        if let Some(camera) = node.camera {
+            if self.camera.is_none() {
+                self.camera(camera, &world)?;
+            }
+        }
+        for &child in &node.children {
+            self.node(child, &world, depth + 1)?;
+        }
+        Ok(())
+    }
+
+    // glTF 的相机朝自己的 -z 看, +y 朝上; 正交相机不支持, 跳过
+    fn camera(&mut self, index: usize, world: &Matrix4) -> Result<(), String> {
+        let camera = self
+            .doc
+            .cameras
+            .get(index)
+            .ok_or_else(|| format!("camera {} does not exist", index))?;
+        if let Some(perspective) = &camera.perspective {
+            self.camera = Some(Gltfcamera {
+                lookfrom: world.point(&Point3::new(0.0, 0.0, 0.0)),
+                lookat: world.point(&Point3::new(0.0, 0.0, -1.0)),
+                vup: world.vector(&Vec3::new(0.0, 1.0, 0.0)),
+                vfov: perspective.yfov.to_degrees(),
+            });
+        }
+        Ok(())
+    }
+
+    // 网格直接按节点变换摆到 glTF 的世界坐标里, 所有节点的三角形放进同一棵 BVH
+    fn mesh(&mut self, index: usize, world: &Matrix4) -> Result<(), String> {
+        let doc = self.doc;
+        let mesh = doc
+            .meshes
+            .get(index)
+            .ok_or_else(|| String::from("does not exist"))?;
+        // 缩放成 0 的节点看不见
+        let normal_matrix = match world.inverse() {
+            Some(inverse) => inverse.transpose(),
+            None => return Ok(()),
+        };
+
+        for primitive in &mesh.primitives {
+            // 只支持三角形列表, 点和线没有面积
+            if primitive.mode != 4 {
+                continue;
+            }
+            let (positions, n) = match primitive.attributes.get("POSITION") {
+                Some(&position) => self.accessor(position)?,
+                None => continue,
+            };
+            if n != 3 {
+                return Err(String::from("POSITION must be VEC3"));
+            }
+            let count = positions.len() / 3;
+            let normals = self.attribute(primitive, "NORMAL", 3, count)?;
+            let texcoords = self.attribute(primitive, "TEXCOORD_0", 2, count)?;
+            let indices: Vec<usize> = match primitive.indices {
+                Some(indices) => self
+                    .accessor(indices)?
+                    .0
+                    .iter()
+                    .map(|&i| i as usize)
+                    .collect(),
+                None => (0..count).collect(),
+            };
+            if indices.iter().any(|&i| i >= count) {
+                return Err(String::from("vertex index out of range"));
+            }
+            let mat = self.material(primitive.material)?;
+
+            let point = |i: usize| {
+                world.point(&Point3::new(
+                    positions[i * 3],
+                    positions[i * 3 + 1],
+                    positions[i * 3 + 2],
+                ))
+            };
+            for face in indices.chunks_exact(3) {
+                let mut triangle =
+                    Triangle::new(&[point(face[0]), point(face[1]), point(face[2])], &mat);
+                if let Some(normals) = &normals {
+                    let normal = |i: usize| {
+                        normal_matrix
+                            .vector(&Vec3::new(
+                                normals[i * 3],
+                                normals[i * 3 + 1],
+                                normals[i * 3 + 2],
+                            ))
+                            .unit_vector()
+                    };
+                    triangle =
+                        triangle.with_normals(&[normal(face[0]), normal(face[1]), normal(face[2])]);
+                }
+                if let Some(texcoords) = &texcoords {
+                    // glTF 的 v 轴朝下, Objtexture 按 OBJ 的习惯 v 轴朝上, 这里先翻过来
+                    let uv = |i: usize| [texcoords[i * 2], 1.0 - texcoords[i * 2 + 1]];
+                    triangle = triangle.with_texcoords(&[uv(face[0]), uv(face[1]), uv(face[2])]);
+                }
+                self.objects.add(Object::Triangle(triangle));
+            }
+        }
+        Ok(())
+    }
+
+    fn material(&mut self, index: Option<usize>) -> Result<Option<Arc<Material>>, String> {
+        if self.mat.is_some() {
+            return Ok(self.mat.clone());
+        }
+        let index = match index {
+            Some(index) => index,
+            None => return Ok(self.default_mat.clone()),
+        };
+        if let Some(material) = self.materials.get(&index) {
+            return Ok(material.clone());
+        }
+        let doc = self.doc;
+        let desc = doc
+            .materials
+            .get(index)
+            .ok_or_else(|| format!("material {} does not exist", index))?;
+        let material = self
+            .build_material(desc)
+            .map_err(|e| format!("material {}: {}", index, e))?;
+        let material = Some(Arc::new(material));
+        self.materials.insert(index, material.clone());
+        Ok(material)
+    }
+
+    // 金属度-粗糙度材质对应到我们的材质: 发光 -> Diffuselight, KHR_materials_transmission -> Dielectric,
+    // metallicFactor >= 0.5 -> Metal(roughness 当 fuzz), 其余 -> Lambertian;
+    // 有 baseColorTexture 时只用贴图, 不再乘 baseColorFactor
+    fn build_material(&mut self, desc: &Gltfmaterial) -> Result<Material, String> {
+        let extension = |name: &str, key: &str| {
+            desc.extensions
+                .get(name)
+                .and_then(|e| e.get(key))
+                .and_then(|v| v.as_f64())
+        };
+        let strength =
+            extension("KHR_materials_emissive_strength", "emissiveStrength").unwrap_or(1.0);
+        let emit = vec3(&desc.emissive_factor) * strength;
+        if emit.length_squared() > 0.0 {
+            return Ok(Material::Diffuselight(Diffuselight::new_from_color(&emit)));
+        }
+        if extension("KHR_materials_transmission", "transmissionFactor").unwrap_or(0.0) > 0.0 {
+            let ir = extension("KHR_materials_ior", "ior").unwrap_or(1.5);
+            return Ok(Material::Dielectric(Dielectric::new(ir)));
+        }
+
+        let pbr = &desc.pbr_metallic_roughness;
+        let c = &pbr.base_color_factor;
+        let base_color = Color::new(c[0], c[1], c[2]);
+        if pbr.metallic_factor >= 0.5 {
+            return Ok(Material::Metal(Metal::new(
+                &base_color,
+                pbr.roughness_factor,
+            )));
+        }
+        match &pbr.base_color_texture {
+            Some(info) => {
+                let texture = self.texture(info.index)?;
+                Ok(Material::Lambertian(Lambertian::new_from_ptr(&Some(
+                    texture,
+                ))))
+            }
+            None => Ok(Material::Lambertian(Lambertian::new(&base_color))),
+        }
+    }
+
+    // 贴图可以是外部文件、data URI, 或者放在 bufferView 里 (GLB)
+    fn texture(&mut self, index: usize) -> Result<Arc<Texture>, String> {
+        if let Some(texture) = self.textures.get(&index) {
+            return Ok(texture.clone());
+        }
+        let doc = self.doc;
+        let source = doc
+            .textures
+            .get(index)
+            .and_then(|t| t.source)
+            .ok_or_else(|| format!("texture {} has no image", index))?;
+        let desc = doc
+            .images
+            .get(source)
+            .ok_or_else(|| format!("image {} does not exist", source))?;
+        let bytes = match (&desc.uri, desc.buffer_view) {
+            (Some(uri), _) => read_uri(uri, self.dir)?,
+            (None, Some(view)) => self.buffer_view(view)?.0.to_vec(),
+            (None, None) => return Err(format!("image {} has no data", source)),
+        };
+        let img = image::load_from_memory(&bytes)
+            .map_err(|e| format!("image {}: {}", source, e))?
+            .to_rgb8();
+        let texture = Arc::new(Texture::Objtexture(Objtexture::new_from_image(img)));
+        self.textures.insert(index, texture.clone());
+        Ok(texture)
+    }
+}
+
+impl Gltf {
+    // mat 不为 None 时覆盖文件里的所有材质
+    pub fn load(
+        file_name: &str,
+        mat: &Option<Arc<Material>>,
+        tm: f64,
+        dur: f64,
+        split: Bvhsplit,
+    ) -> Result<Gltf, Error> {
+        let data = fs::read(file_name).map_err(|e| Error::Io {
+            path: file_name.to_string(),
+            source: e,
+        })?;
+        let dir = Path::new(file_name)
+            .parent()
+            .unwrap_or_else(|| Path::new(""));
+        Gltf::parse(&data, dir, mat, tm, dur, split).map_err(|message| Error::Gltf {
+            path: file_name.to_string(),
+            message,
+        })
+    }
+
+    fn parse(
+        data: &[u8],
+        dir: &Path,
+        mat: &Option<Arc<Material>>,
+        tm: f64,
+        dur: f64,
+        split: Bvhsplit,
+    ) -> Result<Gltf, String> {
+        let (json, bin) = if data.starts_with(b"glTF") {
+            split_glb(data)?
+        } else {
+            (data, None)
+        };
+        let doc: Document = serde_json::from_slice(json).map_err(|e| e.to_string())?;
+
+        // GLB 的二进制块是第一个没有 uri 的 buffer
+        let mut buffers = Vec::new();
+        for (i, buffer) in doc.buffers.iter().enumerate() {
+            let bytes = match (&buffer.uri, bin) {
+                (Some(uri), _) => read_uri(uri, dir)?,
+                (None, Some(bin)) if i == 0 => bin.to_vec(),
+                (None, _) => return Err(format!("buffer {} has no data", i)),
+            };
+            if bytes.len() < buffer.byte_length {
+                return Err(format!("buffer {} is shorter than its byteLength", i));
+            }
+            buffers.push(bytes);
+        }
+
+        // 没有 scenes 时, 不是别的节点的子节点的都当作根节点
+        let roots: Vec<usize> = if doc.scenes.is_empty() {
+            let mut is_child = vec![false; doc.nodes.len()];
+            for node in &doc.nodes {
+                for &child in &node.children {
+                    if child < is_child.len() {
+                        is_child[child] = true;
+                    }
+                }
+            }
+            (0..doc.nodes.len()).filter(|&i| !is_child[i]).collect()
+        } else {
+            let scene = doc.scene.unwrap_or(0);
+            doc.scenes
+                .get(scene)
+                .ok_or_else(|| format!("scene {} does not exist", scene))?
+                .nodes
+                .clone()
+        };
+
+        let mut loader = Loader {
+            doc: &doc,
+            dir,
+            buffers,
+            mat: mat.clone(),
+            default_mat: Some(Arc::new(Material::Lambertian(Lambertian::new(
+                &Color::new(0.73, 0.73, 0.73),
+            )))),
+            materials: HashMap::new(),
+            textures: HashMap::new(),
+            objects: Hittablelist::default_new(),
+            camera: None,
+        };
+        for root in roots {
+            loader.node(root, &Matrix4::identity(), 0)?;
+        }
+
+        let triangle_count = loader.objects.objects.len();
+        if triangle_count == 0 {
+            return Err(String::from("no triangles"));
+        }
+        Ok(Gltf {
+            mesh: Obj {
+                obj: Arc::new(Bvhnode::build(loader.objects, tm, dur, split)?),
+            },
+            camera: loader.camera,
+            triangle_count,
+        })
+    }
+}
@@ -0,0 +1,330 @@
+#![allow(unused_variables)]
+pub use crate::aabb::Aabb;
+pub use crate::error::Error;
+pub use crate::hittable::Boundingbox;
+pub use crate::hittable::Hit;
+pub use crate::hittable::Hitrecord;
+pub use crate::material::Material;
+pub use crate::ray::Ray;
+pub use crate::vec3::Point3;
+pub use crate::vec3::Vec3;
+use std::sync::Arc;
+
+// 灰度图做的地形: 每个像素一个高度采样, 相邻四个采样点围成一格, 每格拆成两个三角形
+// 采样点放在像素中心, 交点的 u, v 和同一张图做的 Imagetexture 正好对上
+pub struct Heightfield {
+    min: Point3, // xz 范围的起点, y 是高度 0 的位置
+    size: Vec3,  // x、z 方向的范围, y 是白色 (最高) 对应的高度
+    nx: usize,
+    nz: usize,
+    heights: Arc<Vec<f64>>,
+    normals: Arc<Vec<Vec3>>,
+    cell_range: Arc<Vec<(f64, f64)>>, // 每格四个角的最低、最高
+    low: Point3, // 包围盒: 采样点围成的范围 (比 size 每边少半个像素) 加上高度范围
+    high: Point3,
+    pub mp: Option<Arc<Material>>,
+}
+
+impl Heightfield {
+    // 图的上边 (第 0 行) 在 z 小的一侧, 和 Imagetexture 的 v 方向一致
+    pub fn new(
+        file_name: &str,
+        min: &Point3,
+        size: &Vec3,
+        mat: &Option<Arc<Material>>,
+    ) -> Result<Heightfield, Error> {
+        let img = image::open(file_name)
+            .map_err(|e| Error::Image {
+                path: file_name.to_string(),
+                source: e,
+            })?
+            .to_luma16();
+        let (nx, nz) = img.dimensions();
+        let heights = img.pixels().map(|p| p[0] as f64 / 65535.0).collect();
+        Ok(Heightfield::from_heights(
+            nx as usize,
+            nz as usize,
+            heights,
+            min,
+            size,
+            mat,
+        ))
+    }
+
+    // heights 按行存, 第 j 行第 i 个是 heights[j * nx + i], 取值 0..1
+    pub fn from_heights(
+        nx: usize,
+        nz: usize,
+        heights: Vec<f64>,
+        min: &Point3,
+        size: &Vec3,
+        mat: &Option<Arc<Material>>,
+    ) -> Heightfield {
+        let heights: Vec<f64> = heights.iter().map(|h| h * size.y()).collect();
+        let dx = size.x() / nx as f64;
+        let dz = size.z() / nz as f64;
+
+        // 顶点法线用相邻采样点的差分, 边上用单侧差分
+        let mut normals = Vec::with_capacity(nx * nz);
+        for j in 0..nz {
+            for i in 0..nx {
+                let (i0, i1) = (i.saturating_sub(1), (i + 1).min(nx - 1));
+                let (j0, j1) = (j.saturating_sub(1), (j + 1).min(nz - 1));
+                let slope_x = if i1 > i0 {
+                    (heights[j * nx + i1] - heights[j * nx + i0]) / ((i1 - i0) as f64 * dx)
+                } else {
+                    0.0
+                };
+                let slope_z = if j1 > j0 {
+                    (heights[j1 * nx + i] - heights[j0 * nx + i]) / ((j1 - j0) as f64 * dz)
+                } else {
+                    0.0
+                };
+                normals.push(Vec3::new(-slope_x, 1.0, -slope_z).unit_vector());
+            }
+        }
+
+        let mut cell_range = Vec::new();
+        for j in 0..nz.saturating_sub(1) {
+            for i in 0..nx.saturating_sub(1) {
+                let corners = [
+                    heights[j * nx + i],
+                    heights[j * nx + i + 1],
+                    heights[(j + 1) * nx + i],
+                    heights[(j + 1) * nx + i + 1],
+                ];
+                let low = corners.iter().cloned().fold(f64::INFINITY, f64::min);
+                let high = corners.iter().cloned().fold(-f64::INFINITY, f64::max);
+                cell_range.push((low, high));
+            }
+        }
+
+        let mut res = Heightfield {
+            min: min.copy(),
+            size: size.copy(),
+            nx,
+            nz,
+            heights: Arc::new(heights),
+            normals: Arc::new(normals),
+            cell_range: Arc::new(cell_range),
+            low: Point3::default_new(),
+            high: Point3::default_new(),
+            mp: mat.clone(),
+        };
+        if nx >= 2 && nz >= 2 {
+            let lowest = res.heights.iter().cloned().fold(f64::INFINITY, f64::min);
+            let highest = res.heights.iter().cloned().fold(-f64::INFINITY, f64::max);
+            let first = res.vertex(0, 0);
+            let last = res.vertex(nx - 1, nz - 1);
+            res.low = Point3::new(first.x(), min.y() + lowest - 0.0001, first.z());
+            res.high = Point3::new(last.x(), min.y() + highest + 0.0001, last.z());
+        }
+        res
+    }
+
+    pub fn copy(&self) -> Heightfield {
+        Heightfield {
+            min: self.min,
+            size: self.size,
+            nx: self.nx,
+            nz: self.nz,
+            heights: self.heights.clone(),
+            normals: self.normals.clone(),
+            cell_range: self.cell_range.clone(),
+            low: self.low,
+            high: self.high,
+            mp: self.mp.clone(),
+        }
+    }
+
+    fn cell_size(&self) -> (f64, f64) {
+        (
+            self.size.x() / self.nx as f64,
+            self.size.z() / self.nz as f64,
+        )
+    }
+
+    // 第 (i, j) 个采样点, 在像素中心
+    fn vertex(&self, i: usize, j: usize) -> Point3 {
+        let (dx, dz) = self.cell_size();
+        Point3::new(
+            self.min.x() + (i as f64 + 0.5) * dx,
+            self.min.y() + self.heights[j * self.nx + i],
+            self.min.z() + (j as f64 + 0.5) * dz,
+        )
+    }
+
+    // 一格里的两个三角形 (a, c, b) 和 (b, c, d), 法线都朝 +y
+    fn hit_cell(
+        &self,
+        i: usize,
+        j: usize,
+        r: &Ray,
+        t_min: f64,
+        t_max: f64,
+        rec: &mut Hitrecord,
+    ) -> bool {
+        let corners = [(i, j + 1), (i + 1, j), (i, j), (i + 1, j + 1)];
+        let mut closest = t_max;
+        let mut found = None;
+        for tri in [[2, 0, 1], [1, 0, 3]].iter() {
+            let ver = [
+                self.vertex(corners[tri[0]].0, corners[tri[0]].1),
+                self.vertex(corners[tri[1]].0, corners[tri[1]].1),
+                self.vertex(corners[tri[2]].0, corners[tri[2]].1),
+            ];
+            if let Some((t, beta, gamma)) = intersect(r, &ver, t_min, closest) {
+                closest = t;
+                found = Some((*tri, ver, beta, gamma));
+            }
+        }
+        let (tri, ver, beta, gamma) = match found {
+            Some(found) => found,
+            None => return false,
+        };
+
+        rec.t = closest;
+        rec.p = r.at(closest);
+        let outward_normal = Vec3::cross(&(ver[1] - ver[0]), &(ver[2] - ver[0])).unit_vector();
+        rec.set_face_normal(&r, &outward_normal);
+        // 着色法线插值顶点法线, 翻到和几何法线同一侧
+        let normal_at = |k: usize| self.normals[corners[tri[k]].1 * self.nx + corners[tri[k]].0];
+        let shading =
+            (normal_at(0) * (1.0 - beta - gamma) + normal_at(1) * beta + normal_at(2) * gamma)
+                .unit_vector();
+        rec.normal = if Vec3::dot(&shading, &rec.normal) < 0.0 {
+            -shading
+        } else {
+            shading
+        };
+        rec.u = (rec.p.x() - self.min.x()) / self.size.x();
+        rec.v = 1.0 - (rec.p.z() - self.min.z()) / self.size.z();
+        rec.mat_ptr = self.mp.clone();
+        true
+    }
+}
+
+// Möller–Trumbore, 边界算打中; 返回 (t, beta, gamma)
+fn intersect(r: &Ray, ver: &[Point3; 3], t_min: f64, t_max: f64) -> Option<(f64, f64, f64)> {
+    let ab = ver[1] - ver[0];
+    let ac = ver[2] - ver[0];
+    let dir = r.direction();
+    let pvec = Vec3::cross(&dir, &ac);
+    let inv_det = 1.0 / Vec3::dot(&ab, &pvec);
+    if !inv_det.is_finite() {
+        return None;
+    }
+    let tvec = r.origin() - ver[0];
+    let beta = Vec3::dot(&tvec, &pvec) * inv_det;
+    if !(0.0..=1.0).contains(&beta) {
+        return None;
+    }
+    let qvec = Vec3::cross(&tvec, &ab);
+    let gamma = Vec3::dot(&dir, &qvec) * inv_det;
+    if gamma < 0.0 || beta + gamma > 1.0 {
+        return None;
+    }
+    let t = Vec3::dot(&ac, &qvec) * inv_det;
+    if t < t_min || t > t_max {
+        return None;
+    }
+    Some((t, beta, gamma))
+}
+
+impl Hit for Heightfield {
+    fn hit(&self, r: &Ray, t_min: f64, t_max: f64, rec: &mut Hitrecord) -> bool {
+        if self.nx < 2 || self.nz < 2 {
+            return false;
+        }
+        let origin = r.origin();
+        let dir = r.direction();
+
+        // 先裁到包围盒里
+        let (low, high) = (self.low, self.high);
+        let mut t0 = t_min;
+        let mut t1 = t_max;
+        for a in 0..3 {
+            let inv_d = 1.0 / dir[a];
+            let mut near = (low[a] - origin[a]) * inv_d;
+            let mut far = (high[a] - origin[a]) * inv_d;
+            if inv_d < 0.0 {
+                std::mem::swap(&mut near, &mut far);
+            }
+            t0 = t0.max(near);
+            t1 = t1.min(far);
+            if t1 < t0 {
+                return false;
+            }
+        }
+
+        // 在 xz 平面上按格子走 (DDA), 光线在这一格里的高度范围碰不到格子的高度范围就跳过
+        let (dx, dz) = self.cell_size();
+        let start = r.at(t0);
+        let grid_x = |x: f64| (x - low.x()) / dx;
+        let grid_z = |z: f64| (z - low.z()) / dz;
+        let clamp_cell = |g: f64, n: usize| (g.floor().max(0.0) as usize).min(n - 2);
+        let mut i = clamp_cell(grid_x(start.x()), self.nx);
+        let mut j = clamp_cell(grid_z(start.z()), self.nz);
+
+        let (step_i, mut next_x, delta_x) = if dir.x() > 0.0 {
+            let boundary = low.x() + (i + 1) as f64 * dx;
+            (1, (boundary - origin.x()) / dir.x(), dx / dir.x())
+        } else if dir.x() < 0.0 {
+            let boundary = low.x() + i as f64 * dx;
+            (-1, (boundary - origin.x()) / dir.x(), -dx / dir.x())
+        } else {
+            (0, f64::INFINITY, f64::INFINITY)
+        };
+        let (step_j, mut next_z, delta_z) = if dir.z() > 0.0 {
+            let boundary = low.z() + (j + 1) as f64 * dz;
+            (1, (boundary - origin.z()) / dir.z(), dz / dir.z())
+        } else if dir.z() < 0.0 {
+            let boundary = low.z() + j as f64 * dz;
+            (-1, (boundary - origin.z()) / dir.z(), -dz / dir.z())
+        } else {
+            (0, f64::INFINITY, f64::INFINITY)
+        };
+
+        let mut t_enter = t0;
+        loop {
+            let t_exit = next_x.min(next_z).min(t1);
+            let y0 = origin.y() + t_enter * dir.y() - self.min.y();
+            let y1 = origin.y() + t_exit * dir.y() - self.min.y();
+            let (cell_low, cell_high) = self.cell_range[j * (self.nx - 1) + i];
+            if y0.min(y1) <= cell_high + 0.0001
+                && y0.max(y1) >= cell_low - 0.0001
+                && self.hit_cell(i, j, r, t_min, t_max, rec)
+            {
+                return true;
+            }
+            if t_exit >= t1 {
+                return false;
+            }
+            if next_x < next_z {
+                if (step_i < 0 && i == 0) || (step_i > 0 && i + 2 >= self.nx) {
+                    return false;
+                }
+                i = (i as i64 + step_i) as usize;
+                t_enter = next_x;
+                next_x += delta_x;
+            } else {
+                if (step_j < 0 && j == 0) || (step_j > 0 && j + 2 >= self.nz) {
+                    return false;
+                }
+                j = (j as i64 + step_j) as usize;
+                t_enter = next_z;
+                next_z += delta_z;
+            }
+        }
+    }
+}
+
+impl Boundingbox for Heightfield {
+    fn boundingbox(&self, time0: f64, time1: f64, output_box: &mut Aabb) -> bool {
+        if self.nx < 2 || self.nz < 2 {
+            return false;
+        }
+        *output_box = Aabb::new(&self.low, &self.high);
+        true
+    }
+}
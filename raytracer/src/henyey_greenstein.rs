@@ -0,0 +1,94 @@
+#![allow(unused_variables)]
+pub use crate::hittable::Hitrecord;
+pub use crate::material::Scatter;
+pub use crate::material::Scatterrecord;
+pub use crate::onb::Onb;
+pub use crate::rand;
+pub use crate::ray::Ray;
+pub use crate::solidcolor::Solidcolor;
+pub use crate::texture::Texture;
+pub use crate::texture::Value;
+pub use crate::vec3::Color;
+pub use crate::vec3::Vec3;
+pub use std::f64::consts::PI;
+use std::sync::Arc;
+
+// Henyey-Greenstein 相函数: g > 0 偏向前方散射 (云的银边、光柱), g < 0 偏向后方, g = 0 就是各向同性
+pub struct Henyeygreenstein {
+    albedo: Option<Arc<Texture>>,
+    g: f64,
+}
+
+impl Henyeygreenstein {
+    pub fn new_from_color(a: &Color, g: f64) -> Henyeygreenstein {
+        Henyeygreenstein {
+            albedo: Some(Arc::new(Texture::Solidcolor(Solidcolor::new_from_color(
+                &a,
+            )))),
+            g,
+        }
+    }
+
+    pub fn new_from_ptr(a: &Option<Arc<Texture>>, g: f64) -> Henyeygreenstein {
+        Henyeygreenstein {
+            albedo: a.clone(),
+            g,
+        }
+    }
+
+    pub fn copy(&self) -> Henyeygreenstein {
+        Henyeygreenstein {
+            albedo: self.albedo.clone(),
+            g: self.g,
+        }
+    }
+
+    // cos_theta 是入射方向和出射方向的夹角余弦, 沿原方向继续走时为 1; 在整个球面上积分为 1
+    pub fn phase(g: f64, cos_theta: f64) -> f64 {
+        let denom = 1.0 + g * g - 2.0 * g * cos_theta;
+        (1.0 - g * g) / (4.0 * PI * denom * denom.sqrt())
+    }
+
+    fn albedo(&self, rec: &Hitrecord) -> Color {
+        match &self.albedo {
+            Some(in_albedo) => in_albedo.value(rec.u, rec.v, &rec.p),
+            None => Color::new(0.0, 0.0, 0.0),
+        }
+    }
+}
+
+impl Scatter for Henyeygreenstein {
+    fn scatter(&self, r_in: &Ray, rec: &Hitrecord, srec: &mut Scatterrecord) -> bool {
+        // 按相函数本身反演采样 cos_theta, 权重正好是 albedo
+        let g = self.g;
+        let xi = rand::random_double();
+        let cos_theta = if g.abs() < 1e-3 {
+            1.0 - 2.0 * xi
+        } else {
+            let s = (1.0 - g * g) / (1.0 - g + 2.0 * g * xi);
+            ((1.0 + g * g - s * s) / (2.0 * g)).clamp(-1.0, 1.0)
+        };
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let phi = 2.0 * PI * rand::random_double();
+        let uvw = Onb::build_from_w(&r_in.direction());
+        let direction = uvw.local(sin_theta * phi.cos(), sin_theta * phi.sin(), cos_theta);
+
+        srec.scattered = Ray::new(&rec.p.copy(), &direction, r_in.time());
+        srec.attenuation = self.albedo(rec);
+        srec.pdf = Henyeygreenstein::phase(g, cos_theta);
+        srec.is_specular = false;
+        true
+    }
+
+    fn scattering_pdf(&self, r_in: &Ray, rec: &Hitrecord, scattered: &Ray) -> f64 {
+        let cos_theta = Vec3::dot(
+            &r_in.direction().unit_vector(),
+            &scattered.direction().unit_vector(),
+        );
+        Henyeygreenstein::phase(self.g, cos_theta)
+    }
+
+    fn eval(&self, r_in: &Ray, rec: &Hitrecord, scattered: &Ray) -> Color {
+        self.albedo(rec) * self.scattering_pdf(r_in, rec, scattered)
+    }
+}
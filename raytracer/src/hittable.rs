@@ -5,11 +5,12 @@ pub use crate::ray::Ray;
 pub use crate::vec3::Color;
 pub use crate::vec3::Point3;
 pub use crate::vec3::Vec3;
+use std::sync::Arc;
 
 pub struct Hitrecord {
     pub p: Point3,
     pub normal: Vec3,
-    pub mat_ptr: Option<Box<Material>>,
+    pub mat_ptr: Option<Arc<Material>>,
     pub t: f64,
     pub u: f64,
     pub v: f64,
@@ -24,6 +25,24 @@ pub trait Boundingbox {
     fn boundingbox(&self, time0: f64, time1: f64, output_box: &mut Aabb) -> bool;
 }
 
+// 光线在封闭物体里面的一段, 从 enter 进去, 从 exit 出来
+pub struct Span {
+    pub enter: Hitrecord,
+    pub exit: Hitrecord,
+}
+
+// t_min 之后光线在物体里面的所有区间, 按 t 从小到大;
+// 起点已经在物体里面时第一段的 enter.t 是负无穷, 出不来时最后一段的 exit.t 是正无穷
+pub trait Intervals {
+    fn intervals(&self, r: &Ray, t_min: f64) -> Vec<Span>;
+}
+
+// 光源采样: 从 o 点看向物体的方向的概率密度 (立体角), 以及按这个密度随机取一个方向
+pub trait Lightsample {
+    fn pdf_value(&self, o: &Point3, v: &Vec3) -> f64;
+    fn random(&self, o: &Point3) -> Vec3;
+}
+
 impl Hitrecord {
     pub fn default_new() -> Hitrecord {
         Hitrecord {
@@ -45,11 +64,7 @@ impl Hitrecord {
             u: rhs.u,
             v: rhs.v,
             front_face: rhs.front_face,
-            mat_ptr: rhs
-                .mat_ptr
-                .as_ref()
-                .map(|in_mat_ptr| Box::new(in_mat_ptr.copy()))
-                .map(|in_mat_ptr| Box::new(in_mat_ptr.copy())),
+            mat_ptr: rhs.mat_ptr.clone(),
         }
     }
 
@@ -61,11 +76,7 @@ impl Hitrecord {
             u: self.u,
             v: self.v,
             front_face: self.front_face,
-            mat_ptr: self
-                .mat_ptr
-                .as_ref()
-                .map(|in_mat_ptr| Box::new(in_mat_ptr.copy()))
-                .map(|in_mat_ptr| Box::new(in_mat_ptr.copy())),
+            mat_ptr: self.mat_ptr.clone(),
         }
     }
 
@@ -78,3 +89,37 @@ impl Hitrecord {
         };
     }
 }
+
+// 沿光线一个个往后找交点, 从正面打到算进入, 从背面打到算离开, 对任何法线朝外的封闭物体都成立;
+// 重叠的几块 (比如 bvh 里的几个球) 按进出的层数合并
+pub fn walk_intervals(object: &dyn Hit, r: &Ray, t_min: f64) -> Vec<Span> {
+    let mut res = Vec::new();
+    let mut enter: Option<Hitrecord> = None;
+    let mut depth = 0;
+    let mut t = t_min;
+    loop {
+        let mut rec = Hitrecord::default_new();
+        if !object.hit(&r, t, f64::INFINITY, &mut rec) {
+            break;
+        }
+        t = rec.t + 0.0001;
+        if rec.front_face {
+            depth += 1;
+            if depth == 1 {
+                enter = Some(rec);
+            }
+        } else if depth <= 1 {
+            // 一开始就在里面
+            let enter = enter.take().unwrap_or_else(|| Hitrecord {
+                t: -f64::INFINITY,
+                ..Hitrecord::default_new()
+            });
+            res.push(Span { enter, exit: rec });
+            depth = 0;
+        } else {
+            depth -= 1;
+        }
+    }
+    // 只进不出是擦边或者数值误差, 丢掉
+    res
+}
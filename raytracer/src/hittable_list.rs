@@ -1,23 +1,40 @@
 pub use crate::aabb::Aabb;
+pub use crate::animation::Animated;
 pub use crate::boxx::Boxx;
 pub use crate::bvhnode::Bvhnode;
+pub use crate::bvhnode::Bvhsplit;
+pub use crate::cone::Cone;
 pub use crate::constant_medium::ConstantMedium;
+pub use crate::csg::Csg;
+pub use crate::cylinder::Cylinder;
+pub use crate::disk::Disk;
+pub use crate::flatbvh::Flatbvh;
+pub use crate::heightfield::Heightfield;
+pub use crate::hittable::walk_intervals;
 pub use crate::hittable::Boundingbox;
 pub use crate::hittable::Hit;
 pub use crate::hittable::Hitrecord;
+pub use crate::hittable::Intervals;
+pub use crate::hittable::Lightsample;
+pub use crate::hittable::Span;
+pub use crate::material::Material;
 pub use crate::movingsphere::Movingsphere;
 pub use crate::obj::Obj;
+pub use crate::quad::Quad;
+pub use crate::rand;
 pub use crate::ray::Ray;
 pub use crate::rect::XYrect;
 pub use crate::rect::XZrect;
 pub use crate::rect::YZrect;
-pub use crate::rotate::RotateY;
+pub use crate::sdf::Sdf;
 pub use crate::sphere::Sphere;
-pub use crate::translate::Translate;
+pub use crate::torus::Torus;
+pub use crate::transform::Transform;
 pub use crate::triangle::Triangle;
 pub use crate::vec3::Color;
 pub use crate::vec3::Point3;
 pub use crate::vec3::Vec3;
+use std::sync::Arc;
 
 pub enum Object {
     Sphere(Sphere),
@@ -28,11 +45,19 @@ pub enum Object {
     XZrect(XZrect),
     YZrect(YZrect),
     Boxx(Boxx),
-    Translate(Translate),
-    RotateY(RotateY),
+    Transform(Transform),
+    Animated(Animated),
     ConstantMedium(ConstantMedium),
     Triangle(Triangle),
     Obj(Obj),
+    Disk(Disk),
+    Cylinder(Cylinder),
+    Cone(Cone),
+    Torus(Torus),
+    Quad(Quad),
+    Csg(Csg),
+    Sdf(Sdf),
+    Heightfield(Heightfield),
 }
 
 impl Hit for Object {
@@ -48,13 +73,23 @@ impl Hit for Object {
             Object::XZrect(xzrect) => XZrect::hit(&xzrect, &r, t_min, t_max, rec),
             Object::YZrect(yzrect) => YZrect::hit(&yzrect, &r, t_min, t_max, rec),
             Object::Boxx(boxx) => Boxx::hit(&boxx, &r, t_min, t_max, rec),
-            Object::Translate(translate) => Translate::hit(&translate, &r, t_min, t_max, rec),
-            Object::RotateY(rotatey) => RotateY::hit(&rotatey, &r, t_min, t_max, rec),
+            Object::Transform(transform) => Transform::hit(&transform, &r, t_min, t_max, rec),
+            Object::Animated(animated) => Animated::hit(&animated, &r, t_min, t_max, rec),
             Object::ConstantMedium(constantmedium) => {
                 ConstantMedium::hit(&constantmedium, &r, t_min, t_max, rec)
             }
             Object::Triangle(triangle) => Triangle::hit(&triangle, &r, t_min, t_max, rec),
             Object::Obj(obj) => Obj::hit(&obj, &r, t_min, t_max, rec),
+            Object::Disk(disk) => Disk::hit(&disk, &r, t_min, t_max, rec),
+            Object::Cylinder(cylinder) => Cylinder::hit(&cylinder, &r, t_min, t_max, rec),
+            Object::Cone(cone) => Cone::hit(&cone, &r, t_min, t_max, rec),
+            Object::Torus(torus) => Torus::hit(&torus, &r, t_min, t_max, rec),
+            Object::Quad(quad) => Quad::hit(&quad, &r, t_min, t_max, rec),
+            Object::Csg(csg) => Csg::hit(&csg, &r, t_min, t_max, rec),
+            Object::Sdf(sdf) => Sdf::hit(&sdf, &r, t_min, t_max, rec),
+            Object::Heightfield(heightfield) => {
+                Heightfield::hit(&heightfield, &r, t_min, t_max, rec)
+            }
         }
     }
 }
@@ -71,10 +106,12 @@ impl Boundingbox for Object {
             Object::XZrect(xzrect) => XZrect::boundingbox(&xzrect, _time0, _time1, output_box),
             Object::YZrect(yzrect) => YZrect::boundingbox(&yzrect, _time0, _time1, output_box),
             Object::Boxx(boxx) => Boxx::boundingbox(&boxx, _time0, _time1, output_box),
-            Object::Translate(translate) => {
-                Translate::boundingbox(&translate, _time0, _time1, output_box)
+            Object::Transform(transform) => {
+                Transform::boundingbox(&transform, _time0, _time1, output_box)
+            }
+            Object::Animated(animated) => {
+                Animated::boundingbox(&animated, _time0, _time1, output_box)
             }
-            Object::RotateY(rotatey) => RotateY::boundingbox(&rotatey, _time0, _time1, output_box),
             Object::ConstantMedium(constantmedium) => {
                 ConstantMedium::boundingbox(&constantmedium, _time0, _time1, output_box)
             }
@@ -82,6 +119,18 @@ impl Boundingbox for Object {
                 Triangle::boundingbox(&triangle, _time0, _time1, output_box)
             }
             Object::Obj(obj) => Obj::boundingbox(&obj, _time0, _time1, output_box),
+            Object::Disk(disk) => Disk::boundingbox(&disk, _time0, _time1, output_box),
+            Object::Cylinder(cylinder) => {
+                Cylinder::boundingbox(&cylinder, _time0, _time1, output_box)
+            }
+            Object::Cone(cone) => Cone::boundingbox(&cone, _time0, _time1, output_box),
+            Object::Torus(torus) => Torus::boundingbox(&torus, _time0, _time1, output_box),
+            Object::Quad(quad) => Quad::boundingbox(&quad, _time0, _time1, output_box),
+            Object::Csg(csg) => Csg::boundingbox(&csg, _time0, _time1, output_box),
+            Object::Sdf(sdf) => Sdf::boundingbox(&sdf, _time0, _time1, output_box),
+            Object::Heightfield(heightfield) => {
+                Heightfield::boundingbox(&heightfield, _time0, _time1, output_box)
+            }
             _ => false,
         }
     }
@@ -98,22 +147,115 @@ impl Object {
             Object::XZrect(xzrect) => Object::XZrect(xzrect.copy()),
             Object::YZrect(yzrect) => Object::YZrect(yzrect.copy()),
             Object::Boxx(boxx) => Object::Boxx(boxx.copy()),
-            Object::Translate(translate) => Object::Translate(translate.copy()),
-            Object::RotateY(rotatey) => Object::RotateY(rotatey.copy()),
+            Object::Transform(transform) => Object::Transform(transform.copy()),
+            Object::Animated(animated) => Object::Animated(animated.copy()),
             Object::ConstantMedium(constantmedium) => Object::ConstantMedium(constantmedium.copy()),
             Object::Triangle(triangle) => Object::Triangle(triangle.copy()),
             Object::Obj(obj) => Object::Obj(obj.copy()),
+            Object::Disk(disk) => Object::Disk(disk.copy()),
+            Object::Cylinder(cylinder) => Object::Cylinder(cylinder.copy()),
+            Object::Cone(cone) => Object::Cone(cone.copy()),
+            Object::Torus(torus) => Object::Torus(torus.copy()),
+            Object::Quad(quad) => Object::Quad(quad.copy()),
+            Object::Csg(csg) => Object::Csg(csg.copy()),
+            Object::Sdf(sdf) => Object::Sdf(sdf.copy()),
+            Object::Heightfield(heightfield) => Object::Heightfield(heightfield.copy()),
+        }
+    }
+
+    // 把带发光材质、能被直接采样的物体收集到 lights 里
+    pub fn collect_lights(&self, lights: &mut Hittablelist) {
+        match self {
+            Object::Sphere(sphere) if is_emissive(&sphere.mat_ptr) => lights.add(self.copy()),
+            Object::XYrect(xyrect) if is_emissive(&xyrect.mp) => lights.add(self.copy()),
+            Object::XZrect(xzrect) if is_emissive(&xzrect.mp) => lights.add(self.copy()),
+            Object::YZrect(yzrect) if is_emissive(&yzrect.mp) => lights.add(self.copy()),
+            Object::Triangle(triangle) if is_emissive(&triangle.mat) => lights.add(self.copy()),
+            Object::Disk(disk) if is_emissive(&disk.mp) => lights.add(self.copy()),
+            Object::Quad(quad) if is_emissive(&quad.mp) => lights.add(self.copy()),
+            Object::Bvhnode(bvhnode) => bvhnode.collect_lights(lights),
+            Object::Obj(obj) => obj.obj.collect_lights(lights),
+            Object::Transform(transform) => transform.collect_lights(lights),
+            Object::Boxx(boxx) => {
+                for side in &boxx.sides().objects {
+                    side.collect_lights(lights);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // 带发光材质但没法直接采样的形状, 只能靠材质采样打到
+    pub fn is_unsampled_light(&self) -> bool {
+        match self {
+            Object::Movingsphere(movingsphere) => is_emissive(&movingsphere.mat_ptr),
+            Object::Cylinder(cylinder) => is_emissive(&cylinder.mp),
+            Object::Cone(cone) => is_emissive(&cone.mp),
+            Object::Torus(torus) => is_emissive(&torus.mp),
+            Object::Sdf(sdf) => is_emissive(&sdf.mp),
+            Object::Heightfield(heightfield) => is_emissive(&heightfield.mp),
+            _ => false,
+        }
+    }
+}
+
+fn is_emissive(mat: &Option<Arc<Material>>) -> bool {
+    match mat {
+        Some(in_mat) => in_mat.is_emissive(),
+        None => false,
+    }
+}
+
+impl Intervals for Object {
+    fn intervals(&self, r: &Ray, t_min: f64) -> Vec<Span> {
+        match self {
+            Object::Csg(csg) => csg.intervals(&r, t_min),
+            _ => walk_intervals(self, &r, t_min),
+        }
+    }
+}
+
+impl Lightsample for Object {
+    fn pdf_value(&self, o: &Point3, v: &Vec3) -> f64 {
+        match self {
+            Object::Sphere(sphere) => sphere.pdf_value(&o, &v),
+            Object::XYrect(xyrect) => xyrect.pdf_value(&o, &v),
+            Object::XZrect(xzrect) => xzrect.pdf_value(&o, &v),
+            Object::YZrect(yzrect) => yzrect.pdf_value(&o, &v),
+            Object::Triangle(triangle) => triangle.pdf_value(&o, &v),
+            Object::Disk(disk) => disk.pdf_value(&o, &v),
+            Object::Quad(quad) => quad.pdf_value(&o, &v),
+            Object::Transform(transform) => transform.pdf_value(&o, &v),
+            _ => 0.0,
+        }
+    }
+
+    fn random(&self, o: &Point3) -> Vec3 {
+        match self {
+            Object::Sphere(sphere) => sphere.random(&o),
+            Object::XYrect(xyrect) => xyrect.random(&o),
+            Object::XZrect(xzrect) => xzrect.random(&o),
+            Object::YZrect(yzrect) => yzrect.random(&o),
+            Object::Triangle(triangle) => triangle.random(&o),
+            Object::Disk(disk) => disk.random(&o),
+            Object::Quad(quad) => quad.random(&o),
+            Object::Transform(transform) => transform.random(&o),
+            _ => Vec3::new(1.0, 0.0, 0.0),
         }
     }
 }
 
 pub struct Hittablelist {
     pub objects: Vec<Object>,
+    pub bvh: Option<Flatbvh>, // build_bvh 之后求交走扁平 BVH, add/clear 会让它失效
 }
 
 impl Hittablelist {
     pub fn default_new() -> Hittablelist {
-        Hittablelist { objects: vec![] }
+        Hittablelist {
+            objects: vec![],
+            bvh: None,
+        }
     }
 
     pub fn new(obj: Object) -> Hittablelist {
@@ -124,6 +266,7 @@ impl Hittablelist {
 
     pub fn add(&mut self, obj: Object) {
         self.objects.push(obj);
+        self.bvh = None;
     }
 
     pub fn copy(&self) -> Hittablelist {
@@ -131,16 +274,35 @@ impl Hittablelist {
         for obj in &self.objects {
             res.add(obj.copy());
         }
+        res.bvh = self.bvh.as_ref().map(|bvh| bvh.copy());
         res
     }
 
     pub fn clear(&mut self) {
         self.objects.clear();
+        self.bvh = None;
+    }
+
+    // 会按叶子顺序重排 objects
+    pub fn build_bvh(&mut self, time0: f64, time1: f64, split: Bvhsplit) {
+        self.bvh = Some(Flatbvh::build(&mut self.objects, time0, time1, split));
+    }
+
+    pub fn lights(&self) -> Hittablelist {
+        let mut res = Hittablelist::default_new();
+        for obj in &self.objects {
+            obj.collect_lights(&mut res);
+        }
+        res
     }
 }
 
 impl Hit for Hittablelist {
     fn hit(&self, r: &Ray, t_min: f64, t_max: f64, rec: &mut Hitrecord) -> bool {
+        if let Some(bvh) = &self.bvh {
+            return bvh.hit(&self.objects, &r, t_min, t_max, rec);
+        }
+
         let mut temp_rec = Hitrecord::default_new();
         let mut hit_anything = false;
         let mut closest_so_far = t_max;
@@ -181,3 +343,23 @@ impl Boundingbox for Hittablelist {
         true
     }
 }
+
+// 在所有光源里均匀选一个再采样
+impl Lightsample for Hittablelist {
+    fn pdf_value(&self, o: &Point3, v: &Vec3) -> f64 {
+        if self.objects.is_empty() {
+            return 0.0;
+        }
+        let weight = 1.0 / self.objects.len() as f64;
+        let mut sum = 0.0;
+        for object in &self.objects {
+            sum += weight * object.pdf_value(&o, &v);
+        }
+        sum
+    }
+
+    fn random(&self, o: &Point3) -> Vec3 {
+        let size = self.objects.len() as i32;
+        self.objects[rand::random_int_between(0, size - 1) as usize].random(&o)
+    }
+}
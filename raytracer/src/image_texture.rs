@@ -5,34 +5,41 @@
 extern crate image;
 
 pub use crate::color;
+pub use crate::error::Error;
 pub use crate::vec3::Color;
 pub use crate::vec3::Point3;
 pub use crate::vec3::Vec3;
 pub use crate::Value;
 pub use image::{imageops, DynamicImage, GenericImage, GenericImageView, ImageBuffer, RgbImage};
+use std::sync::Arc;
 const BYTES_PER_PIXEL: i32 = 3;
 
 pub struct Imagetexture {
-    data: RgbImage,
+    data: Arc<RgbImage>, // 像素数据只读, copy 时共享
     width: i32,
     height: i32,
     bytes_per_scanline: i32,
 }
 
 impl Imagetexture {
-    pub fn new(filename: &str) -> Imagetexture {
-        let img = image::open(filename).unwrap().to_rgb8();
+    pub fn new(filename: &str) -> Result<Imagetexture, Error> {
+        let img = image::open(filename)
+            .map_err(|e| Error::Image {
+                path: filename.to_string(),
+                source: e,
+            })?
+            .to_rgb8();
         let (width, height) = img.dimensions();
         let width = width as i32;
         let height = height as i32;
         let bytes_per_scanline = BYTES_PER_PIXEL * width;
 
-        Imagetexture {
-            data: img,
+        Ok(Imagetexture {
+            data: Arc::new(img),
             width,
             height,
             bytes_per_scanline,
-        }
+        })
     }
 
     pub fn copy(&self) -> Imagetexture {
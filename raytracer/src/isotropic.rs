@@ -5,6 +5,7 @@ pub use crate::hittable::Hitrecord;
 pub use crate::hittable_list::Hittablelist;
 pub use crate::hittable_list::Object;
 pub use crate::material::Scatter;
+pub use crate::material::Scatterrecord;
 pub use crate::movingsphere::Movingsphere;
 pub use crate::ray::Ray;
 pub use crate::solidcolor::Solidcolor;
@@ -14,53 +15,55 @@ pub use crate::texture::Value;
 pub use crate::vec3::Color;
 pub use crate::vec3::Point3;
 pub use crate::vec3::Vec3;
+pub use std::f64::consts::PI;
+use std::sync::Arc;
 
 pub struct Isotropic {
-    albedo: Option<Box<Texture>>,
+    albedo: Option<Arc<Texture>>,
 }
 
 impl Scatter for Isotropic {
-    fn scatter(
-        &self,
-        r_in: &Ray,
-        rec: &Hitrecord,
-        attenuation: &mut Color,
-        scattered: &mut Ray,
-    ) -> bool {
-        *scattered = Ray::new(&rec.p.copy(), &Vec3::random_in_unit_sphere(), r_in.time());
-        *attenuation = match &self.albedo {
+    fn scatter(&self, r_in: &Ray, rec: &Hitrecord, srec: &mut Scatterrecord) -> bool {
+        srec.scattered = Ray::new(&rec.p.copy(), &Vec3::random_unit_vector(), r_in.time());
+        srec.attenuation = match &self.albedo {
             Some(in_albedo) => in_albedo.value(rec.u, rec.v, &rec.p),
             None => Color::new(0.0, 0.0, 0.0),
         };
+        srec.pdf = 1.0 / (4.0 * PI);
+        srec.is_specular = false;
         true
     }
+
+    // 各向同性, 整个球面均匀
+    fn scattering_pdf(&self, r_in: &Ray, rec: &Hitrecord, scattered: &Ray) -> f64 {
+        1.0 / (4.0 * PI)
+    }
+
+    fn eval(&self, r_in: &Ray, rec: &Hitrecord, scattered: &Ray) -> Color {
+        let albedo = match &self.albedo {
+            Some(in_albedo) => in_albedo.value(rec.u, rec.v, &rec.p),
+            None => Color::new(0.0, 0.0, 0.0),
+        };
+        albedo / (4.0 * PI)
+    }
 }
 
 impl Isotropic {
     pub fn new_from_color(a: &Color) -> Isotropic {
         Isotropic {
-            albedo: Some(Box::new(Texture::Solidcolor(Solidcolor::new_from_color(
+            albedo: Some(Arc::new(Texture::Solidcolor(Solidcolor::new_from_color(
                 &a,
             )))),
         }
     }
 
-    pub fn new_from_ptr(a: &Option<Box<Texture>>) -> Isotropic {
-        Isotropic {
-            albedo: a
-                .as_ref()
-                .map(|in_a| Box::new(in_a.copy()))
-                .map(|in_a| Box::new(in_a.copy())),
-        }
+    pub fn new_from_ptr(a: &Option<Arc<Texture>>) -> Isotropic {
+        Isotropic { albedo: a.clone() }
     }
 
     pub fn copy(&self) -> Isotropic {
         Isotropic {
-            albedo: self
-                .albedo
-                .as_ref()
-                .map(|in_albedo| Box::new(in_albedo.copy()))
-                .map(|in_albedo| Box::new(in_albedo.copy())),
+            albedo: self.albedo.clone(),
         }
     }
 }
@@ -5,7 +5,9 @@ pub use crate::hittable::Hitrecord;
 pub use crate::hittable_list::Hittablelist;
 pub use crate::hittable_list::Object;
 pub use crate::material::Scatter;
+pub use crate::material::Scatterrecord;
 pub use crate::movingsphere::Movingsphere;
+pub use crate::onb::Onb;
 pub use crate::ray::Ray;
 pub use crate::solidcolor::Solidcolor;
 pub use crate::sphere::Sphere;
@@ -14,59 +16,63 @@ pub use crate::texture::Value;
 pub use crate::vec3::Color;
 pub use crate::vec3::Point3;
 pub use crate::vec3::Vec3;
+pub use std::f64::consts::PI;
+use std::sync::Arc;
 
 pub struct Lambertian {
-    pub albedo: Option<Box<Texture>>,
+    pub albedo: Option<Arc<Texture>>,
 }
 
 impl Scatter for Lambertian {
-    fn scatter(
-        &self,
-        r_in: &Ray,
-        rec: &Hitrecord,
-        attenuation: &mut Color,
-        scattered: &mut Ray,
-    ) -> bool {
-        let mut scatter_direction = rec.normal.copy() + Vec3::random_unit_vector();
+    fn scatter(&self, r_in: &Ray, rec: &Hitrecord, srec: &mut Scatterrecord) -> bool {
+        // 按 cos 加权采样半球
+        let uvw = Onb::build_from_w(&rec.normal);
+        let scatter_direction = uvw.local_vec(&Vec3::random_cosine_direction());
 
-        if scatter_direction.near_zero() {
-            scatter_direction = rec.normal.copy();
+        srec.scattered = Ray::new(&rec.p.copy(), &scatter_direction, r_in.time());
+        srec.attenuation = self.albedo(&rec);
+        srec.pdf = Vec3::dot(&uvw.w(), &scatter_direction.unit_vector()) / PI;
+        srec.is_specular = false;
+        true
+    }
+
+    fn scattering_pdf(&self, r_in: &Ray, rec: &Hitrecord, scattered: &Ray) -> f64 {
+        let cosine = Vec3::dot(&rec.normal, &scattered.direction().unit_vector());
+        if cosine < 0.0 {
+            0.0
+        } else {
+            cosine / PI
         }
+    }
 
-        *scattered = Ray::new(&rec.p.copy(), &scatter_direction.copy(), r_in.time());
-        *attenuation = match &self.albedo {
-            Some(in_albedo) => in_albedo.value(rec.u, rec.v, &rec.p),
-            None => Color::new(0.0, 0.0, 0.0),
-        };
-        true
+    fn eval(&self, r_in: &Ray, rec: &Hitrecord, scattered: &Ray) -> Color {
+        self.albedo(&rec) * self.scattering_pdf(&r_in, &rec, &scattered)
     }
 }
 
 impl Lambertian {
     pub fn new(a: &Color) -> Lambertian {
         Lambertian {
-            albedo: Some(Box::new(Texture::Solidcolor(Solidcolor::new_from_color(
+            albedo: Some(Arc::new(Texture::Solidcolor(Solidcolor::new_from_color(
                 &a,
             )))),
         }
     }
 
-    pub fn new_from_ptr(a: &Option<Box<Texture>>) -> Lambertian {
-        Lambertian {
-            albedo: a
-                .as_ref()
-                .map(|in_a| Box::new(in_a.copy()))
-                .map(|in_a| Box::new(in_a.copy())),
+    pub fn new_from_ptr(a: &Option<Arc<Texture>>) -> Lambertian {
+        Lambertian { albedo: a.clone() }
+    }
+
+    fn albedo(&self, rec: &Hitrecord) -> Color {
+        match &self.albedo {
+            Some(in_albedo) => in_albedo.value(rec.u, rec.v, &rec.p),
+            None => Color::new(0.0, 0.0, 0.0),
         }
     }
 
     pub fn copy(&self) -> Lambertian {
         Lambertian {
-            albedo: self
-                .albedo
-                .as_ref()
-                .map(|in_albedo| Box::new(in_albedo.copy()))
-                .map(|in_albedo| Box::new(in_albedo.copy())),
+            albedo: self.albedo.clone(),
         }
     }
 }
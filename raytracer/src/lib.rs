@@ -0,0 +1,129 @@
+#![allow(non_snake_case)]
+pub mod aabb;
+pub mod animation;
+pub mod boxx;
+pub mod bvhnode;
+pub mod camera;
+pub mod checker_texture;
+pub mod color;
+pub mod cone;
+pub mod constant_medium;
+pub mod csg;
+pub mod cylinder;
+pub mod density;
+pub mod dielectric;
+pub mod diffuse_light;
+pub mod disk;
+pub mod error;
+pub mod flatbvh;
+pub mod func;
+pub mod gltf;
+pub mod heightfield;
+pub mod henyey_greenstein;
+pub mod hittable;
+pub mod hittable_list;
+pub mod image_texture;
+pub mod isotropic;
+pub mod lambertian;
+pub mod material;
+pub mod matrix;
+pub mod metal;
+pub mod movingsphere;
+pub mod noise_texture;
+pub mod obj;
+pub mod obj_texture;
+pub mod onb;
+pub mod perlin;
+pub mod ply;
+pub mod quad;
+pub mod rand;
+pub mod ray;
+pub mod rect;
+pub mod render;
+pub mod scene;
+pub mod sdf;
+pub mod solidcolor;
+pub mod sphere;
+pub mod texture;
+pub mod torus;
+pub mod transform;
+pub mod triangle;
+pub mod vec3;
+pub mod vertex_color;
+
+pub use crate::aabb::Aabb;
+pub use crate::animation::Animated;
+pub use crate::animation::Interpolation;
+pub use crate::animation::Keyframe;
+pub use crate::boxx::Boxx;
+pub use crate::bvhnode::Bvhnode;
+pub use crate::bvhnode::Bvhsplit;
+pub use crate::bvhnode::Bvhstats;
+pub use crate::camera::Camera;
+pub use crate::camera::Camerakey;
+pub use crate::camera::Cameratrack;
+pub use crate::checker_texture::Checkertexture;
+pub use crate::cone::Cone;
+pub use crate::constant_medium::ConstantMedium;
+pub use crate::csg::Csg;
+pub use crate::csg::Csgop;
+pub use crate::cylinder::Cylinder;
+pub use crate::density::Density;
+pub use crate::dielectric::Dielectric;
+pub use crate::diffuse_light::Diffuselight;
+pub use crate::disk::Disk;
+pub use crate::error::Error;
+pub use crate::flatbvh::Flatbvh;
+pub use crate::gltf::Gltf;
+pub use crate::gltf::Gltfcamera;
+pub use crate::heightfield::Heightfield;
+pub use crate::henyey_greenstein::Henyeygreenstein;
+pub use crate::hittable::Boundingbox;
+pub use crate::hittable::Hit;
+pub use crate::hittable::Hitrecord;
+pub use crate::hittable::Intervals;
+pub use crate::hittable::Lightsample;
+pub use crate::hittable::Span;
+pub use crate::hittable_list::Hittablelist;
+pub use crate::hittable_list::Object;
+pub use crate::image_texture::Imagetexture;
+pub use crate::isotropic::Isotropic;
+pub use crate::lambertian::Lambertian;
+pub use crate::material::Emitted;
+pub use crate::material::Material;
+pub use crate::material::Scatter;
+pub use crate::material::Scatterrecord;
+pub use crate::matrix::Matrix4;
+pub use crate::metal::Metal;
+pub use crate::movingsphere::Movingsphere;
+pub use crate::noise_texture::Noisetexture;
+pub use crate::obj::Obj;
+pub use crate::obj_texture::Objtexture;
+pub use crate::onb::Onb;
+pub use crate::perlin::Perlin;
+pub use crate::ply::Ply;
+pub use crate::quad::Quad;
+pub use crate::ray::Ray;
+pub use crate::rect::XYrect;
+pub use crate::rect::XZrect;
+pub use crate::rect::YZrect;
+pub use crate::render::frame_path;
+pub use crate::render::render;
+pub use crate::render::save;
+pub use crate::render::Image;
+pub use crate::render::RenderSettings;
+pub use crate::render::Renderer;
+pub use crate::scene::Scene;
+pub use crate::sdf::Sdf;
+pub use crate::sdf::Sdfshape;
+pub use crate::solidcolor::Solidcolor;
+pub use crate::sphere::Sphere;
+pub use crate::texture::Texture;
+pub use crate::texture::Value;
+pub use crate::torus::Torus;
+pub use crate::transform::Transform;
+pub use crate::triangle::Triangle;
+pub use crate::vec3::Color;
+pub use crate::vec3::Point3;
+pub use crate::vec3::Vec3;
+pub use crate::vertex_color::Vertexcolor;
@@ -1,6 +1,7 @@
 pub use crate::camera::Camera;
 pub use crate::dielectric::Dielectric;
 pub use crate::diffuse_light::Diffuselight;
+pub use crate::henyey_greenstein::Henyeygreenstein;
 pub use crate::hittable::Hit;
 pub use crate::hittable::Hitrecord;
 pub use crate::hittable_list::Hittablelist;
@@ -20,42 +21,87 @@ pub enum Material {
     Dielectric(Dielectric),
     Diffuselight(Diffuselight),
     Isotropic(Isotropic),
+    Henyeygreenstein(Henyeygreenstein),
+}
+
+pub struct Scatterrecord {
+    pub scattered: Ray,
+    pub attenuation: Color, // BSDF * cos / pdf, 即路径权重要乘的值
+    pub pdf: f64,
+    pub is_specular: bool, // 镜面 (delta 分布) 不能做光源采样和 MIS
+}
+
+impl Scatterrecord {
+    pub fn default_new() -> Scatterrecord {
+        Scatterrecord {
+            scattered: Ray::default_new(),
+            attenuation: Color::default_new(),
+            pdf: 0.0,
+            is_specular: false,
+        }
+    }
 }
 
 pub trait Scatter {
-    fn scatter(
-        &self,
-        r_in: &Ray,
-        rec: &Hitrecord,
-        attenuation: &mut Color,
-        scattered: &mut Ray,
-    ) -> bool;
+    // 按材质自己的分布采样一个出射方向
+    fn scatter(&self, r_in: &Ray, rec: &Hitrecord, srec: &mut Scatterrecord) -> bool;
+    // 给定出射方向, scatter 采样到它的概率密度
+    fn scattering_pdf(&self, r_in: &Ray, rec: &Hitrecord, scattered: &Ray) -> f64;
+    // 给定出射方向, BSDF * cos
+    fn eval(&self, r_in: &Ray, rec: &Hitrecord, scattered: &Ray) -> Color;
 }
 
 impl Scatter for Material {
-    fn scatter(
-        &self,
-        r_in: &Ray,
-        rec: &Hitrecord,
-        attenuation: &mut Color,
-        scattered: &mut Ray,
-    ) -> bool {
+    fn scatter(&self, r_in: &Ray, rec: &Hitrecord, srec: &mut Scatterrecord) -> bool {
+        match &self {
+            Material::Lambertian(lambertian) => Lambertian::scatter(&lambertian, &r_in, &rec, srec),
+            Material::Metal(metal) => Metal::scatter(&metal, &r_in, &rec, srec),
+            Material::Dielectric(dielectric) => Dielectric::scatter(&dielectric, &r_in, &rec, srec),
+            Material::Diffuselight(diffuse_light) => {
+                Diffuselight::scatter(&diffuse_light, &r_in, &rec, srec)
+            }
+            Material::Isotropic(isotropic) => Isotropic::scatter(&isotropic, &r_in, &rec, srec),
+            Material::Henyeygreenstein(hg) => Henyeygreenstein::scatter(&hg, &r_in, &rec, srec),
+        }
+    }
+
+    fn scattering_pdf(&self, r_in: &Ray, rec: &Hitrecord, scattered: &Ray) -> f64 {
         match &self {
             Material::Lambertian(lambertian) => {
-                Lambertian::scatter(&lambertian, &r_in, &rec, attenuation, scattered)
+                Lambertian::scattering_pdf(&lambertian, &r_in, &rec, &scattered)
             }
-            Material::Metal(metal) => Metal::scatter(&metal, &r_in, &rec, attenuation, scattered),
+            Material::Metal(metal) => Metal::scattering_pdf(&metal, &r_in, &rec, &scattered),
             Material::Dielectric(dielectric) => {
-                Dielectric::scatter(&dielectric, &r_in, &rec, attenuation, scattered)
+                Dielectric::scattering_pdf(&dielectric, &r_in, &rec, &scattered)
             }
             Material::Diffuselight(diffuse_light) => {
-                Diffuselight::scatter(&diffuse_light, &r_in, &rec, attenuation, scattered)
+                Diffuselight::scattering_pdf(&diffuse_light, &r_in, &rec, &scattered)
             }
             Material::Isotropic(isotropic) => {
-                Isotropic::scatter(&isotropic, &r_in, &rec, attenuation, scattered)
+                Isotropic::scattering_pdf(&isotropic, &r_in, &rec, &scattered)
+            }
+            Material::Henyeygreenstein(hg) => {
+                Henyeygreenstein::scattering_pdf(&hg, &r_in, &rec, &scattered)
             }
         }
     }
+
+    fn eval(&self, r_in: &Ray, rec: &Hitrecord, scattered: &Ray) -> Color {
+        match &self {
+            Material::Lambertian(lambertian) => {
+                Lambertian::eval(&lambertian, &r_in, &rec, &scattered)
+            }
+            Material::Metal(metal) => Metal::eval(&metal, &r_in, &rec, &scattered),
+            Material::Dielectric(dielectric) => {
+                Dielectric::eval(&dielectric, &r_in, &rec, &scattered)
+            }
+            Material::Diffuselight(diffuse_light) => {
+                Diffuselight::eval(&diffuse_light, &r_in, &rec, &scattered)
+            }
+            Material::Isotropic(isotropic) => Isotropic::eval(&isotropic, &r_in, &rec, &scattered),
+            Material::Henyeygreenstein(hg) => Henyeygreenstein::eval(&hg, &r_in, &rec, &scattered),
+        }
+    }
 }
 
 impl Material {
@@ -66,8 +112,13 @@ impl Material {
             Material::Dielectric(dielectric) => Material::Dielectric(dielectric.copy()),
             Material::Diffuselight(diffuse_light) => Material::Diffuselight(diffuse_light.copy()),
             Material::Isotropic(isotropic) => Material::Isotropic(isotropic.copy()),
+            Material::Henyeygreenstein(hg) => Material::Henyeygreenstein(hg.copy()),
         }
     }
+
+    pub fn is_emissive(&self) -> bool {
+        matches!(self, Material::Diffuselight(_))
+    }
 }
 
 pub trait Emitted {
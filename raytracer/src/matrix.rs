@@ -0,0 +1,200 @@
+#![allow(clippy::needless_range_loop)]
+#![allow(clippy::many_single_char_names)]
+pub use crate::func;
+pub use crate::vec3::Point3;
+pub use crate::vec3::Vec3;
+use std::ops::Mul;
+
+// 4x4 仿射变换矩阵, 作用在列向量上: 点的 w = 1, 方向的 w = 0
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Matrix4 {
+    pub m: [[f64; 4]; 4],
+}
+
+impl Matrix4 {
+    pub fn new(m: [[f64; 4]; 4]) -> Matrix4 {
+        Matrix4 { m }
+    }
+
+    pub fn identity() -> Matrix4 {
+        let mut m = [[0.0; 4]; 4];
+        for i in 0..4 {
+            m[i][i] = 1.0;
+        }
+        Matrix4 { m }
+    }
+
+    pub fn translate(offset: &Vec3) -> Matrix4 {
+        let mut res = Matrix4::identity();
+        for i in 0..3 {
+            res.m[i][3] = offset[i];
+        }
+        res
+    }
+
+    pub fn scale(factor: &Vec3) -> Matrix4 {
+        let mut res = Matrix4::identity();
+        for i in 0..3 {
+            res.m[i][i] = factor[i];
+        }
+        res
+    }
+
+    // 绕坐标轴转 angle 度, 从轴的正方向看过去是逆时针
+    pub fn rotate_x(angle: f64) -> Matrix4 {
+        let (sin_theta, cos_theta) = func::degrees_to_radians(angle).sin_cos();
+        Matrix4::new([
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, cos_theta, -sin_theta, 0.0],
+            [0.0, sin_theta, cos_theta, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn rotate_y(angle: f64) -> Matrix4 {
+        let (sin_theta, cos_theta) = func::degrees_to_radians(angle).sin_cos();
+        Matrix4::new([
+            [cos_theta, 0.0, sin_theta, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [-sin_theta, 0.0, cos_theta, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn rotate_z(angle: f64) -> Matrix4 {
+        let (sin_theta, cos_theta) = func::degrees_to_radians(angle).sin_cos();
+        Matrix4::new([
+            [cos_theta, -sin_theta, 0.0, 0.0],
+            [sin_theta, cos_theta, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    // 单位四元数 (x, y, z, w) 表示的旋转
+    pub fn rotate_quaternion(q: &[f64; 4]) -> Matrix4 {
+        let (x, y, z, w) = (q[0], q[1], q[2], q[3]);
+        Matrix4::new([
+            [
+                1.0 - 2.0 * (y * y + z * z),
+                2.0 * (x * y - z * w),
+                2.0 * (x * z + y * w),
+                0.0,
+            ],
+            [
+                2.0 * (x * y + z * w),
+                1.0 - 2.0 * (x * x + z * z),
+                2.0 * (y * z - x * w),
+                0.0,
+            ],
+            [
+                2.0 * (x * z - y * w),
+                2.0 * (y * z + x * w),
+                1.0 - 2.0 * (x * x + y * y),
+                0.0,
+            ],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    // 把物体放到 from, 并让它的 +z 轴朝向 to, +y 轴尽量靠近 vup
+    pub fn look_at(from: &Point3, to: &Point3, vup: &Vec3) -> Matrix4 {
+        let w = (to.copy() - from.copy()).unit_vector();
+        let u = Vec3::cross(&vup, &w).unit_vector();
+        let v = Vec3::cross(&w, &u);
+        Matrix4::new([
+            [u.x(), v.x(), w.x(), from.x()],
+            [u.y(), v.y(), w.y(), from.y()],
+            [u.z(), v.z(), w.z(), from.z()],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn transpose(&self) -> Matrix4 {
+        let mut m = [[0.0; 4]; 4];
+        for i in 0..4 {
+            for j in 0..4 {
+                m[i][j] = self.m[j][i];
+            }
+        }
+        Matrix4 { m }
+    }
+
+    // 高斯-约当消元求逆, 奇异矩阵返回 None;
+    // 主元相对这一列原来最大的元素判断是不是 0, 整体缩得很小的矩阵照样可逆
+    pub fn inverse(&self) -> Option<Matrix4> {
+        let mut a = self.m;
+        let mut inv = Matrix4::identity().m;
+        for col in 0..4 {
+            let magnitude = (0..4).fold(0.0_f64, |acc, row| acc.max(self.m[row][col].abs()));
+            let mut pivot = col;
+            for row in col + 1..4 {
+                if a[row][col].abs() > a[pivot][col].abs() {
+                    pivot = row;
+                }
+            }
+            if a[pivot][col].abs() <= 1e-12 * magnitude {
+                return None;
+            }
+            a.swap(col, pivot);
+            inv.swap(col, pivot);
+
+            let scale = 1.0 / a[col][col];
+            for j in 0..4 {
+                a[col][j] *= scale;
+                inv[col][j] *= scale;
+            }
+            for row in 0..4 {
+                if row != col {
+                    let factor = a[row][col];
+                    for j in 0..4 {
+                        a[row][j] -= factor * a[col][j];
+                        inv[row][j] -= factor * inv[col][j];
+                    }
+                }
+            }
+        }
+        Some(Matrix4 { m: inv })
+    }
+
+    // 左上 3x3 的行列式, 即线性部分对体积的缩放
+    pub fn determinant3(&self) -> f64 {
+        let m = &self.m;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+
+    pub fn point(&self, p: &Point3) -> Point3 {
+        let mut res = Point3::default_new();
+        for i in 0..3 {
+            res[i] = self.m[i][0] * p[0] + self.m[i][1] * p[1] + self.m[i][2] * p[2] + self.m[i][3];
+        }
+        res
+    }
+
+    pub fn vector(&self, v: &Vec3) -> Vec3 {
+        let mut res = Vec3::default_new();
+        for i in 0..3 {
+            res[i] = self.m[i][0] * v[0] + self.m[i][1] * v[1] + self.m[i][2] * v[2];
+        }
+        res
+    }
+}
+
+// a * b: 先做 b 再做 a
+impl Mul for Matrix4 {
+    type Output = Matrix4;
+
+    fn mul(self, rhs: Matrix4) -> Matrix4 {
+        let mut m = [[0.0; 4]; 4];
+        for i in 0..4 {
+            for j in 0..4 {
+                for k in 0..4 {
+                    m[i][j] += self.m[i][k] * rhs.m[k][j];
+                }
+            }
+        }
+        Matrix4 { m }
+    }
+}
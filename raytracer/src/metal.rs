@@ -4,6 +4,7 @@ pub use crate::hittable::Hitrecord;
 pub use crate::hittable_list::Hittablelist;
 pub use crate::hittable_list::Object;
 pub use crate::material::Scatter;
+pub use crate::material::Scatterrecord;
 pub use crate::movingsphere::Movingsphere;
 pub use crate::ray::Ray;
 pub use crate::sphere::Sphere;
@@ -17,21 +18,25 @@ pub struct Metal {
 }
 
 impl Scatter for Metal {
-    fn scatter(
-        &self,
-        r_in: &Ray,
-        rec: &Hitrecord,
-        attenuation: &mut Color,
-        scattered: &mut Ray,
-    ) -> bool {
+    fn scatter(&self, r_in: &Ray, rec: &Hitrecord, srec: &mut Scatterrecord) -> bool {
         let reflected = Vec3::reflect(&Vec3::unit_vector(&r_in.direction()), &rec.normal);
-        *scattered = Ray::new(
+        srec.scattered = Ray::new(
             &rec.p,
             &(reflected + Vec3::random_in_unit_sphere() * self.fuzz),
             r_in.time(),
         );
-        *attenuation = self.albedo.copy();
-        Vec3::dot(&scattered.direction(), &rec.normal) > 0.0
+        srec.attenuation = self.albedo.copy();
+        srec.pdf = 0.0;
+        srec.is_specular = true;
+        Vec3::dot(&srec.scattered.direction(), &rec.normal) > 0.0
+    }
+
+    fn scattering_pdf(&self, _r_in: &Ray, _rec: &Hitrecord, _scattered: &Ray) -> f64 {
+        0.0
+    }
+
+    fn eval(&self, _r_in: &Ray, _rec: &Hitrecord, _scattered: &Ray) -> Color {
+        Color::new(0.0, 0.0, 0.0)
     }
 }
 
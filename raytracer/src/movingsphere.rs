@@ -7,6 +7,7 @@ pub use crate::ray::Ray;
 pub use crate::vec3::Color;
 pub use crate::vec3::Point3;
 pub use crate::vec3::Vec3;
+use std::sync::Arc;
 
 pub struct Movingsphere {
     pub center0: Point3,
@@ -14,7 +15,7 @@ pub struct Movingsphere {
     pub time0: f64,
     pub time1: f64,
     pub radius: f64,
-    pub mat_ptr: Option<Box<Material>>,
+    pub mat_ptr: Option<Arc<Material>>,
 }
 
 impl Movingsphere {
@@ -35,7 +36,7 @@ impl Movingsphere {
         _time0: f64,
         _time1: f64,
         r: f64,
-        m: &Option<Box<Material>>,
+        m: &Option<Arc<Material>>,
     ) -> Movingsphere {
         Movingsphere {
             center0: cen0.copy(),
@@ -43,10 +44,7 @@ impl Movingsphere {
             time0: _time0,
             time1: _time1,
             radius: r,
-            mat_ptr: m
-                .as_ref()
-                .map(|in_mat_ptr| Box::new(in_mat_ptr.copy()))
-                .map(|in_mat_ptr| Box::new(in_mat_ptr.copy())),
+            mat_ptr: m.clone(),
         }
     }
 
@@ -57,11 +55,7 @@ impl Movingsphere {
             time0: self.time0,
             time1: self.time1,
             radius: self.radius,
-            mat_ptr: self
-                .mat_ptr
-                .as_ref()
-                .map(|in_mat_ptr| Box::new(in_mat_ptr.copy()))
-                .map(|in_mat_ptr| Box::new(in_mat_ptr.copy())),
+            mat_ptr: self.mat_ptr.clone(),
         }
     }
 
@@ -97,11 +91,7 @@ impl Hit for Movingsphere {
         rec.p = r.at(rec.t);
         let outward_normal = (rec.p.copy() - self.center(r.time())) / self.radius;
         rec.set_face_normal(&r, &outward_normal);
-        rec.mat_ptr = self
-            .mat_ptr
-            .as_ref()
-            .map(|in_mat_ptr| Box::new(in_mat_ptr.copy()))
-            .map(|in_mat_ptr| Box::new(in_mat_ptr.copy()));
+        rec.mat_ptr = self.mat_ptr.clone();
         true
     }
 }
@@ -2,18 +2,20 @@
 #![allow(unused_variables)]
 #![allow(clippy::many_single_char_names)]
 #![allow(clippy::clone_double_ref)]
-#![allow(clippy::never_loop)]
 
 //use tobj::Material;
 
 pub use crate::aabb::Aabb;
 pub use crate::boxx::Boxx;
 pub use crate::bvhnode::Bvhnode;
+pub use crate::bvhnode::Bvhsplit;
 pub use crate::camera::Camera;
 pub use crate::checker_texture::Checkertexture;
 pub use crate::constant_medium::ConstantMedium;
 pub use crate::dielectric::Dielectric;
 pub use crate::diffuse_light::Diffuselight;
+pub use crate::error::Error;
+use crate::hittable::Boundingbox;
 pub use crate::hittable::Hit;
 pub use crate::hittable::Hitrecord;
 pub use crate::hittable_list::Hittablelist;
@@ -32,77 +34,190 @@ pub use crate::ray::Ray;
 pub use crate::rect::XYrect;
 pub use crate::rect::XZrect;
 pub use crate::rect::YZrect;
-pub use crate::rotate::RotateY;
 pub use crate::solidcolor::Solidcolor;
 pub use crate::sphere::Sphere;
 pub use crate::texture::Texture;
 pub use crate::texture::Value;
-use crate::translate::Boundingbox;
-pub use crate::translate::Translate;
 pub use crate::triangle::Triangle;
 pub use crate::vec3::Color;
 pub use crate::vec3::Point3;
 pub use crate::vec3::Vec3;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+fn vec3_at(data: &[f32], index: u32) -> Vec3 {
+    let i = (index as usize) * 3;
+    Vec3::new(data[i] as f64, data[i + 1] as f64, data[i + 2] as f64)
+}
+
+fn uv_at(data: &[f32], index: u32) -> [f64; 2] {
+    let i = (index as usize) * 2;
+    [data[i] as f64, data[i + 1] as f64]
+}
+
+// 网格建好的 BVH 只读, 复制 Obj 时共享同一棵
 pub struct Obj {
-    pub obj: Bvhnode,
+    pub obj: Arc<Bvhnode>,
 }
 
 impl Obj {
-    pub fn new(file_name: &str, mat: &Option<Box<Material>>, tm: f64, dur: f64) -> Obj {
-        let tmp_tri = tobj::load_obj(
+    pub fn new(
+        file_name: &str,
+        mat: &Option<Arc<Material>>,
+        tm: f64,
+        dur: f64,
+        split: Bvhsplit,
+    ) -> Result<Obj, Error> {
+        let (tri, mtl_mat) = tobj::load_obj(
             file_name,
             &tobj::LoadOptions {
                 single_index: false,
                 triangulate: true,
                 ..Default::default()
             },
-        );
-        assert!(tmp_tri.is_ok());
-        let (tri, _mtl_mat) = tmp_tri.expect("Failed to load OBJ file.");
+        )
+        .map_err(|e| Error::Obj {
+            path: file_name.to_string(),
+            source: e,
+        })?;
+
+        // mat 不为 None 时覆盖 MTL 里的材质, 这时 MTL 读不出来也没关系;
+        // 没有 mtllib 或者没指定材质的部分用灰色漫反射
+        let default_mat = Some(Arc::new(Material::Lambertian(Lambertian::new(
+            &Color::new(0.73, 0.73, 0.73),
+        ))));
+        let mut mtl_mats = Vec::new();
+        if mat.is_none() {
+            let mtl_mat = mtl_mat.map_err(|e| Error::Obj {
+                path: file_name.to_string(),
+                source: e,
+            })?;
+            let dir = Path::new(file_name)
+                .parent()
+                .unwrap_or_else(|| Path::new(""));
+            let mut textures = HashMap::new();
+            for m in &mtl_mat {
+                mtl_mats.push(Some(Arc::new(Obj::mtl_material(m, dir, &mut textures)?)));
+            }
+        }
 
         let mut objects = Hittablelist::default_new();
-        for (i, obj) in tri.iter().enumerate() {
-            let mut cnt = 0;
-            let mut pos = [0; 3];
+        for obj in &tri {
             let mesh = &obj.mesh;
-            for p in &mesh.indices {
-                pos[cnt] = (*p as usize) * 3;
-                cnt += 1;
-                if cnt == 3 {
-                    objects.add(Object::Triangle(Triangle::new(
-                        &[
-                            Point3::new(
-                                mesh.positions[pos[0]] as f64,
-                                mesh.positions[pos[0] + 1] as f64,
-                                mesh.positions[pos[0] + 2] as f64,
-                            ),
-                            Point3::new(
-                                mesh.positions[pos[1]] as f64,
-                                mesh.positions[pos[1] + 1] as f64,
-                                mesh.positions[pos[1] + 2] as f64,
-                            ),
-                            Point3::new(
-                                mesh.positions[pos[2]] as f64,
-                                mesh.positions[pos[2] + 1] as f64,
-                                mesh.positions[pos[2] + 2] as f64,
-                            ),
-                        ],
-                        mat.clone(),
-                    )));
-                    cnt = 0;
+            let mesh_mat = match (mat, mesh.material_id) {
+                (Some(_), _) => mat.clone(),
+                (None, Some(id)) if id < mtl_mats.len() => mtl_mats[id].clone(),
+                (None, _) => default_mat.clone(),
+            };
+            // 没有 single_index 时法线和纹理坐标有自己的下标, 和 indices 一一对应
+            let has_normals =
+                !mesh.normals.is_empty() && mesh.normal_indices.len() == mesh.indices.len();
+            let has_texcoords =
+                !mesh.texcoords.is_empty() && mesh.texcoord_indices.len() == mesh.indices.len();
+            for face in 0..mesh.indices.len() / 3 {
+                let k = face * 3;
+                let mut triangle = Triangle::new(
+                    &[
+                        vec3_at(&mesh.positions, mesh.indices[k]),
+                        vec3_at(&mesh.positions, mesh.indices[k + 1]),
+                        vec3_at(&mesh.positions, mesh.indices[k + 2]),
+                    ],
+                    &mesh_mat,
+                );
+                if has_normals {
+                    triangle = triangle.with_normals(&[
+                        vec3_at(&mesh.normals, mesh.normal_indices[k]),
+                        vec3_at(&mesh.normals, mesh.normal_indices[k + 1]),
+                        vec3_at(&mesh.normals, mesh.normal_indices[k + 2]),
+                    ]);
+                }
+                if has_texcoords {
+                    triangle = triangle.with_texcoords(&[
+                        uv_at(&mesh.texcoords, mesh.texcoord_indices[k]),
+                        uv_at(&mesh.texcoords, mesh.texcoord_indices[k + 1]),
+                        uv_at(&mesh.texcoords, mesh.texcoord_indices[k + 2]),
+                    ]);
                 }
+                objects.add(Object::Triangle(triangle));
             }
-            break;
         }
 
-        Self {
-            obj: Bvhnode::new_from_list(&mut objects, tm, dur),
+        if objects.objects.is_empty() {
+            return Err(Error::Scene(format!(
+                "obj file `{}`: no triangles",
+                file_name
+            )));
         }
+        let bvhnode = Bvhnode::build(objects, tm, dur, split)
+            .map_err(|e| Error::Scene(format!("obj file `{}`: {}", file_name, e)))?;
+        Ok(Self {
+            obj: Arc::new(bvhnode),
+        })
+    }
+
+    // MTL 材质对应到我们的材质: Ke 发光 -> Diffuselight, 透明 (d < 1 或 illum 4/6/7/9) -> Dielectric(Ni),
+    // illum 3 或只有 Ks -> Metal(Ks, Ns 越大越光滑), 其余 -> Lambertian(Kd 或 map_Kd 贴图)
+    fn mtl_material(
+        m: &tobj::Material,
+        dir: &Path,
+        textures: &mut HashMap<String, Arc<Texture>>,
+    ) -> Result<Material, Error> {
+        let color = |c: &[f32; 3]| Color::new(c[0] as f64, c[1] as f64, c[2] as f64);
+        let kd = color(&m.diffuse);
+        let ks = color(&m.specular);
+        let illum = m.illumination_model.unwrap_or(2);
+
+        let ke = m.unknown_param.get("Ke").map(|ke| {
+            let c: Vec<f64> = ke
+                .split_whitespace()
+                .filter_map(|x| x.parse().ok())
+                .collect();
+            Color::new(
+                c.first().copied().unwrap_or(0.0),
+                c.get(1).copied().unwrap_or(0.0),
+                c.get(2).copied().unwrap_or(0.0),
+            )
+        });
+        if let Some(ke) = ke {
+            if ke.length_squared() > 0.0 {
+                return Ok(Material::Diffuselight(Diffuselight::new_from_color(&ke)));
+            }
+        }
+        if m.dissolve < 1.0 || matches!(illum, 4 | 6 | 7 | 9) {
+            let ir = if m.optical_density > 1.0 {
+                m.optical_density as f64
+            } else {
+                1.5
+            };
+            return Ok(Material::Dielectric(Dielectric::new(ir)));
+        }
+        if illum == 3 || (kd.length_squared() == 0.0 && ks.length_squared() > 0.0) {
+            let fuzz = 1.0 - (m.shininess as f64 / 1000.0).min(1.0);
+            return Ok(Material::Metal(Metal::new(&ks, fuzz)));
+        }
+        // map_Kd 前面可能带 -s 之类的选项, 文件名在最后
+        let texture_name = match m.diffuse_texture.split_whitespace().last() {
+            Some(texture_name) => texture_name,
+            None => return Ok(Material::Lambertian(Lambertian::new(&kd))),
+        };
+        let path = dir.join(texture_name).to_string_lossy().into_owned();
+        let texture = match textures.get(&path) {
+            Some(texture) => texture.clone(),
+            None => {
+                let texture = Arc::new(Texture::Imagetexture(Imagetexture::new(&path)?));
+                textures.insert(path, texture.clone());
+                texture
+            }
+        };
+        Ok(Material::Lambertian(Lambertian::new_from_ptr(&Some(
+            texture,
+        ))))
     }
 
     pub fn copy(&self) -> Obj {
         Obj {
-            obj: self.obj.copy(),
+            obj: self.obj.clone(),
         }
     }
 }
@@ -5,21 +5,37 @@
 extern crate image;
 
 pub use crate::color;
+pub use crate::error::Error;
 pub use crate::vec3::Color;
 pub use crate::vec3::Point3;
 pub use crate::vec3::Vec3;
 pub use crate::Value;
 pub use image::{imageops, DynamicImage, GenericImage, GenericImageView, ImageBuffer, RgbImage};
+use std::sync::Arc;
 const BYTES_PER_PIXEL: i32 = 3;
 
 pub struct Objtexture {
-    data: RgbImage,
+    data: Arc<RgbImage>, // 像素数据只读, copy 时共享
 }
 
 impl Objtexture {
-    pub fn new(filename: &str) -> Objtexture {
-        let img = image::open(filename).unwrap().to_rgb8();
-        Objtexture { data: img }
+    pub fn new(filename: &str) -> Result<Objtexture, Error> {
+        let img = image::open(filename)
+            .map_err(|e| Error::Image {
+                path: filename.to_string(),
+                source: e,
+            })?
+            .to_rgb8();
+        Ok(Objtexture {
+            data: Arc::new(img),
+        })
+    }
+
+    // 已经解码好的图片, 比如 glTF 里内嵌的贴图
+    pub fn new_from_image(img: RgbImage) -> Objtexture {
+        Objtexture {
+            data: Arc::new(img),
+        }
     }
 
     pub fn copy(&self) -> Objtexture {
@@ -31,12 +47,19 @@ impl Objtexture {
 
 impl Value for Objtexture {
     fn value(&self, u: f64, v: f64, p: &Point3) -> Color {
-        let color_scale = 1.0 / 255.0;
+        // OBJ 的纹理坐标可以超出 [0, 1], 按重复平铺处理; v 轴朝上, 图片的行朝下
+        let (width, height) = self.data.dimensions();
+        let u = u - u.floor();
+        let v = 1.0 - (v - v.floor());
+        let i = ((u * width as f64) as u32).min(width - 1);
+        let j = ((v * height as f64) as u32).min(height - 1);
 
+        let color_scale = 1.0 / 255.0;
+        let pixel = self.data.get_pixel(i, j);
         Color::new(
-            (self.data.get_pixel(u as u32, v as u32)[0] as f64) * color_scale,
-            (self.data.get_pixel(u as u32, v as u32)[1] as f64) * color_scale,
-            (self.data.get_pixel(u as u32, v as u32)[2] as f64) * color_scale,
+            (pixel[0] as f64) * color_scale,
+            (pixel[1] as f64) * color_scale,
+            (pixel[2] as f64) * color_scale,
         )
     }
 }
@@ -0,0 +1,41 @@
+#![allow(clippy::many_single_char_names)]
+pub use crate::vec3::Vec3;
+
+// 以法线为 w 轴的正交基
+pub struct Onb {
+    pub axis: [Vec3; 3],
+}
+
+impl Onb {
+    pub fn build_from_w(n: &Vec3) -> Onb {
+        let w = n.unit_vector();
+        let a = if w.x().abs() > 0.9 {
+            Vec3::new(0.0, 1.0, 0.0)
+        } else {
+            Vec3::new(1.0, 0.0, 0.0)
+        };
+        let v = Vec3::cross(&w, &a).unit_vector();
+        let u = Vec3::cross(&w, &v);
+        Onb { axis: [u, v, w] }
+    }
+
+    pub fn u(&self) -> Vec3 {
+        self.axis[0]
+    }
+
+    pub fn v(&self) -> Vec3 {
+        self.axis[1]
+    }
+
+    pub fn w(&self) -> Vec3 {
+        self.axis[2]
+    }
+
+    pub fn local(&self, a: f64, b: f64, c: f64) -> Vec3 {
+        self.u() * a + self.v() * b + self.w() * c
+    }
+
+    pub fn local_vec(&self, a: &Vec3) -> Vec3 {
+        self.local(a.x(), a.y(), a.z())
+    }
+}
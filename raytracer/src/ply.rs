@@ -0,0 +1,358 @@
+pub use crate::bvhnode::Bvhnode;
+pub use crate::bvhnode::Bvhsplit;
+pub use crate::error::Error;
+pub use crate::hittable_list::Hittablelist;
+pub use crate::hittable_list::Object;
+pub use crate::lambertian::Lambertian;
+pub use crate::material::Material;
+pub use crate::obj::Obj;
+pub use crate::texture::Texture;
+pub use crate::triangle::Triangle;
+pub use crate::vec3::Color;
+pub use crate::vec3::Point3;
+pub use crate::vec3::Vec3;
+pub use crate::vertex_color::Vertexcolor;
+use std::fs;
+use std::sync::Arc;
+
+// 一个 .ply 文件里的三角形网格, 和 Obj 一样建成一棵 BVH
+pub struct Ply {
+    pub mesh: Obj,
+    pub triangle_count: usize,
+}
+
+#[derive(Clone, Copy)]
+enum Format {
+    Ascii,
+    LittleEndian,
+    BigEndian,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Scalar {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl Scalar {
+    fn parse(name: &str) -> Result<Scalar, String> {
+        Ok(match name {
+            "char" | "int8" => Scalar::I8,
+            "uchar" | "uint8" => Scalar::U8,
+            "short" | "int16" => Scalar::I16,
+            "ushort" | "uint16" => Scalar::U16,
+            "int" | "int32" => Scalar::I32,
+            "uint" | "uint32" => Scalar::U32,
+            "float" | "float32" => Scalar::F32,
+            "double" | "float64" => Scalar::F64,
+            _ => return Err(format!("unknown property type `{}`", name)),
+        })
+    }
+
+    fn size(self) -> usize {
+        match self {
+            Scalar::I8 | Scalar::U8 => 1,
+            Scalar::I16 | Scalar::U16 => 2,
+            Scalar::I32 | Scalar::U32 | Scalar::F32 => 4,
+            Scalar::F64 => 8,
+        }
+    }
+}
+
+enum Property {
+    Scalar(String, Scalar),
+    List(String, Scalar, Scalar), // 名字, 个数的类型, 元素的类型
+}
+
+impl Property {
+    fn name(&self) -> &str {
+        match self {
+            Property::Scalar(name, _) | Property::List(name, _, _) => name,
+        }
+    }
+}
+
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+impl Element {
+    fn property(&self, name: &str) -> Option<usize> {
+        self.properties.iter().position(|p| p.name() == name)
+    }
+}
+
+// 文件头是文本, 到 end_header 为止; 返回格式、各个 element 和数据开始的位置
+fn parse_header(data: &[u8]) -> Result<(Format, Vec<Element>, usize), String> {
+    let mut format = None;
+    let mut elements: Vec<Element> = Vec::new();
+    let mut pos = 0;
+    let mut first = true;
+    loop {
+        let end = match data[pos..].iter().position(|&b| b == b'\n') {
+            Some(end) => pos + end,
+            None => return Err(String::from("header has no end_header")),
+        };
+        let line = String::from_utf8_lossy(&data[pos..end]);
+        pos = end + 1;
+        let words: Vec<&str> = line.split_whitespace().collect();
+        if first {
+            if words != ["ply"] {
+                return Err(String::from("not a PLY file"));
+            }
+            first = false;
+            continue;
+        }
+        match words.as_slice() {
+            ["format", "ascii", _] => format = Some(Format::Ascii),
+            ["format", "binary_little_endian", _] => format = Some(Format::LittleEndian),
+            ["format", "binary_big_endian", _] => format = Some(Format::BigEndian),
+            ["element", name, count] => elements.push(Element {
+                name: name.to_string(),
+                count: count
+                    .parse()
+                    .map_err(|_| format!("invalid element count `{}`", count))?,
+                properties: Vec::new(),
+            }),
+            ["property", "list", count, item, name] => match elements.last_mut() {
+                Some(element) => element.properties.push(Property::List(
+                    name.to_string(),
+                    Scalar::parse(count)?,
+                    Scalar::parse(item)?,
+                )),
+                None => return Err(String::from("property before any element")),
+            },
+            ["property", kind, name] => match elements.last_mut() {
+                Some(element) => element
+                    .properties
+                    .push(Property::Scalar(name.to_string(), Scalar::parse(kind)?)),
+                None => return Err(String::from("property before any element")),
+            },
+            ["end_header"] => break,
+            ["comment", ..] | ["obj_info", ..] | [] => {}
+            _ => return Err(format!("invalid header line `{}`", line)),
+        }
+    }
+    match format {
+        Some(format) => Ok((format, elements, pos)),
+        None => Err(String::from("header has no format")),
+    }
+}
+
+struct Reader<'a> {
+    format: Format,
+    data: &'a [u8],
+    pos: usize,
+    tokens: std::str::SplitAsciiWhitespace<'a>, // 只有 ASCII 格式用
+}
+
+impl<'a> Reader<'a> {
+    fn new(format: Format, data: &'a [u8]) -> Result<Reader<'a>, String> {
+        let text = match format {
+            Format::Ascii => std::str::from_utf8(data).map_err(|e| e.to_string())?,
+            _ => "",
+        };
+        Ok(Reader {
+            format,
+            data,
+            pos: 0,
+            tokens: text.split_ascii_whitespace(),
+        })
+    }
+
+    fn scalar(&mut self, kind: Scalar) -> Result<f64, String> {
+        if let Format::Ascii = self.format {
+            let token = match self.tokens.next() {
+                Some(token) => token,
+                None => return Err(String::from("unexpected end of file")),
+            };
+            return token
+                .parse()
+                .map_err(|_| format!("invalid number `{}`", token));
+        }
+
+        let size = kind.size();
+        if self.pos + size > self.data.len() {
+            return Err(String::from("unexpected end of file"));
+        }
+        // 统一换成小端再解释
+        let mut b = [0u8; 8];
+        b[..size].copy_from_slice(&self.data[self.pos..self.pos + size]);
+        if let Format::BigEndian = self.format {
+            b[..size].reverse();
+        }
+        self.pos += size;
+        Ok(match kind {
+            Scalar::I8 => b[0] as i8 as f64,
+            Scalar::U8 => b[0] as f64,
+            Scalar::I16 => i16::from_le_bytes([b[0], b[1]]) as f64,
+            Scalar::U16 => u16::from_le_bytes([b[0], b[1]]) as f64,
+            Scalar::I32 => i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+            Scalar::U32 => u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+            Scalar::F32 => f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+            Scalar::F64 => f64::from_le_bytes(b),
+        })
+    }
+
+    // 一条记录里每个属性读成一个数组, 标量属性的数组只有一个元素
+    fn record(&mut self, element: &Element) -> Result<Vec<Vec<f64>>, String> {
+        let mut res = Vec::with_capacity(element.properties.len());
+        for property in &element.properties {
+            match property {
+                Property::Scalar(_, kind) => res.push(vec![self.scalar(*kind)?]),
+                Property::List(_, count, item) => {
+                    let count = self.scalar(*count)? as usize;
+                    let mut list = Vec::with_capacity(count);
+                    for _ in 0..count {
+                        list.push(self.scalar(*item)?);
+                    }
+                    res.push(list);
+                }
+            }
+        }
+        Ok(res)
+    }
+}
+
+impl Ply {
+    // mat 为 None 时, 有顶点颜色就用顶点颜色的漫反射, 没有就是灰色漫反射
+    pub fn load(
+        file_name: &str,
+        mat: &Option<Arc<Material>>,
+        tm: f64,
+        dur: f64,
+        split: Bvhsplit,
+    ) -> Result<Ply, Error> {
+        let data = fs::read(file_name).map_err(|e| Error::Io {
+            path: file_name.to_string(),
+            source: e,
+        })?;
+        Ply::parse(&data, mat, tm, dur, split).map_err(|message| Error::Ply {
+            path: file_name.to_string(),
+            message,
+        })
+    }
+
+    fn parse(
+        data: &[u8],
+        mat: &Option<Arc<Material>>,
+        tm: f64,
+        dur: f64,
+        split: Bvhsplit,
+    ) -> Result<Ply, String> {
+        let (format, elements, start) = parse_header(data)?;
+        let mut reader = Reader::new(format, &data[start..])?;
+
+        let mut positions = Vec::new();
+        let mut normals = Vec::new();
+        let mut colors = Vec::new();
+        let mut faces = Vec::new();
+        for element in &elements {
+            let property = |names: &[&str]| names.iter().find_map(|name| element.property(name));
+            match element.name.as_str() {
+                "vertex" => {
+                    let xyz = [property(&["x"]), property(&["y"]), property(&["z"])];
+                    let nxyz = [property(&["nx"]), property(&["ny"]), property(&["nz"])];
+                    let rgb = [
+                        property(&["red", "diffuse_red", "r"]),
+                        property(&["green", "diffuse_green", "g"]),
+                        property(&["blue", "diffuse_blue", "b"]),
+                    ];
+                    let (x, y, z) = match xyz {
+                        [Some(x), Some(y), Some(z)] => (x, y, z),
+                        _ => return Err(String::from("vertex has no x, y, z")),
+                    };
+                    // 整数颜色是 0..255, 浮点颜色是 0..1
+                    let color_scale = match rgb[0].map(|i| &element.properties[i]) {
+                        Some(Property::Scalar(_, Scalar::F32))
+                        | Some(Property::Scalar(_, Scalar::F64)) => 1.0,
+                        _ => 1.0 / 255.0,
+                    };
+                    for _ in 0..element.count {
+                        let r = reader.record(element)?;
+                        let at = |i: usize| r[i].first().copied().unwrap_or(0.0);
+                        positions.push(Point3::new(at(x), at(y), at(z)));
+                        if let [Some(nx), Some(ny), Some(nz)] = nxyz {
+                            normals.push(Vec3::new(at(nx), at(ny), at(nz)));
+                        }
+                        if let [Some(red), Some(green), Some(blue)] = rgb {
+                            colors.push(Color::new(at(red), at(green), at(blue)) * color_scale);
+                        }
+                    }
+                }
+                "face" => {
+                    let indices = match property(&["vertex_indices", "vertex_index"]) {
+                        Some(indices) => indices,
+                        None => return Err(String::from("face has no vertex_indices")),
+                    };
+                    for _ in 0..element.count {
+                        let mut r = reader.record(element)?;
+                        faces.push(r.swap_remove(indices));
+                    }
+                }
+                // 其它 element (比如 edge) 读过去就行
+                _ => {
+                    for _ in 0..element.count {
+                        reader.record(element)?;
+                    }
+                }
+            }
+        }
+
+        let default_mat = Some(Arc::new(Material::Lambertian(Lambertian::new(
+            &Color::new(0.73, 0.73, 0.73),
+        ))));
+        let colors = Arc::new(colors);
+        let mut objects = Hittablelist::default_new();
+        for face in &faces {
+            let face: Vec<usize> = face.iter().map(|&i| i as usize).collect();
+            if face.iter().any(|&i| i >= positions.len()) {
+                return Err(String::from("face vertex index out of range"));
+            }
+            // 多边形按扇形拆成三角形
+            for k in 1..face.len().saturating_sub(1) {
+                let v = [face[0], face[k], face[k + 1]];
+                let triangle_mat = match (mat, colors.is_empty()) {
+                    (Some(_), _) => mat.clone(),
+                    (None, true) => default_mat.clone(),
+                    // 颜色共享, 但每个三角形还是要一份小的纹理和材质记下标 (两次 Arc 分配);
+                    // 三角形不设纹理坐标, u, v 才是重心坐标
+                    (None, false) => {
+                        let texture = Texture::Vertexcolor(Vertexcolor::new(&colors, &v));
+                        Some(Arc::new(Material::Lambertian(Lambertian::new_from_ptr(
+                            &Some(Arc::new(texture)),
+                        ))))
+                    }
+                };
+                let mut triangle = Triangle::new(
+                    &[positions[v[0]], positions[v[1]], positions[v[2]]],
+                    &triangle_mat,
+                );
+                if !normals.is_empty() {
+                    triangle =
+                        triangle.with_normals(&[normals[v[0]], normals[v[1]], normals[v[2]]]);
+                }
+                objects.add(Object::Triangle(triangle));
+            }
+        }
+
+        let triangle_count = objects.objects.len();
+        if triangle_count == 0 {
+            return Err(String::from("no triangles"));
+        }
+        Ok(Ply {
+            mesh: Obj {
+                obj: Arc::new(Bvhnode::build(objects, tm, dur, split)?),
+            },
+            triangle_count,
+        })
+    }
+}
@@ -0,0 +1,121 @@
+#![allow(unused_variables)]
+pub use crate::aabb::Aabb;
+pub use crate::hittable::Boundingbox;
+pub use crate::hittable::Hit;
+pub use crate::hittable::Hitrecord;
+pub use crate::hittable::Lightsample;
+pub use crate::material::Material;
+pub use crate::rand;
+pub use crate::ray::Ray;
+pub use crate::vec3::Point3;
+pub use crate::vec3::Vec3;
+use std::sync::Arc;
+
+// 任意朝向的平行四边形: 一个角 q 加两条边 u, v, 外法线是 u x v 的方向
+pub struct Quad {
+    pub q: Point3,
+    pub u: Vec3,
+    pub v: Vec3,
+    pub mp: Option<Arc<Material>>,
+    normal: Vec3,
+    d: f64,  // 平面方程 normal . p = d
+    w: Vec3, // 求交点在 u, v 上的坐标用, n / (n . n), n = u x v
+    area: f64,
+}
+
+impl Quad {
+    pub fn new(q: &Point3, u: &Vec3, v: &Vec3, mat: &Option<Arc<Material>>) -> Quad {
+        let cross = Vec3::cross(u, v);
+        let area = cross.length();
+        // 两条边平行时面积为 0, 法线留 0, 永远打不到
+        let (normal, w) = if area > 0.0 {
+            (cross / area, cross / (area * area))
+        } else {
+            (Vec3::default_new(), Vec3::default_new())
+        };
+        Quad {
+            q: q.copy(),
+            u: u.copy(),
+            v: v.copy(),
+            mp: mat.clone(),
+            normal,
+            d: Vec3::dot(&normal, q),
+            w,
+            area,
+        }
+    }
+
+    pub fn copy(&self) -> Quad {
+        Quad::new(&self.q, &self.u, &self.v, &self.mp)
+    }
+}
+
+impl Hit for Quad {
+    fn hit(&self, r: &Ray, t_min: f64, t_max: f64, rec: &mut Hitrecord) -> bool {
+        let denom = Vec3::dot(&self.normal, &r.direction());
+        // 光线和平面平行
+        if denom.abs() < 1e-8 {
+            return false;
+        }
+        let t = (self.d - Vec3::dot(&self.normal, &r.origin())) / denom;
+        if t < t_min || t > t_max {
+            return false;
+        }
+        // 交点写成 q + alpha u + beta v, 两个系数都在 [0, 1] 里才在四边形内
+        let planar = r.at(t) - self.q;
+        let alpha = Vec3::dot(&self.w, &Vec3::cross(&planar, &self.v));
+        let beta = Vec3::dot(&self.w, &Vec3::cross(&self.u, &planar));
+        if !(0.0..=1.0).contains(&alpha) || !(0.0..=1.0).contains(&beta) {
+            return false;
+        }
+        rec.u = alpha;
+        rec.v = beta;
+        rec.t = t;
+        rec.p = r.at(t);
+        rec.set_face_normal(&r, &self.normal);
+        rec.mat_ptr = self.mp.clone();
+        true
+    }
+}
+
+impl Boundingbox for Quad {
+    fn boundingbox(&self, time0: f64, time1: f64, output_box: &mut Aabb) -> bool {
+        let corners = [
+            self.q,
+            self.q + self.u,
+            self.q + self.v,
+            self.q + self.u + self.v,
+        ];
+        let mut min = corners[0];
+        let mut max = corners[0];
+        for corner in corners.iter() {
+            for i in 0..3 {
+                min[i] = min[i].min(corner[i]);
+                max[i] = max[i].max(corner[i]);
+            }
+        }
+        // 和轴对齐矩形一样垫一点厚度, 平面贴着坐标轴时包围盒不会是扁的
+        let pad = Vec3::new(0.0001, 0.0001, 0.0001);
+        *output_box = Aabb::new(&(min - pad), &(max + pad));
+        true
+    }
+}
+
+impl Lightsample for Quad {
+    fn pdf_value(&self, o: &Point3, v: &Vec3) -> f64 {
+        let mut rec = Hitrecord::default_new();
+        if !self.hit(&Ray::new(&o, &v, 0.0), 0.001, f64::INFINITY, &mut rec) {
+            return 0.0;
+        }
+
+        let distance_squared = rec.t * rec.t * v.length_squared();
+        let cosine = (Vec3::dot(&v, &self.normal) / v.length()).abs();
+
+        distance_squared / (cosine * self.area)
+    }
+
+    fn random(&self, o: &Point3) -> Vec3 {
+        let random_point = self.q + self.u * rand::random_double() + self.v * rand::random_double();
+        random_point - o.copy()
+    }
+}
@@ -1,8 +1,18 @@
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cell::RefCell;
+
+thread_local! {
+    // 每个线程一个生成器, 设置了 seed 之后结果可复现
+    static RNG: RefCell<StdRng> = RefCell::new(StdRng::from_entropy());
+}
+
+pub fn seed(s: u64) {
+    RNG.with(|rng| *rng.borrow_mut() = StdRng::seed_from_u64(s));
+}
 
 pub fn random_double() -> f64 {
-    let mut rng = rand::thread_rng();
-    rng.gen_range(0.0..1.0)
+    RNG.with(|rng| rng.borrow_mut().gen_range(0.0..1.0))
 }
 
 pub fn random_double_between(min: f64, max: f64) -> f64 {
@@ -10,7 +20,6 @@ pub fn random_double_between(min: f64, max: f64) -> f64 {
 }
 
 pub fn random_int_between(min: i32, max: i32) -> i32 {
-    let mut rng = rand::thread_rng();
-    let num = rng.gen_range(0.0..1.0);
+    let num = random_double();
     min + ((((max - min + 1) as f64) * num) as i32)
 }
@@ -3,16 +3,19 @@
 pub use crate::aabb::Aabb;
 pub use crate::hittable::Hit;
 pub use crate::hittable::Hitrecord;
+pub use crate::hittable::Lightsample;
 pub use crate::hittable_list::Hittablelist;
 pub use crate::material::Material;
+pub use crate::rand;
 pub use crate::ray::Ray;
 pub use crate::sphere::Boundingbox;
 pub use crate::vec3::Color;
 pub use crate::vec3::Point3;
 pub use crate::vec3::Vec3;
+use std::sync::Arc;
 
 pub struct XYrect {
-    pub mp: Option<Box<Material>>,
+    pub mp: Option<Arc<Material>>,
     x0: f64,
     x1: f64,
     y0: f64,
@@ -21,7 +24,7 @@ pub struct XYrect {
 }
 
 pub struct XZrect {
-    pub mp: Option<Box<Material>>,
+    pub mp: Option<Arc<Material>>,
     x0: f64,
     x1: f64,
     z0: f64,
@@ -30,7 +33,7 @@ pub struct XZrect {
 }
 
 pub struct YZrect {
-    pub mp: Option<Box<Material>>,
+    pub mp: Option<Arc<Material>>,
     y0: f64,
     y1: f64,
     z0: f64,
@@ -51,7 +54,7 @@ impl XYrect {
     }
 
     pub fn new(
-        mat: &Option<Box<Material>>,
+        mat: &Option<Arc<Material>>,
         _x0: f64,
         _x1: f64,
         _y0: f64,
@@ -59,10 +62,7 @@ impl XYrect {
         _k: f64,
     ) -> XYrect {
         XYrect {
-            mp: mat
-                .as_ref()
-                .map(|in_mp| Box::new(in_mp.copy()))
-                .map(|in_mp| Box::new(in_mp.copy())),
+            mp: mat.clone(),
             x0: _x0,
             x1: _x1,
             y0: _y0,
@@ -73,11 +73,7 @@ impl XYrect {
 
     pub fn copy(&self) -> XYrect {
         XYrect {
-            mp: self
-                .mp
-                .as_ref()
-                .map(|in_mp| Box::new(in_mp.copy()))
-                .map(|in_mp| Box::new(in_mp.copy())),
+            mp: self.mp.clone(),
             x0: self.x0,
             x1: self.x1,
             y0: self.y0,
@@ -100,7 +96,7 @@ impl XZrect {
     }
 
     pub fn new(
-        mat: &Option<Box<Material>>,
+        mat: &Option<Arc<Material>>,
         _x0: f64,
         _x1: f64,
         _z0: f64,
@@ -108,10 +104,7 @@ impl XZrect {
         _k: f64,
     ) -> XZrect {
         XZrect {
-            mp: mat
-                .as_ref()
-                .map(|in_mp| Box::new(in_mp.copy()))
-                .map(|in_mp| Box::new(in_mp.copy())),
+            mp: mat.clone(),
             x0: _x0,
             x1: _x1,
             z0: _z0,
@@ -122,11 +115,7 @@ impl XZrect {
 
     pub fn copy(&self) -> XZrect {
         XZrect {
-            mp: self
-                .mp
-                .as_ref()
-                .map(|in_mp| Box::new(in_mp.copy()))
-                .map(|in_mp| Box::new(in_mp.copy())),
+            mp: self.mp.clone(),
             x0: self.x0,
             x1: self.x1,
             z0: self.z0,
@@ -149,7 +138,7 @@ impl YZrect {
     }
 
     pub fn new(
-        mat: &Option<Box<Material>>,
+        mat: &Option<Arc<Material>>,
         _y0: f64,
         _y1: f64,
         _z0: f64,
@@ -157,10 +146,7 @@ impl YZrect {
         _k: f64,
     ) -> YZrect {
         YZrect {
-            mp: mat
-                .as_ref()
-                .map(|in_mp| Box::new(in_mp.copy()))
-                .map(|in_mp| Box::new(in_mp.copy())),
+            mp: mat.clone(),
             y0: _y0,
             y1: _y1,
             z0: _z0,
@@ -171,11 +157,7 @@ impl YZrect {
 
     pub fn copy(&self) -> YZrect {
         YZrect {
-            mp: self
-                .mp
-                .as_ref()
-                .map(|in_mp| Box::new(in_mp.copy()))
-                .map(|in_mp| Box::new(in_mp.copy())),
+            mp: self.mp.clone(),
             y0: self.y0,
             y1: self.y1,
             z0: self.z0,
@@ -201,11 +183,7 @@ impl Hit for XYrect {
         rec.t = t;
         let outward_normal = Vec3::new(0.0, 0.0, 1.0);
         rec.set_face_normal(&r, &outward_normal);
-        rec.mat_ptr = self
-            .mp
-            .as_ref()
-            .map(|in_mat_ptr| Box::new(in_mat_ptr.copy()))
-            .map(|in_mat_ptr| Box::new(in_mat_ptr.copy()));
+        rec.mat_ptr = self.mp.clone();
         rec.p = r.at(t);
         true
     }
@@ -237,11 +215,7 @@ impl Hit for XZrect {
         rec.t = t;
         let outward_normal = Vec3::new(0.0, 1.0, 0.0);
         rec.set_face_normal(&r, &outward_normal);
-        rec.mat_ptr = self
-            .mp
-            .as_ref()
-            .map(|in_mat_ptr| Box::new(in_mat_ptr.copy()))
-            .map(|in_mat_ptr| Box::new(in_mat_ptr.copy()));
+        rec.mat_ptr = self.mp.clone();
         rec.p = r.at(t);
         true
     }
@@ -273,11 +247,7 @@ impl Hit for YZrect {
         rec.t = t;
         let outward_normal = Vec3::new(1.0, 0.0, 0.0);
         rec.set_face_normal(&r, &outward_normal);
-        rec.mat_ptr = self
-            .mp
-            .as_ref()
-            .map(|in_mat_ptr| Box::new(in_mat_ptr.copy()))
-            .map(|in_mat_ptr| Box::new(in_mat_ptr.copy()));
+        rec.mat_ptr = self.mp.clone();
         rec.p = r.at(t);
         true
     }
@@ -292,3 +262,75 @@ impl Boundingbox for YZrect {
         true
     }
 }
+
+impl Lightsample for XYrect {
+    fn pdf_value(&self, o: &Point3, v: &Vec3) -> f64 {
+        let mut rec = Hitrecord::default_new();
+        if !self.hit(&Ray::new(&o, &v, 0.0), 0.001, f64::INFINITY, &mut rec) {
+            return 0.0;
+        }
+
+        let area = (self.x1 - self.x0) * (self.y1 - self.y0);
+        let distance_squared = rec.t * rec.t * v.length_squared();
+        let cosine = (v.z() / v.length()).abs();
+
+        distance_squared / (cosine * area)
+    }
+
+    fn random(&self, o: &Point3) -> Vec3 {
+        let random_point = Point3::new(
+            rand::random_double_between(self.x0, self.x1),
+            rand::random_double_between(self.y0, self.y1),
+            self.k,
+        );
+        random_point - o.copy()
+    }
+}
+
+impl Lightsample for XZrect {
+    fn pdf_value(&self, o: &Point3, v: &Vec3) -> f64 {
+        let mut rec = Hitrecord::default_new();
+        if !self.hit(&Ray::new(&o, &v, 0.0), 0.001, f64::INFINITY, &mut rec) {
+            return 0.0;
+        }
+
+        let area = (self.x1 - self.x0) * (self.z1 - self.z0);
+        let distance_squared = rec.t * rec.t * v.length_squared();
+        let cosine = (v.y() / v.length()).abs();
+
+        distance_squared / (cosine * area)
+    }
+
+    fn random(&self, o: &Point3) -> Vec3 {
+        let random_point = Point3::new(
+            rand::random_double_between(self.x0, self.x1),
+            self.k,
+            rand::random_double_between(self.z0, self.z1),
+        );
+        random_point - o.copy()
+    }
+}
+
+impl Lightsample for YZrect {
+    fn pdf_value(&self, o: &Point3, v: &Vec3) -> f64 {
+        let mut rec = Hitrecord::default_new();
+        if !self.hit(&Ray::new(&o, &v, 0.0), 0.001, f64::INFINITY, &mut rec) {
+            return 0.0;
+        }
+
+        let area = (self.y1 - self.y0) * (self.z1 - self.z0);
+        let distance_squared = rec.t * rec.t * v.length_squared();
+        let cosine = (v.x() / v.length()).abs();
+
+        distance_squared / (cosine * area)
+    }
+
+    fn random(&self, o: &Point3) -> Vec3 {
+        let random_point = Point3::new(
+            self.k,
+            rand::random_double_between(self.y0, self.y1),
+            rand::random_double_between(self.z0, self.z1),
+        );
+        random_point - o.copy()
+    }
+}
@@ -0,0 +1,318 @@
+pub use std::{
+    sync::{mpsc, Arc},
+    thread,
+};
+
+pub use image::{ImageBuffer, ImageOutputFormat, RgbImage};
+pub use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+
+pub use crate::error::Error;
+pub use crate::func;
+pub use crate::hittable::Hit;
+pub use crate::hittable::Hitrecord;
+pub use crate::hittable::Lightsample;
+pub use crate::hittable_list::Hittablelist;
+pub use crate::material::Emitted;
+pub use crate::material::Material;
+pub use crate::material::Scatter;
+pub use crate::material::Scatterrecord;
+pub use crate::rand;
+pub use crate::ray::Ray;
+pub use crate::scene::Scene;
+pub use crate::vec3::Color;
+
+pub type Image = RgbImage;
+
+pub fn save(img: &Image, path: &str, format: ImageOutputFormat) -> Result<(), Error> {
+    let mut file = std::fs::File::create(path).map_err(|e| Error::Io {
+        path: path.to_string(),
+        source: e,
+    })?;
+    image::DynamicImage::ImageRgb8(img.clone())
+        .write_to(&mut file, format)
+        .map_err(|e| Error::Image {
+            path: path.to_string(),
+            source: e,
+        })
+}
+
+// 动画第 frame 帧的文件名: 路径里有一串 # 时换成补零到同样位数的帧号,
+// 否则在扩展名前面加上 _0001 这样的四位帧号
+pub fn frame_path(path: &str, frame: u32) -> String {
+    if let Some(begin) = path.find('#') {
+        let width = path[begin..].chars().take_while(|&c| c == '#').count();
+        return format!(
+            "{}{:0width$}{}",
+            &path[..begin],
+            frame,
+            &path[begin + width..],
+            width = width
+        );
+    }
+    let stem_end = match path.rfind('.') {
+        Some(dot) if !path[dot..].contains('/') => dot,
+        _ => path.len(),
+    };
+    format!("{}_{:04}{}", &path[..stem_end], frame, &path[stem_end..])
+}
+
+pub struct RenderSettings {
+    pub image_width: u32,
+    pub image_height: u32,
+    pub samples_per_pixel: u32,
+    pub max_depth: i32,
+    pub rr_depth: i32, // 从第几次反弹开始俄罗斯轮盘
+    pub threads: usize,
+    pub seed: Option<u64>,
+}
+
+impl RenderSettings {
+    pub fn new(image_width: u32, image_height: u32) -> Self {
+        Self {
+            image_width,
+            image_height,
+            samples_per_pixel: 100,
+            max_depth: 100,
+            rr_depth: 5,
+            threads: 1,
+            seed: None,
+        }
+    }
+
+    pub fn aspect_ratio(&self) -> f64 {
+        self.image_width as f64 / self.image_height as f64
+    }
+}
+
+pub struct Renderer {
+    pub settings: RenderSettings,
+    pub progress: Option<Arc<MultiProgress>>,
+}
+
+impl Renderer {
+    pub fn new(settings: RenderSettings) -> Self {
+        Self {
+            settings,
+            progress: None,
+        }
+    }
+
+    // 每个线程在 progress 上加一个进度条, 渲染时会阻塞等待它们结束
+    pub fn with_progress(mut self, progress: Arc<MultiProgress>) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    pub fn render(&self, scene: &Scene) -> Image {
+        let image_width = self.settings.image_width;
+        let image_height = self.settings.image_height;
+        let samples_per_pixel = self.settings.samples_per_pixel;
+        let max_depth = self.settings.max_depth;
+        let rr_depth = self.settings.rr_depth;
+        let thread_number = self.settings.threads.max(1).min(image_height as usize);
+        // 最后一行/列正好落在画面边上; 只有一个像素时不用除以 0
+        let u_span = image_width.max(2) as f64 - 1.0;
+        let v_span = image_height.max(2) as f64 - 1.0;
+
+        let mut thread_pool = Vec::<_>::new();
+        for thread_id in 0..thread_number {
+            // 按行平均分给各个线程
+            let line_beg = (image_height as usize) * thread_id / thread_number;
+            let line_end = (image_height as usize) * (thread_id + 1) / thread_number;
+            let seed = self.settings.seed.map(|s| s.wrapping_add(thread_id as u64));
+
+            let world = Arc::clone(&scene.world);
+            let lights = Arc::clone(&scene.lights);
+            let cam = scene.camera.copy();
+            let background = scene.background;
+            let progress_bar = match &self.progress {
+                Some(mp) => {
+                    let progress_bar = mp.add(ProgressBar::new(
+                        ((line_end - line_beg) * (image_width as usize)) as u64,
+                    ));
+                    progress_bar.set_style(ProgressStyle::default_bar()
+                    .template("{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] [{pos}/{len}] ({eta})")
+                    .progress_chars("#>-"));
+                    progress_bar
+                }
+                None => ProgressBar::hidden(),
+            };
+
+            let (tx, rx) = mpsc::channel();
+
+            thread_pool.push((
+                thread::spawn(move || {
+                    if let Some(seed) = seed {
+                        rand::seed(seed);
+                    }
+                    let mut progress = 0;
+                    progress_bar.set_position(0);
+
+                    let mut section_pixel_color = Vec::<Color>::new();
+                    for y in line_beg..line_end {
+                        for x in 0..image_width {
+                            let mut pixel_color = Color::default_new();
+                            for _i in 0..samples_per_pixel {
+                                let u = (x as f64 + rand::random_double()) / u_span;
+                                let v = (y as f64 + rand::random_double()) / v_span;
+                                let ray = cam.get_ray(u, v);
+                                pixel_color += ray_color(
+                                    &ray,
+                                    &background,
+                                    &world,
+                                    &lights,
+                                    max_depth,
+                                    rr_depth,
+                                );
+                            }
+                            section_pixel_color.push(pixel_color);
+
+                            progress += 1;
+                            progress_bar.set_position(progress);
+                        }
+                    }
+                    tx.send(section_pixel_color).unwrap();
+                    progress_bar.finish_with_message("Finished.");
+                }),
+                rx,
+            ));
+        }
+        // 等待所有线程结束
+        if let Some(mp) = &self.progress {
+            mp.join().unwrap();
+        }
+
+        // join 和 recv 均会阻塞主线程
+        let mut output_pixel_color = Vec::<Color>::new();
+        for (handle, rx) in thread_pool {
+            handle.join().expect("render thread panicked");
+            output_pixel_color.append(&mut rx.recv().unwrap());
+        }
+
+        let mut img: Image = ImageBuffer::new(image_width, image_height);
+        let mut pixel_id = 0;
+        for y in 0..image_height {
+            for x in 0..image_width {
+                let pixel_color = output_pixel_color[pixel_id].calc_color(samples_per_pixel);
+
+                let pixel = img.get_pixel_mut(x, image_height - y - 1);
+                *pixel = image::Rgb(pixel_color.to_u8_array());
+
+                pixel_id += 1;
+            }
+        }
+        img
+    }
+}
+
+pub fn render(scene: &Scene, settings: RenderSettings) -> Image {
+    Renderer::new(settings).render(scene)
+}
+
+// 多重重要性采样的 power heuristic (beta = 2)
+pub fn power_heuristic(pdf_f: f64, pdf_g: f64) -> f64 {
+    let f2 = pdf_f * pdf_f;
+    let g2 = pdf_g * pdf_g;
+    if f2 + g2 <= 0.0 {
+        return 0.0;
+    }
+    f2 / (f2 + g2)
+}
+
+// 迭代的路径追踪: throughput 是当前路径上各次反射权重的乘积,
+// rr_depth 次反弹之后按 throughput 做俄罗斯轮盘, max_depth 只是兜底的上限
+pub fn ray_color(
+    r: &Ray,
+    background: &Color,
+    world: &Hittablelist,
+    lights: &Hittablelist,
+    max_depth: i32,
+    rr_depth: i32,
+) -> Color {
+    let inf: f64 = 1.79769e+308;
+    let mut color = Color::new(0.0, 0.0, 0.0);
+    let mut throughput = Color::new(1.0, 1.0, 1.0);
+    let mut ray = Ray::new(&r.origin(), &r.direction(), r.time());
+    // 上一个交点按材质采样出这条光线的概率密度, 那里也做了光源采样,
+    // 所以打到的自发光要和光源采样按 MIS 分权重; None 表示相机光线或镜面反射
+    let mut bsdf_pdf: Option<f64> = None;
+
+    for depth in 0..max_depth {
+        let mut rec = Hitrecord::default_new();
+        if !world.hit(&ray, 0.001, inf, &mut rec) {
+            color += throughput * background.copy();
+            break;
+        }
+        let in_mat_ptr = match &rec.mat_ptr {
+            Some(in_mat_ptr) => in_mat_ptr,
+            None => break,
+        };
+
+        let mut emitted = in_mat_ptr.emitted(rec.u, rec.v, &rec.p.copy());
+        if let Some(bsdf_pdf) = bsdf_pdf {
+            if emitted.length_squared() > 0.0 {
+                let light_pdf = lights.pdf_value(&ray.origin(), &ray.direction());
+                emitted *= power_heuristic(bsdf_pdf, light_pdf);
+            }
+        }
+        color += throughput * emitted;
+
+        let mut srec = Scatterrecord::default_new();
+        if !in_mat_ptr.scatter(&ray, &rec, &mut srec) {
+            break;
+        }
+        if srec.is_specular || lights.objects.is_empty() {
+            bsdf_pdf = None;
+        } else {
+            color += throughput * direct_light(&ray, &rec, &in_mat_ptr, &world, &lights);
+            bsdf_pdf = Some(srec.pdf);
+        }
+        throughput = throughput * srec.attenuation;
+
+        // 俄罗斯轮盘: 以 p 的概率继续, 活下来的路径权重除以 p, 期望不变
+        if depth + 1 >= rr_depth {
+            let p = func::fmin(
+                func::fmax(throughput.x(), func::fmax(throughput.y(), throughput.z())),
+                0.95,
+            );
+            if p <= 0.0 || rand::random_double() >= p {
+                break;
+            }
+            throughput /= p;
+        }
+        ray = srec.scattered;
+    }
+
+    color
+}
+
+// 向随机一个光源上的点发 shadow ray, 和材质采样按 MIS 分权重
+fn direct_light(
+    r: &Ray,
+    rec: &Hitrecord,
+    mat: &Material,
+    world: &Hittablelist,
+    lights: &Hittablelist,
+) -> Color {
+    let to_light = Ray::new(&rec.p, &lights.random(&rec.p), r.time());
+    let light_pdf = lights.pdf_value(&rec.p, &to_light.direction());
+    if light_pdf <= 0.0 {
+        return Color::new(0.0, 0.0, 0.0);
+    }
+    let f = mat.eval(&r, &rec, &to_light);
+    if f.length_squared() <= 0.0 {
+        return Color::new(0.0, 0.0, 0.0);
+    }
+
+    let mut light_rec = Hitrecord::default_new();
+    if !world.hit(&to_light, 0.001, f64::INFINITY, &mut light_rec) {
+        return Color::new(0.0, 0.0, 0.0);
+    }
+    match &light_rec.mat_ptr {
+        Some(light_mat) => {
+            let weight = power_heuristic(light_pdf, mat.scattering_pdf(&r, &rec, &to_light));
+            light_mat.emitted(light_rec.u, light_rec.v, &light_rec.p) * f * (weight / light_pdf)
+        }
+        None => Color::new(0.0, 0.0, 0.0),
+    }
+}
@@ -0,0 +1,1491 @@
+#![allow(clippy::large_enum_variant)]
+pub use crate::animation::Animated;
+pub use crate::animation::Interpolation;
+pub use crate::animation::Keyframe;
+pub use crate::boxx::Boxx;
+pub use crate::bvhnode::Bvhnode;
+pub use crate::bvhnode::Bvhsplit;
+pub use crate::bvhnode::Bvhstats;
+pub use crate::camera::Camera;
+pub use crate::camera::Camerakey;
+pub use crate::camera::Cameratrack;
+pub use crate::checker_texture::Checkertexture;
+pub use crate::cone::Cone;
+pub use crate::constant_medium::ConstantMedium;
+pub use crate::csg::Csg;
+pub use crate::csg::Csgop;
+pub use crate::cylinder::Cylinder;
+pub use crate::density::Density;
+pub use crate::dielectric::Dielectric;
+pub use crate::diffuse_light::Diffuselight;
+pub use crate::disk::Disk;
+pub use crate::error::Error;
+pub use crate::gltf::Gltf;
+pub use crate::gltf::Gltfcamera;
+pub use crate::heightfield::Heightfield;
+pub use crate::henyey_greenstein::Henyeygreenstein;
+pub use crate::hittable_list::Hittablelist;
+pub use crate::hittable_list::Object;
+pub use crate::image_texture::Imagetexture;
+pub use crate::isotropic::Isotropic;
+pub use crate::lambertian::Lambertian;
+pub use crate::material::Material;
+pub use crate::matrix::Matrix4;
+pub use crate::metal::Metal;
+pub use crate::movingsphere::Movingsphere;
+pub use crate::noise_texture::Noisetexture;
+pub use crate::obj::Obj;
+pub use crate::obj_texture::Objtexture;
+pub use crate::perlin::Perlin;
+pub use crate::ply::Ply;
+pub use crate::quad::Quad;
+pub use crate::rect::XYrect;
+pub use crate::rect::XZrect;
+pub use crate::rect::YZrect;
+pub use crate::sdf::Sdf;
+pub use crate::sdf::Sdfshape;
+pub use crate::solidcolor::Solidcolor;
+pub use crate::sphere::Sphere;
+pub use crate::texture::Texture;
+pub use crate::torus::Torus;
+pub use crate::transform::Transform;
+pub use crate::triangle::Triangle;
+pub use crate::vec3::Color;
+pub use crate::vec3::Point3;
+pub use crate::vec3::Vec3;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer};
+use serde_json::Value as Json;
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Arc;
+use std::time::Instant;
+
+// 场景文件: camera / background / textures / materials / prototypes / objects / animation
+pub struct Scene {
+    pub world: Arc<Hittablelist>,  // 渲染线程共享同一份, 不再各自深拷贝
+    pub lights: Arc<Hittablelist>, // 带发光材质的物体, 渲染时直接对它们采样
+    pub camera: Camera,
+    pub background: Color,
+    pub bvh_stats: Vec<(String, Bvhstats)>, // 场景里每棵 BVH 的统计, 按建树顺序
+    pub animation: Option<Animation>,
+    pub warnings: Vec<String>, // 能渲染但结果可能不对的地方, 比如不会被直接采样的光源
+}
+
+// 第 n 帧的快门从 n / fps 开到 (n + shutter) / fps, 关键帧的时间也用秒
+pub struct Animation {
+    pub fps: f64,
+    pub first_frame: u32,
+    pub last_frame: u32,
+    pub shutter: f64, // 快门开着的时间占一帧的比例, 0 就没有运动模糊
+}
+
+impl Animation {
+    pub fn shutter_interval(&self, frame: u32) -> (f64, f64) {
+        (
+            frame as f64 / self.fps,
+            (frame as f64 + self.shutter) / self.fps,
+        )
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Scenefile {
+    camera: Option<Cameradesc>, // 不写就用场景里第一个 glTF 文件自带的相机
+    #[serde(default)]
+    background: [f64; 3],
+    #[serde(default)]
+    textures: HashMap<String, Texturedesc>,
+    #[serde(default)]
+    materials: HashMap<String, Materialdesc>,
+    #[serde(default)]
+    prototypes: HashMap<String, Objectdesc>, // 只建一次, 由 instance 引用
+    objects: Vec<Objectdesc>,
+    animation: Option<Animationdesc>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Animationdesc {
+    fps: f64,
+    frames: [u32; 2], // 第一帧和最后一帧, 都包括
+    #[serde(default = "default_shutter")]
+    shutter: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Cameradesc {
+    lookfrom: [f64; 3],
+    lookat: [f64; 3],
+    #[serde(default = "default_vup")]
+    vup: [f64; 3],
+    vfov: f64,
+    #[serde(default)]
+    aperture: f64,
+    #[serde(default = "default_focus_dist")]
+    focus_dist: f64,
+    #[serde(default)]
+    time0: f64,
+    #[serde(default = "default_time1")]
+    time1: f64,
+    #[serde(default)]
+    keyframes: Vec<Camerakeydesc>,
+    #[serde(default = "default_interpolation")]
+    interpolation: Interpolationdesc,
+}
+
+// 没写的参数沿用 camera 本身的
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Camerakeydesc {
+    time: f64,
+    lookfrom: [f64; 3],
+    lookat: [f64; 3],
+    vup: Option<[f64; 3]>,
+    vfov: Option<f64>,
+    aperture: Option<f64>,
+    focus_dist: Option<f64>,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+enum Interpolationdesc {
+    #[serde(rename = "linear")]
+    Linear,
+    #[serde(rename = "smooth")]
+    Smooth,
+}
+
+impl Interpolationdesc {
+    fn interpolation(self) -> Interpolation {
+        match self {
+            Interpolationdesc::Linear => Interpolation::Linear,
+            Interpolationdesc::Smooth => Interpolation::Smooth,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Keyframedesc {
+    time: f64,
+    #[serde(default)]
+    translate: [f64; 3],
+    #[serde(default)]
+    rotate: [f64; 3], // 依次绕 x、y、z 轴转的角度, 可以超过 360
+    #[serde(default = "default_scale")]
+    scale: [f64; 3],
+}
+
+// 颜色或者纹理名
+enum Colorref {
+    Color([f64; 3]),
+    Texture(String),
+}
+
+// 材质名或者内联材质
+enum Materialref {
+    Name(String),
+    Inline(Materialdesc),
+}
+
+// 下面几个 "名字或者内联" 的引用按 JSON 的类型分支, 内联部分写错时报的是里面的错误 (比如拼错的字段),
+// 而不是 untagged 的 "data did not match any variant"
+impl<'de> Deserialize<'de> for Colorref {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match Json::deserialize(deserializer)? {
+            Json::String(name) => Ok(Colorref::Texture(name)),
+            value @ Json::Array(_) => serde_json::from_value(value)
+                .map(Colorref::Color)
+                .map_err(D::Error::custom),
+            _ => Err(D::Error::custom(
+                "expected a color [r, g, b] or a texture name",
+            )),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Materialref {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match Json::deserialize(deserializer)? {
+            Json::String(name) => Ok(Materialref::Name(name)),
+            value @ Json::Object(_) => serde_json::from_value(value)
+                .map(Materialref::Inline)
+                .map_err(|e| D::Error::custom(format!("inline material: {}", e))),
+            _ => Err(D::Error::custom(
+                "expected a material name or an inline material",
+            )),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", deny_unknown_fields)]
+enum Texturedesc {
+    #[serde(rename = "solid_color")]
+    Solidcolor { color: [f64; 3] },
+    #[serde(rename = "checker")]
+    Checker { even: Colorref, odd: Colorref },
+    #[serde(rename = "noise")]
+    Noise { scale: f64 },
+    #[serde(rename = "image")]
+    Image { file: String },
+    #[serde(rename = "obj_image")]
+    Objimage { file: String },
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", deny_unknown_fields)]
+enum Materialdesc {
+    #[serde(rename = "lambertian")]
+    Lambertian { albedo: Colorref },
+    #[serde(rename = "metal")]
+    Metal { albedo: [f64; 3], fuzz: f64 },
+    #[serde(rename = "dielectric")]
+    Dielectric { ir: f64 },
+    #[serde(rename = "diffuse_light")]
+    Diffuselight { emit: Colorref },
+    #[serde(rename = "isotropic")]
+    Isotropic { albedo: Colorref },
+    #[serde(rename = "henyey_greenstein")]
+    Henyeygreenstein { albedo: Colorref, g: f64 },
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", deny_unknown_fields)]
+enum Objectdesc {
+    #[serde(rename = "sphere")]
+    Sphere {
+        center: [f64; 3],
+        radius: f64,
+        material: Materialref,
+    },
+    #[serde(rename = "moving_sphere")]
+    Movingsphere {
+        center0: [f64; 3],
+        center1: [f64; 3],
+        #[serde(default)]
+        time0: f64,
+        #[serde(default = "default_time1")]
+        time1: f64,
+        radius: f64,
+        material: Materialref,
+    },
+    #[serde(rename = "xy_rect")]
+    XYrect {
+        x0: f64,
+        x1: f64,
+        y0: f64,
+        y1: f64,
+        k: f64,
+        material: Materialref,
+    },
+    #[serde(rename = "xz_rect")]
+    XZrect {
+        x0: f64,
+        x1: f64,
+        z0: f64,
+        z1: f64,
+        k: f64,
+        material: Materialref,
+    },
+    #[serde(rename = "yz_rect")]
+    YZrect {
+        y0: f64,
+        y1: f64,
+        z0: f64,
+        z1: f64,
+        k: f64,
+        material: Materialref,
+    },
+    #[serde(rename = "quad")]
+    Quad {
+        q: [f64; 3], // 一个角, u 和 v 是从它出发的两条边
+        u: [f64; 3],
+        v: [f64; 3],
+        material: Materialref,
+    },
+    #[serde(rename = "box")]
+    Boxx {
+        p0: [f64; 3],
+        p1: [f64; 3],
+        material: Materialref,
+    },
+    #[serde(rename = "triangle")]
+    Triangle {
+        vertices: [[f64; 3]; 3],
+        material: Materialref,
+    },
+    #[serde(rename = "disk")]
+    Disk {
+        center: [f64; 3],
+        radius: f64,
+        material: Materialref,
+    },
+    #[serde(rename = "cylinder")]
+    Cylinder {
+        center: [f64; 3], // 底面圆心, 沿 +y 方向长 height
+        radius: f64,
+        height: f64,
+        #[serde(default = "default_capped")]
+        capped: bool,
+        material: Materialref,
+    },
+    #[serde(rename = "cone")]
+    Cone {
+        center: [f64; 3], // 底面圆心, 顶点在上方 height 处
+        radius: f64,
+        height: f64,
+        #[serde(default = "default_capped")]
+        capped: bool,
+        material: Materialref,
+    },
+    #[serde(rename = "torus")]
+    Torus {
+        center: [f64; 3], // 躺在 xz 平面里
+        major_radius: f64,
+        minor_radius: f64,
+        material: Materialref,
+    },
+    #[serde(rename = "heightfield")]
+    Heightfield {
+        file: String, // 灰度图, 白色最高
+        corner: [f64; 3],
+        extent: [f64; 2], // x、z 方向的范围
+        height: f64,
+        material: Materialref,
+    },
+    #[serde(rename = "obj")]
+    Obj {
+        file: String,
+        #[serde(default)]
+        material: Option<Materialref>, // 不写就用 OBJ 自带的 MTL 材质
+    },
+    #[serde(rename = "gltf")]
+    Gltf {
+        file: String,
+        #[serde(default)]
+        material: Option<Materialref>, // 不写就用 glTF 自带的材质
+    },
+    #[serde(rename = "ply")]
+    Ply {
+        file: String,
+        #[serde(default)]
+        material: Option<Materialref>, // 不写就用顶点颜色, 没有顶点颜色是灰色
+    },
+    #[serde(rename = "translate")]
+    Translate {
+        offset: [f64; 3],
+        object: Box<Objectdesc>,
+    },
+    #[serde(rename = "rotate_x")]
+    RotateX { angle: f64, object: Box<Objectdesc> },
+    #[serde(rename = "rotate_y")]
+    RotateY { angle: f64, object: Box<Objectdesc> },
+    #[serde(rename = "rotate_z")]
+    RotateZ { angle: f64, object: Box<Objectdesc> },
+    #[serde(rename = "scale")]
+    Scale {
+        factor: [f64; 3],
+        object: Box<Objectdesc>,
+    },
+    #[serde(rename = "transform")]
+    Transform {
+        steps: Vec<Transformdesc>,
+        object: Box<Objectdesc>,
+    },
+    #[serde(rename = "animated")]
+    Animated {
+        keyframes: Vec<Keyframedesc>,
+        #[serde(default = "default_interpolation")]
+        interpolation: Interpolationdesc,
+        object: Box<Objectdesc>,
+    },
+    #[serde(rename = "constant_medium")]
+    ConstantMedium {
+        boundary: Box<Objectdesc>,
+        density: Densityref,
+        albedo: Colorref, // 散射反照率, 剩下的被吸收
+        #[serde(default)]
+        g: Option<f64>, // Henyey-Greenstein 的不对称参数, 不写就是各向同性
+    },
+    #[serde(rename = "sdf")]
+    Sdf {
+        shape: Sdfdesc,
+        material: Materialref,
+    },
+    #[serde(rename = "csg")]
+    Csg {
+        op: Csgopdesc,
+        left: Box<Objectdesc>,
+        right: Box<Objectdesc>,
+    },
+    #[serde(rename = "bvh")]
+    Bvh { objects: Vec<Objectdesc> },
+    #[serde(rename = "instance")]
+    Instance {
+        prototype: String,
+        #[serde(default)]
+        offset: [f64; 3],
+        #[serde(default)]
+        rotate_y: f64,
+        #[serde(default)]
+        steps: Vec<Transformdesc>, // 在 rotate_y 和 offset 之前做
+        #[serde(default)]
+        material: Option<Materialref>,
+    },
+}
+
+// 距离场的表达式树, 叶子都以原点为中心
+#[derive(Deserialize)]
+#[serde(tag = "type", deny_unknown_fields)]
+enum Sdfdesc {
+    #[serde(rename = "sphere")]
+    Sphere { radius: f64 },
+    #[serde(rename = "box")]
+    Boxx { half_size: [f64; 3] },
+    #[serde(rename = "round_box")]
+    RoundBox { half_size: [f64; 3], radius: f64 },
+    #[serde(rename = "torus")]
+    Torus {
+        major_radius: f64,
+        minor_radius: f64,
+    },
+    #[serde(rename = "translate")]
+    Translate {
+        offset: [f64; 3],
+        shape: Box<Sdfdesc>,
+    },
+    #[serde(rename = "union")]
+    Union {
+        #[serde(default)]
+        smoothness: f64,
+        shapes: Vec<Sdfdesc>,
+    },
+    #[serde(rename = "subtraction")]
+    Subtraction {
+        #[serde(default)]
+        smoothness: f64,
+        left: Box<Sdfdesc>,
+        right: Box<Sdfdesc>,
+    },
+    #[serde(rename = "repeat")]
+    Repeat {
+        spacing: [f64; 3],
+        count: [u32; 3], // 每个方向两边各复制几份
+        shape: Box<Sdfdesc>,
+    },
+    #[serde(rename = "twist")]
+    Twist {
+        angle: f64, // 每升高 1 转多少度
+        shape: Box<Sdfdesc>,
+    },
+}
+
+// 常数密度或者密度场
+enum Densityref {
+    Constant(f64),
+    Field(Densitydesc),
+}
+
+impl<'de> Deserialize<'de> for Densityref {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match Json::deserialize(deserializer)? {
+            Json::Number(density) => Ok(Densityref::Constant(density.as_f64().unwrap_or(0.0))),
+            value @ Json::Object(_) => serde_json::from_value(value)
+                .map(Densityref::Field)
+                .map_err(|e| D::Error::custom(format!("density field: {}", e))),
+            _ => Err(D::Error::custom(
+                "expected a constant density or a density field",
+            )),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", deny_unknown_fields)]
+enum Densitydesc {
+    #[serde(rename = "noise")]
+    Noise {
+        density: f64,
+        scale: f64,
+        #[serde(default = "default_depth")]
+        depth: i32,
+        #[serde(default)]
+        threshold: f64,
+    },
+    #[serde(rename = "grid")]
+    Grid {
+        min: [f64; 3],
+        max: [f64; 3],
+        size: [usize; 3],
+        values: Vec<f64>, // x 最快、z 最慢
+        #[serde(default = "default_grid_density")]
+        density: f64, // 乘到每个值上
+    },
+    #[serde(rename = "texture")]
+    Texture { texture: Colorref, density: f64 },
+}
+
+#[derive(Deserialize, Clone, Copy)]
+enum Csgopdesc {
+    #[serde(rename = "union")]
+    Union,
+    #[serde(rename = "intersection")]
+    Intersection,
+    #[serde(rename = "difference")]
+    Difference,
+}
+
+// transform 的一步, 按列表顺序依次作用在物体上
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+enum Transformdesc {
+    #[serde(rename = "translate")]
+    Translate([f64; 3]),
+    #[serde(rename = "rotate_x")]
+    RotateX(f64),
+    #[serde(rename = "rotate_y")]
+    RotateY(f64),
+    #[serde(rename = "rotate_z")]
+    RotateZ(f64),
+    #[serde(rename = "scale")]
+    Scale([f64; 3]),
+    #[serde(rename = "look_at")]
+    Lookat {
+        from: [f64; 3],
+        to: [f64; 3],
+        #[serde(default = "default_vup")]
+        vup: [f64; 3],
+    },
+    #[serde(rename = "matrix")]
+    Matrix([[f64; 4]; 3]), // 前三行, 最后一行固定是 [0, 0, 0, 1]
+}
+
+// 物体里有没有会被 collect_lights 收集的光源
+fn emits_light(object: &Object) -> bool {
+    let mut lights = Hittablelist::default_new();
+    object.collect_lights(&mut lights);
+    !lights.objects.is_empty()
+}
+
+fn default_vup() -> [f64; 3] {
+    [0.0, 1.0, 0.0]
+}
+
+fn default_focus_dist() -> f64 {
+    10.0
+}
+
+fn default_time1() -> f64 {
+    1.0
+}
+
+fn default_capped() -> bool {
+    true
+}
+
+fn default_scale() -> [f64; 3] {
+    [1.0, 1.0, 1.0]
+}
+
+fn default_interpolation() -> Interpolationdesc {
+    Interpolationdesc::Linear
+}
+
+fn default_shutter() -> f64 {
+    0.5
+}
+
+// 关键帧至少一个, 时间严格递增
+fn check_key_times(times: &[f64]) -> Result<(), String> {
+    if times.is_empty() {
+        return Err(String::from("keyframes must not be empty"));
+    }
+    if times.iter().any(|t| !t.is_finite()) || times.windows(2).any(|w| w[0] >= w[1]) {
+        return Err(String::from("keyframe times must be strictly increasing"));
+    }
+    Ok(())
+}
+
+fn default_depth() -> i32 {
+    7
+}
+
+fn default_grid_density() -> f64 {
+    1.0
+}
+
+fn check_asymmetry(g: f64) -> Result<(), String> {
+    if g > -1.0 && g < 1.0 {
+        Ok(())
+    } else {
+        Err(String::from("g must be in (-1, 1)"))
+    }
+}
+
+fn animation(desc: &Animationdesc) -> Result<Animation, Error> {
+    if !desc.fps.is_finite() || desc.fps <= 0.0 {
+        return Err(Error::Scene(String::from("animation fps must be positive")));
+    }
+    if desc.frames[0] > desc.frames[1] {
+        return Err(Error::Scene(String::from(
+            "animation frames must be [first, last] with first <= last",
+        )));
+    }
+    if !(desc.shutter >= 0.0 && desc.shutter <= 1.0) {
+        return Err(Error::Scene(String::from(
+            "animation shutter must be in [0, 1]",
+        )));
+    }
+    Ok(Animation {
+        fps: desc.fps,
+        first_frame: desc.frames[0],
+        last_frame: desc.frames[1],
+        shutter: desc.shutter,
+    })
+}
+
+fn camera_track(cam: &Cameradesc, aspect_ratio: f64) -> Result<Cameratrack, String> {
+    check_key_times(&cam.keyframes.iter().map(|k| k.time).collect::<Vec<_>>())
+        .map_err(|e| format!("camera: {}", e))?;
+    let keys = cam
+        .keyframes
+        .iter()
+        .map(|k| Camerakey {
+            time: k.time,
+            lookfrom: vec3(&k.lookfrom),
+            lookat: vec3(&k.lookat),
+            vup: vec3(&k.vup.unwrap_or(cam.vup)),
+            vfov: k.vfov.unwrap_or(cam.vfov),
+            aperture: k.aperture.unwrap_or(cam.aperture),
+            focus_dist: k.focus_dist.unwrap_or(cam.focus_dist),
+        })
+        .collect();
+    Ok(Cameratrack::new(
+        keys,
+        cam.interpolation.interpolation(),
+        aspect_ratio,
+    ))
+}
+
+fn vec3(a: &[f64; 3]) -> Vec3 {
+    Vec3::new(a[0], a[1], a[2])
+}
+
+fn sdf_shape(desc: &Sdfdesc) -> Result<Sdfshape, String> {
+    Ok(match desc {
+        Sdfdesc::Sphere { radius } => Sdfshape::Sphere { radius: *radius },
+        Sdfdesc::Boxx { half_size } => Sdfshape::Box {
+            half_size: vec3(half_size),
+        },
+        Sdfdesc::RoundBox { half_size, radius } => Sdfshape::RoundBox {
+            half_size: vec3(half_size),
+            radius: *radius,
+        },
+        Sdfdesc::Torus {
+            major_radius,
+            minor_radius,
+        } => Sdfshape::Torus {
+            major_radius: *major_radius,
+            minor_radius: *minor_radius,
+        },
+        Sdfdesc::Translate { offset, shape } => Sdfshape::Translate {
+            offset: vec3(offset),
+            shape: Box::new(sdf_shape(shape)?),
+        },
+        Sdfdesc::Union { smoothness, shapes } => {
+            let mut shapes = shapes.iter();
+            let mut res = match shapes.next() {
+                Some(first) => sdf_shape(first)?,
+                None => return Err(String::from("sdf union needs at least one shape")),
+            };
+            for shape in shapes {
+                res = Sdfshape::Union {
+                    smoothness: *smoothness,
+                    left: Box::new(res),
+                    right: Box::new(sdf_shape(shape)?),
+                };
+            }
+            res
+        }
+        Sdfdesc::Subtraction {
+            smoothness,
+            left,
+            right,
+        } => Sdfshape::Subtraction {
+            smoothness: *smoothness,
+            left: Box::new(sdf_shape(left)?),
+            right: Box::new(sdf_shape(right)?),
+        },
+        Sdfdesc::Repeat {
+            spacing,
+            count,
+            shape,
+        } => Sdfshape::Repeat {
+            spacing: vec3(spacing),
+            count: *count,
+            shape: Box::new(sdf_shape(shape)?),
+        },
+        Sdfdesc::Twist { angle, shape } => Sdfshape::Twist {
+            rate: angle.to_radians(),
+            shape: Box::new(sdf_shape(shape)?),
+        },
+    })
+}
+
+// 把各步乘起来, 后面的步骤乘在左边
+fn matrix(steps: &[Transformdesc]) -> Result<Matrix4, String> {
+    let mut res = Matrix4::identity();
+    for step in steps {
+        let m = match step {
+            Transformdesc::Translate(offset) => Matrix4::translate(&vec3(offset)),
+            Transformdesc::RotateX(angle) => Matrix4::rotate_x(*angle),
+            Transformdesc::RotateY(angle) => Matrix4::rotate_y(*angle),
+            Transformdesc::RotateZ(angle) => Matrix4::rotate_z(*angle),
+            Transformdesc::Scale(factor) => Matrix4::scale(&vec3(factor)),
+            Transformdesc::Lookat { from, to, vup } => {
+                let w = vec3(to) - vec3(from);
+                if w.near_zero() || Vec3::cross(&vec3(vup), &w).near_zero() {
+                    return Err(String::from(
+                        "look_at needs distinct `from`/`to` not parallel to `vup`",
+                    ));
+                }
+                Matrix4::look_at(&vec3(from), &vec3(to), &vec3(vup))
+            }
+            Transformdesc::Matrix(m) => Matrix4::new([m[0], m[1], m[2], [0.0, 0.0, 0.0, 1.0]]),
+        };
+        res = m * res;
+    }
+    Ok(res)
+}
+
+impl Objectdesc {
+    fn kind(&self) -> &'static str {
+        match self {
+            Objectdesc::Sphere { .. } => "sphere",
+            Objectdesc::Movingsphere { .. } => "moving_sphere",
+            Objectdesc::XYrect { .. } => "xy_rect",
+            Objectdesc::XZrect { .. } => "xz_rect",
+            Objectdesc::YZrect { .. } => "yz_rect",
+            Objectdesc::Quad { .. } => "quad",
+            Objectdesc::Boxx { .. } => "box",
+            Objectdesc::Triangle { .. } => "triangle",
+            Objectdesc::Disk { .. } => "disk",
+            Objectdesc::Cylinder { .. } => "cylinder",
+            Objectdesc::Cone { .. } => "cone",
+            Objectdesc::Torus { .. } => "torus",
+            Objectdesc::Heightfield { .. } => "heightfield",
+            Objectdesc::Obj { .. } => "obj",
+            Objectdesc::Gltf { .. } => "gltf",
+            Objectdesc::Ply { .. } => "ply",
+            Objectdesc::Translate { .. } => "translate",
+            Objectdesc::RotateX { .. } => "rotate_x",
+            Objectdesc::RotateY { .. } => "rotate_y",
+            Objectdesc::RotateZ { .. } => "rotate_z",
+            Objectdesc::Scale { .. } => "scale",
+            Objectdesc::Transform { .. } => "transform",
+            Objectdesc::Animated { .. } => "animated",
+            Objectdesc::ConstantMedium { .. } => "constant_medium",
+            Objectdesc::Sdf { .. } => "sdf",
+            Objectdesc::Csg { .. } => "csg",
+            Objectdesc::Bvh { .. } => "bvh",
+            Objectdesc::Instance { .. } => "instance",
+        }
+    }
+}
+
+// 按名字解析纹理和材质, 并检测循环引用
+struct Resolver<'a> {
+    texture_descs: &'a HashMap<String, Texturedesc>,
+    material_descs: &'a HashMap<String, Materialdesc>,
+    prototype_descs: &'a HashMap<String, Objectdesc>,
+    textures: HashMap<String, Arc<Texture>>,
+    materials: HashMap<String, Arc<Material>>,
+    prototypes: HashMap<String, Arc<Object>>,
+    visiting_textures: Vec<String>, // 正在解析的纹理和原型分开记, 同名不算循环引用
+    visiting_prototypes: Vec<String>,
+    time0: f64,
+    time1: f64,
+    split: Bvhsplit,
+    bvh_stats: Vec<(String, Bvhstats)>,
+    gltf_camera: Option<Gltfcamera>, // 第一个带相机的 glTF 文件里的相机
+    warnings: Vec<String>,
+}
+
+impl<'a> Resolver<'a> {
+    // 同名的纹理只建一次, 之后都共享同一份
+    fn texture(&mut self, name: &str) -> Result<Arc<Texture>, String> {
+        if let Some(texture) = self.textures.get(name) {
+            return Ok(texture.clone());
+        }
+        let desc = match self.texture_descs.get(name) {
+            Some(desc) => desc,
+            None => return Err(format!("unknown texture `{}`", name)),
+        };
+        if self.visiting_textures.iter().any(|n| n == name) {
+            return Err(format!("texture `{}` references itself", name));
+        }
+        self.visiting_textures.push(name.to_string());
+        let texture = self
+            .build_texture(desc)
+            .map_err(|e| format!("texture `{}`: {}", name, e));
+        self.visiting_textures.pop();
+        let texture = Arc::new(texture?);
+        self.textures.insert(name.to_string(), texture.clone());
+        Ok(texture)
+    }
+
+    fn build_texture(&mut self, desc: &Texturedesc) -> Result<Texture, String> {
+        Ok(match desc {
+            Texturedesc::Solidcolor { color } => {
+                Texture::Solidcolor(Solidcolor::new_from_color(&vec3(color)))
+            }
+            Texturedesc::Checker { even, odd } => {
+                let even = Some(self.color_or_texture(even)?);
+                let odd = Some(self.color_or_texture(odd)?);
+                Texture::Checkertexture(Checkertexture::new_from_ptr(&even, &odd))
+            }
+            Texturedesc::Noise { scale } => Texture::Noisetexture(Noisetexture::new(*scale)),
+            Texturedesc::Image { file } => {
+                Texture::Imagetexture(Imagetexture::new(file).map_err(|e| e.to_string())?)
+            }
+            Texturedesc::Objimage { file } => {
+                Texture::Objtexture(Objtexture::new(file).map_err(|e| e.to_string())?)
+            }
+        })
+    }
+
+    fn color_or_texture(&mut self, c: &Colorref) -> Result<Arc<Texture>, String> {
+        match c {
+            Colorref::Color(color) => Ok(Arc::new(Texture::Solidcolor(
+                Solidcolor::new_from_color(&vec3(color)),
+            ))),
+            Colorref::Texture(name) => self.texture(name),
+        }
+    }
+
+    fn density(&mut self, d: &Densityref) -> Result<Density, String> {
+        let desc = match d {
+            Densityref::Constant(density) => {
+                if *density <= 0.0 {
+                    return Err(String::from("density must be positive"));
+                }
+                return Ok(Density::Constant(*density));
+            }
+            Densityref::Field(desc) => desc,
+        };
+        Ok(match desc {
+            Densitydesc::Noise {
+                density,
+                scale,
+                depth,
+                threshold,
+            } => {
+                if *density <= 0.0 {
+                    return Err(String::from("density must be positive"));
+                }
+                if !(*threshold >= 0.0 && *threshold < 1.0) {
+                    return Err(String::from("threshold must be in [0, 1)"));
+                }
+                Density::Noise {
+                    noise: Arc::new(Perlin::default_new()),
+                    scale: *scale,
+                    depth: *depth,
+                    threshold: *threshold,
+                    density: *density,
+                }
+            }
+            Densitydesc::Grid {
+                min,
+                max,
+                size,
+                values,
+                density,
+            } => {
+                if size.contains(&0) {
+                    return Err(String::from("grid size must be positive"));
+                }
+                if values.len() != size[0] * size[1] * size[2] {
+                    return Err(format!(
+                        "grid has {} values, expected {}",
+                        values.len(),
+                        size[0] * size[1] * size[2]
+                    ));
+                }
+                if (0..3).any(|a| min[a] >= max[a]) {
+                    return Err(String::from("grid min must be below max"));
+                }
+                if *density < 0.0 || values.iter().any(|v| v.is_nan() || *v < 0.0) {
+                    return Err(String::from("grid values must not be negative"));
+                }
+                Density::Grid {
+                    min: vec3(min),
+                    max: vec3(max),
+                    size: *size,
+                    values: Arc::new(values.iter().map(|v| v * density).collect()),
+                }
+            }
+            Densitydesc::Texture { texture, density } => {
+                if *density <= 0.0 {
+                    return Err(String::from("density must be positive"));
+                }
+                Density::Texture {
+                    texture: self.color_or_texture(texture)?,
+                    density: *density,
+                }
+            }
+        })
+    }
+
+    // 同名的材质只建一次, 用到它的物体共享同一份
+    fn material(&mut self, m: &Materialref) -> Result<Option<Arc<Material>>, String> {
+        match m {
+            Materialref::Inline(desc) => Ok(Some(Arc::new(self.build_material(desc)?))),
+            Materialref::Name(name) => {
+                if let Some(material) = self.materials.get(name) {
+                    return Ok(Some(material.clone()));
+                }
+                let desc = match self.material_descs.get(name) {
+                    Some(desc) => desc,
+                    None => return Err(format!("unknown material `{}`", name)),
+                };
+                let material = self
+                    .build_material(desc)
+                    .map_err(|e| format!("material `{}`: {}", name, e))?;
+                let material = Arc::new(material);
+                self.materials.insert(name.clone(), material.clone());
+                Ok(Some(material))
+            }
+        }
+    }
+
+    // 原型第一次被引用时才建, 之后所有实例共享
+    fn prototype(&mut self, name: &str) -> Result<Arc<Object>, String> {
+        if let Some(prototype) = self.prototypes.get(name) {
+            return Ok(prototype.clone());
+        }
+        let desc = match self.prototype_descs.get(name) {
+            Some(desc) => desc,
+            None => return Err(format!("unknown prototype `{}`", name)),
+        };
+        if self.visiting_prototypes.iter().any(|n| n == name) {
+            return Err(format!("prototype `{}` references itself", name));
+        }
+        self.visiting_prototypes.push(name.to_string());
+        let prototype = self
+            .object(desc)
+            .map_err(|e| format!("prototype `{}` ({}): {}", name, desc.kind(), e));
+        self.visiting_prototypes.pop();
+        let prototype = Arc::new(prototype?);
+        self.prototypes.insert(name.to_string(), prototype.clone());
+        Ok(prototype)
+    }
+
+    fn build_material(&mut self, desc: &Materialdesc) -> Result<Material, String> {
+        Ok(match desc {
+            Materialdesc::Lambertian { albedo } => {
+                let albedo = Some(self.color_or_texture(albedo)?);
+                Material::Lambertian(Lambertian::new_from_ptr(&albedo))
+            }
+            Materialdesc::Metal { albedo, fuzz } => {
+                Material::Metal(Metal::new(&vec3(albedo), *fuzz))
+            }
+            Materialdesc::Dielectric { ir } => Material::Dielectric(Dielectric::new(*ir)),
+            Materialdesc::Diffuselight { emit } => {
+                let emit = Some(self.color_or_texture(emit)?);
+                Material::Diffuselight(Diffuselight::new(&emit))
+            }
+            Materialdesc::Isotropic { albedo } => {
+                let albedo = Some(self.color_or_texture(albedo)?);
+                Material::Isotropic(Isotropic::new_from_ptr(&albedo))
+            }
+            Materialdesc::Henyeygreenstein { albedo, g } => {
+                check_asymmetry(*g)?;
+                let albedo = Some(self.color_or_texture(albedo)?);
+                Material::Henyeygreenstein(Henyeygreenstein::new_from_ptr(&albedo, *g))
+            }
+        })
+    }
+
+    // 发光但不能直接采样的形状照样渲染, 只记一条警告
+    fn object(&mut self, desc: &Objectdesc) -> Result<Object, String> {
+        let object = self.build_object(desc)?;
+        if object.is_unsampled_light() {
+            self.warnings.push(format!(
+                "{} with a light material is not sampled as a light",
+                desc.kind()
+            ));
+        }
+        Ok(object)
+    }
+
+    fn build_object(&mut self, desc: &Objectdesc) -> Result<Object, String> {
+        Ok(match desc {
+            Objectdesc::Sphere {
+                center,
+                radius,
+                material,
+            } => Object::Sphere(Sphere::new(
+                &vec3(center),
+                *radius,
+                &self.material(material)?,
+            )),
+            Objectdesc::Movingsphere {
+                center0,
+                center1,
+                time0,
+                time1,
+                radius,
+                material,
+            } => Object::Movingsphere(Movingsphere::new(
+                &vec3(center0),
+                &vec3(center1),
+                *time0,
+                *time1,
+                *radius,
+                &self.material(material)?,
+            )),
+            Objectdesc::XYrect {
+                x0,
+                x1,
+                y0,
+                y1,
+                k,
+                material,
+            } => Object::XYrect(XYrect::new(
+                &self.material(material)?,
+                *x0,
+                *x1,
+                *y0,
+                *y1,
+                *k,
+            )),
+            Objectdesc::XZrect {
+                x0,
+                x1,
+                z0,
+                z1,
+                k,
+                material,
+            } => Object::XZrect(XZrect::new(
+                &self.material(material)?,
+                *x0,
+                *x1,
+                *z0,
+                *z1,
+                *k,
+            )),
+            Objectdesc::YZrect {
+                y0,
+                y1,
+                z0,
+                z1,
+                k,
+                material,
+            } => Object::YZrect(YZrect::new(
+                &self.material(material)?,
+                *y0,
+                *y1,
+                *z0,
+                *z1,
+                *k,
+            )),
+            Objectdesc::Quad { q, u, v, material } => Object::Quad(Quad::new(
+                &vec3(q),
+                &vec3(u),
+                &vec3(v),
+                &self.material(material)?,
+            )),
+            Objectdesc::Boxx { p0, p1, material } => {
+                Object::Boxx(Boxx::new(&vec3(p0), &vec3(p1), &self.material(material)?))
+            }
+            Objectdesc::Triangle { vertices, material } => Object::Triangle(Triangle::new(
+                &[vec3(&vertices[0]), vec3(&vertices[1]), vec3(&vertices[2])],
+                &self.material(material)?,
+            )),
+            Objectdesc::Disk {
+                center,
+                radius,
+                material,
+            } => Object::Disk(Disk::new(&vec3(center), *radius, &self.material(material)?)),
+            Objectdesc::Cylinder {
+                center,
+                radius,
+                height,
+                capped,
+                material,
+            } => Object::Cylinder(Cylinder::new(
+                &vec3(center),
+                *radius,
+                *height,
+                *capped,
+                &self.material(material)?,
+            )),
+            Objectdesc::Cone {
+                center,
+                radius,
+                height,
+                capped,
+                material,
+            } => Object::Cone(Cone::new(
+                &vec3(center),
+                *radius,
+                *height,
+                *capped,
+                &self.material(material)?,
+            )),
+            Objectdesc::Torus {
+                center,
+                major_radius,
+                minor_radius,
+                material,
+            } => Object::Torus(Torus::new(
+                &vec3(center),
+                *major_radius,
+                *minor_radius,
+                &self.material(material)?,
+            )),
+            Objectdesc::Heightfield {
+                file,
+                corner,
+                extent,
+                height,
+                material,
+            } => Object::Heightfield(
+                Heightfield::new(
+                    file,
+                    &vec3(corner),
+                    &Vec3::new(extent[0], *height, extent[1]),
+                    &self.material(material)?,
+                )
+                .map_err(|e| e.to_string())?,
+            ),
+            Objectdesc::Obj { file, material } => {
+                let material = match material {
+                    Some(material) => self.material(material)?,
+                    None => None,
+                };
+                let begin_time = Instant::now();
+                let obj = Obj::new(file, &material, self.time0, self.time1, self.split)
+                    .map_err(|e| e.to_string())?;
+                let mut stats = obj.obj.stats();
+                stats.build_time = begin_time.elapsed();
+                self.bvh_stats.push((format!("obj `{}`", file), stats));
+                Object::Obj(obj)
+            }
+            Objectdesc::Gltf { file, material } => {
+                let material = match material {
+                    Some(material) => self.material(material)?,
+                    None => None,
+                };
+                let begin_time = Instant::now();
+                let gltf = Gltf::load(file, &material, self.time0, self.time1, self.split)
+                    .map_err(|e| e.to_string())?;
+                let mut stats = gltf.mesh.obj.stats();
+                stats.build_time = begin_time.elapsed();
+                self.bvh_stats.push((
+                    format!("gltf `{}` ({} triangles)", file, gltf.triangle_count),
+                    stats,
+                ));
+                if self.gltf_camera.is_none() {
+                    self.gltf_camera = gltf.camera;
+                }
+                Object::Obj(gltf.mesh)
+            }
+            Objectdesc::Ply { file, material } => {
+                let material = match material {
+                    Some(material) => self.material(material)?,
+                    None => None,
+                };
+                let begin_time = Instant::now();
+                let ply = Ply::load(file, &material, self.time0, self.time1, self.split)
+                    .map_err(|e| e.to_string())?;
+                let mut stats = ply.mesh.obj.stats();
+                stats.build_time = begin_time.elapsed();
+                self.bvh_stats.push((
+                    format!("ply `{}` ({} triangles)", file, ply.triangle_count),
+                    stats,
+                ));
+                Object::Obj(ply.mesh)
+            }
+            Objectdesc::Translate { offset, object } => {
+                Object::Transform(Transform::translate(self.object(object)?, &vec3(offset)))
+            }
+            Objectdesc::RotateX { angle, object } => {
+                Object::Transform(Transform::rotate_x(self.object(object)?, *angle))
+            }
+            Objectdesc::RotateY { angle, object } => {
+                Object::Transform(Transform::rotate_y(self.object(object)?, *angle))
+            }
+            Objectdesc::RotateZ { angle, object } => {
+                Object::Transform(Transform::rotate_z(self.object(object)?, *angle))
+            }
+            Objectdesc::Scale { factor, object } => Object::Transform(
+                Transform::scale(self.object(object)?, &vec3(factor))
+                    .ok_or_else(|| String::from("transform is not invertible"))?,
+            ),
+            Objectdesc::Transform { steps, object } => {
+                let m = matrix(steps)?;
+                Object::Transform(
+                    Transform::new(self.object(object)?, &m)
+                        .ok_or_else(|| String::from("transform is not invertible"))?,
+                )
+            }
+            Objectdesc::Animated {
+                keyframes,
+                interpolation,
+                object,
+            } => {
+                check_key_times(&keyframes.iter().map(|k| k.time).collect::<Vec<_>>())?;
+                let keys = keyframes
+                    .iter()
+                    .map(|k| Keyframe {
+                        time: k.time,
+                        translate: vec3(&k.translate),
+                        rotate: vec3(&k.rotate),
+                        scale: vec3(&k.scale),
+                    })
+                    .collect();
+                let object = self.object(object)?;
+                if emits_light(&object) {
+                    self.warnings.push(String::from(
+                        "lights inside an animated object are not sampled as lights",
+                    ));
+                }
+                Object::Animated(Animated::new(object, keys, interpolation.interpolation()))
+            }
+            Objectdesc::ConstantMedium {
+                boundary,
+                density,
+                albedo,
+                g,
+            } => {
+                let density = self.density(density)?;
+                let albedo = Some(self.color_or_texture(albedo)?);
+                let phase = match g {
+                    Some(g) => {
+                        check_asymmetry(*g)?;
+                        Material::Henyeygreenstein(Henyeygreenstein::new_from_ptr(&albedo, *g))
+                    }
+                    None => Material::Isotropic(Isotropic::new_from_ptr(&albedo)),
+                };
+                Object::ConstantMedium(ConstantMedium::new_with_phase(
+                    &Some(Box::new(self.object(boundary)?)),
+                    density,
+                    phase,
+                ))
+            }
+            Objectdesc::Sdf { shape, material } => {
+                Object::Sdf(Sdf::new(sdf_shape(shape)?, &self.material(material)?))
+            }
+            Objectdesc::Csg { op, left, right } => {
+                let op = match op {
+                    Csgopdesc::Union => Csgop::Union,
+                    Csgopdesc::Intersection => Csgop::Intersection,
+                    Csgopdesc::Difference => Csgop::Difference,
+                };
+                let left = self
+                    .object(left)
+                    .map_err(|e| format!("left ({}): {}", left.kind(), e))?;
+                let right = self
+                    .object(right)
+                    .map_err(|e| format!("right ({}): {}", right.kind(), e))?;
+                if emits_light(&left) || emits_light(&right) {
+                    self.warnings.push(String::from(
+                        "lights inside a csg are not sampled as lights",
+                    ));
+                }
+                Object::Csg(Csg::new(op, left, right))
+            }
+            Objectdesc::Bvh { objects } => {
+                if objects.is_empty() {
+                    return Err(String::from("bvh needs at least one object"));
+                }
+                let mut list = Hittablelist::default_new();
+                for (i, object) in objects.iter().enumerate() {
+                    list.add(
+                        self.object(object)
+                            .map_err(|e| format!("object #{} ({}): {}", i, object.kind(), e))?,
+                    );
+                }
+                let begin_time = Instant::now();
+                let bvhnode = Bvhnode::build(list, self.time0, self.time1, self.split)?;
+                let mut stats = bvhnode.stats();
+                stats.build_time = begin_time.elapsed();
+                self.bvh_stats
+                    .push((format!("bvh ({} objects)", objects.len()), stats));
+                Object::Bvhnode(bvhnode)
+            }
+            Objectdesc::Instance {
+                prototype,
+                offset,
+                rotate_y,
+                steps,
+                material,
+            } => {
+                let m = Matrix4::translate(&vec3(offset))
+                    * Matrix4::rotate_y(*rotate_y)
+                    * matrix(steps)?;
+                let prototype = self.prototype(prototype)?;
+                let material = match material {
+                    Some(material) => self.material(material)?,
+                    None => None,
+                };
+                Object::Transform(
+                    Transform::new_shared(&prototype, &m, &material)
+                        .ok_or_else(|| String::from("transform is not invertible"))?,
+                )
+            }
+        })
+    }
+}
+
+impl Scene {
+    pub fn load(path: &str, aspect_ratio: f64) -> Result<Scene, Error> {
+        Scene::load_with(path, aspect_ratio, Bvhsplit::Sah)
+    }
+
+    pub fn load_with(path: &str, aspect_ratio: f64, split: Bvhsplit) -> Result<Scene, Error> {
+        let text = fs::read_to_string(path).map_err(|e| Error::Io {
+            path: path.to_string(),
+            source: e,
+        })?;
+        Scene::parse_with(&text, aspect_ratio, split)
+            .map_err(|e| Error::Scene(format!("`{}`: {}", path, e)))
+    }
+
+    // 把相机的快门设到动画的第 frame 帧
+    // 渲染第一帧之前先检查整个 --frames 范围, 免得渲染到一半才发现越界
+    pub fn check_frames(&self, first: u32, last: u32) -> Result<&Animation, Error> {
+        let animation = match &self.animation {
+            Some(animation) => animation,
+            None => return Err(Error::Scene(String::from("the scene has no `animation`"))),
+        };
+        if first < animation.first_frame || last > animation.last_frame {
+            return Err(Error::Scene(format!(
+                "frames {}..{} are outside the animation ({}..{})",
+                first, last, animation.first_frame, animation.last_frame
+            )));
+        }
+        Ok(animation)
+    }
+
+    pub fn set_frame(&mut self, frame: u32) -> Result<(), Error> {
+        let (time0, time1) = self.check_frames(frame, frame)?.shutter_interval(frame);
+        self.camera.time0 = time0;
+        self.camera.time1 = time1;
+        Ok(())
+    }
+
+    pub fn parse(text: &str, aspect_ratio: f64) -> Result<Scene, Error> {
+        Scene::parse_with(text, aspect_ratio, Bvhsplit::Sah)
+    }
+
+    // 场景里引用的贴图和网格出错时, 错误信息带上是哪个物体、哪个材质引用的
+    pub fn parse_with(text: &str, aspect_ratio: f64, split: Bvhsplit) -> Result<Scene, Error> {
+        let file: Scenefile =
+            serde_json::from_str(text).map_err(|e| Error::Scene(e.to_string()))?;
+        let (mut time0, mut time1) = match &file.camera {
+            Some(cam) => (cam.time0, cam.time1),
+            None => (0.0, default_time1()),
+        };
+        let animation = match &file.animation {
+            Some(desc) => Some(animation(desc)?),
+            None => None,
+        };
+        // BVH 要对动画里的每一帧都成立
+        if let Some(animation) = &animation {
+            let (begin, _) = animation.shutter_interval(animation.first_frame);
+            let (_, end) = animation.shutter_interval(animation.last_frame);
+            time0 = time0.min(begin);
+            time1 = time1.max(end);
+        }
+
+        let mut resolver = Resolver {
+            texture_descs: &file.textures,
+            material_descs: &file.materials,
+            prototype_descs: &file.prototypes,
+            textures: HashMap::new(),
+            materials: HashMap::new(),
+            prototypes: HashMap::new(),
+            visiting_textures: Vec::new(),
+            visiting_prototypes: Vec::new(),
+            time0,
+            time1,
+            split,
+            bvh_stats: Vec::new(),
+            gltf_camera: None,
+            warnings: Vec::new(),
+        };
+        let mut world = Hittablelist::default_new();
+        for (i, object) in file.objects.iter().enumerate() {
+            let warned = resolver.warnings.len();
+            world.add(
+                resolver.object(object).map_err(|e| {
+                    Error::Scene(format!("object #{} ({}): {}", i, object.kind(), e))
+                })?,
+            );
+            for warning in &mut resolver.warnings[warned..] {
+                *warning = format!("object #{} ({}): {}", i, object.kind(), warning);
+            }
+        }
+
+        let camera = match (&file.camera, &resolver.gltf_camera) {
+            (Some(cam), _) => Camera::new(
+                &vec3(&cam.lookfrom),
+                &vec3(&cam.lookat),
+                &vec3(&cam.vup),
+                cam.vfov,
+                aspect_ratio,
+                cam.aperture,
+                cam.focus_dist,
+                cam.time0,
+                cam.time1,
+            ),
+            (None, Some(cam)) => Camera::new(
+                &cam.lookfrom,
+                &cam.lookat,
+                &cam.vup,
+                cam.vfov,
+                aspect_ratio,
+                0.0,
+                default_focus_dist(),
+                time0,
+                time1,
+            ),
+            (None, None) => {
+                return Err(Error::Scene(String::from(
+                    "no camera: add `camera` or a glTF file that has one",
+                )))
+            }
+        };
+        let camera = match &file.camera {
+            Some(cam) if !cam.keyframes.is_empty() => {
+                camera.with_track(camera_track(cam, aspect_ratio).map_err(Error::Scene)?)
+            }
+            _ => camera,
+        };
+
+        let lights = world.lights();
+        let begin_time = Instant::now();
+        world.build_bvh(time0, time1, resolver.split);
+        if let Some(bvh) = &world.bvh {
+            let mut stats = bvh.stats();
+            stats.build_time = begin_time.elapsed();
+            resolver
+                .bvh_stats
+                .push((format!("scene ({} objects)", world.objects.len()), stats));
+        }
+        Ok(Scene {
+            world: Arc::new(world),
+            lights: Arc::new(lights),
+            camera,
+            background: vec3(&file.background),
+            bvh_stats: resolver.bvh_stats,
+            animation,
+            warnings: resolver.warnings,
+        })
+    }
+}
@@ -0,0 +1,332 @@
+#![allow(unused_variables)]
+pub use crate::aabb::Aabb;
+pub use crate::hittable::Boundingbox;
+pub use crate::hittable::Hit;
+pub use crate::hittable::Hitrecord;
+pub use crate::material::Material;
+pub use crate::ray::Ray;
+pub use crate::vec3::Point3;
+pub use crate::vec3::Vec3;
+use std::sync::Arc;
+
+const MAX_STEPS: usize = 512;
+const EPSILON: f64 = 1e-4; // 离表面这么近就算打中
+const NORMAL_DELTA: f64 = 1e-5; // 求梯度时的步长
+
+// 有向距离场的一棵表达式树, 都在物体自己的坐标系里, 叶子以原点为中心
+pub enum Sdfshape {
+    Sphere {
+        radius: f64,
+    },
+    Box {
+        half_size: Vec3,
+    },
+    RoundBox {
+        half_size: Vec3, // 包括圆角在内的外沿
+        radius: f64,
+    },
+    Torus {
+        major_radius: f64, // 躺在 xz 平面里
+        minor_radius: f64,
+    },
+    Translate {
+        offset: Vec3,
+        shape: Box<Sdfshape>,
+    },
+    // smoothness 为 0 时就是普通的并/差, 越大接缝越圆
+    Union {
+        smoothness: f64,
+        left: Box<Sdfshape>,
+        right: Box<Sdfshape>,
+    },
+    Subtraction {
+        smoothness: f64,
+        left: Box<Sdfshape>,
+        right: Box<Sdfshape>, // 从 left 里挖掉的部分
+    },
+    // 每隔 spacing 复制一份, 每个方向两边各 count 份; spacing 为 0 的方向不复制
+    Repeat {
+        spacing: Vec3,
+        count: [u32; 3],
+        shape: Box<Sdfshape>,
+    },
+    // 绕 y 轴扭转, 每升高 1 转 rate 弧度
+    Twist {
+        rate: f64,
+        shape: Box<Sdfshape>,
+    },
+}
+
+// 多项式平滑 min, 接缝处最多比 min 小 k / 4
+fn smooth_min(a: f64, b: f64, k: f64) -> f64 {
+    if k <= 0.0 {
+        return a.min(b);
+    }
+    let h = (k - (a - b).abs()).max(0.0) / k;
+    a.min(b) - h * h * k * 0.25
+}
+
+fn box_distance(p: &Point3, half_size: &Vec3) -> f64 {
+    let q = Vec3::new(
+        p.x().abs() - half_size.x(),
+        p.y().abs() - half_size.y(),
+        p.z().abs() - half_size.z(),
+    );
+    let outside = Vec3::new(q.x().max(0.0), q.y().max(0.0), q.z().max(0.0)).length();
+    let inside = q.x().max(q.y()).max(q.z()).min(0.0);
+    outside + inside
+}
+
+impl Sdfshape {
+    pub fn distance(&self, p: &Point3) -> f64 {
+        match self {
+            Sdfshape::Sphere { radius } => p.length() - radius,
+            Sdfshape::Box { half_size } => box_distance(p, half_size),
+            Sdfshape::RoundBox { half_size, radius } => {
+                let inner = *half_size - Vec3::new(*radius, *radius, *radius);
+                box_distance(p, &inner) - radius
+            }
+            Sdfshape::Torus {
+                major_radius,
+                minor_radius,
+            } => {
+                let ring = (p.x() * p.x() + p.z() * p.z()).sqrt() - major_radius;
+                (ring * ring + p.y() * p.y()).sqrt() - minor_radius
+            }
+            Sdfshape::Translate { offset, shape } => shape.distance(&(*p - *offset)),
+            Sdfshape::Union {
+                smoothness,
+                left,
+                right,
+            } => smooth_min(left.distance(p), right.distance(p), *smoothness),
+            Sdfshape::Subtraction {
+                smoothness,
+                left,
+                right,
+            } => -smooth_min(-left.distance(p), right.distance(p), *smoothness),
+            Sdfshape::Repeat {
+                spacing,
+                count,
+                shape,
+            } => {
+                // 挪回离得最近的那一份里
+                let mut q = *p;
+                for i in 0..3 {
+                    if spacing[i] > 0.0 {
+                        let limit = count[i] as f64;
+                        q[i] -= spacing[i] * (p[i] / spacing[i]).round().max(-limit).min(limit);
+                    }
+                }
+                shape.distance(&q)
+            }
+            Sdfshape::Twist { rate, shape } => {
+                let (sin, cos) = (rate * p.y()).sin_cos();
+                let q = Point3::new(cos * p.x() - sin * p.z(), p.y(), sin * p.x() + cos * p.z());
+                shape.distance(&q)
+            }
+        }
+    }
+
+    // 保守的包围盒 (min, max)
+    pub fn bounds(&self) -> (Point3, Point3) {
+        match self {
+            Sdfshape::Sphere { radius } => {
+                let d = Vec3::new(*radius, *radius, *radius);
+                (-d, d)
+            }
+            Sdfshape::Box { half_size } | Sdfshape::RoundBox { half_size, .. } => {
+                (-*half_size, *half_size)
+            }
+            Sdfshape::Torus {
+                major_radius,
+                minor_radius,
+            } => {
+                let outer = major_radius + minor_radius;
+                let d = Vec3::new(outer, *minor_radius, outer);
+                (-d, d)
+            }
+            Sdfshape::Translate { offset, shape } => {
+                let (min, max) = shape.bounds();
+                (min + *offset, max + *offset)
+            }
+            Sdfshape::Union {
+                smoothness,
+                left,
+                right,
+            } => {
+                // 平滑并集会往外鼓出最多 smoothness / 4
+                let (a0, a1) = left.bounds();
+                let (b0, b1) = right.bounds();
+                let pad = Vec3::new(*smoothness, *smoothness, *smoothness) * 0.25;
+                let mut min = a0;
+                let mut max = a1;
+                for i in 0..3 {
+                    min[i] = min[i].min(b0[i]);
+                    max[i] = max[i].max(b1[i]);
+                }
+                (min - pad, max + pad)
+            }
+            Sdfshape::Subtraction { left, .. } => left.bounds(),
+            Sdfshape::Repeat {
+                spacing,
+                count,
+                shape,
+            } => {
+                let (mut min, mut max) = shape.bounds();
+                for i in 0..3 {
+                    let reach = spacing[i].max(0.0) * count[i] as f64;
+                    min[i] -= reach;
+                    max[i] += reach;
+                }
+                (min, max)
+            }
+            Sdfshape::Twist { shape, .. } => {
+                let (min, max) = shape.bounds();
+                let radius = self.twist_radius();
+                (
+                    Point3::new(-radius, min.y(), -radius),
+                    Point3::new(radius, max.y(), radius),
+                )
+            }
+        }
+    }
+
+    // 扭转后子树离 y 轴最远有多远
+    fn twist_radius(&self) -> f64 {
+        match self {
+            Sdfshape::Twist { shape, .. } => {
+                let (min, max) = shape.bounds();
+                let x = min.x().abs().max(max.x().abs());
+                let z = min.z().abs().max(max.z().abs());
+                (x * x + z * z).sqrt()
+            }
+            _ => 0.0,
+        }
+    }
+
+    // 距离函数梯度大小的上界; 大于 1 时 (扭转) 每步要按比例缩短, 不然会走过头穿过表面
+    pub fn lipschitz(&self) -> f64 {
+        match self {
+            Sdfshape::Sphere { .. }
+            | Sdfshape::Box { .. }
+            | Sdfshape::RoundBox { .. }
+            | Sdfshape::Torus { .. } => 1.0,
+            Sdfshape::Translate { shape, .. } | Sdfshape::Repeat { shape, .. } => shape.lipschitz(),
+            Sdfshape::Union { left, right, .. } | Sdfshape::Subtraction { left, right, .. } => {
+                left.lipschitz().max(right.lipschitz())
+            }
+            Sdfshape::Twist { rate, shape } => {
+                let stretch = rate * self.twist_radius();
+                shape.lipschitz() * (1.0 + stretch * stretch).sqrt()
+            }
+        }
+    }
+}
+
+// 用球面追踪 (sphere tracing) 渲染的距离场物体: 每步前进当前点到表面的距离, 不会跨过表面
+pub struct Sdf {
+    shape: Arc<Sdfshape>,
+    pub mp: Option<Arc<Material>>,
+    min: Point3,
+    max: Point3,
+    lipschitz: f64,
+}
+
+impl Sdf {
+    pub fn new(shape: Sdfshape, mat: &Option<Arc<Material>>) -> Sdf {
+        let (min, max) = shape.bounds();
+        let pad = Vec3::new(EPSILON, EPSILON, EPSILON) * 10.0;
+        Sdf {
+            lipschitz: shape.lipschitz(),
+            shape: Arc::new(shape),
+            mp: mat.clone(),
+            min: min - pad,
+            max: max + pad,
+        }
+    }
+
+    pub fn copy(&self) -> Sdf {
+        Sdf {
+            shape: self.shape.clone(),
+            mp: self.mp.clone(),
+            min: self.min,
+            max: self.max,
+            lipschitz: self.lipschitz,
+        }
+    }
+
+    pub fn distance(&self, p: &Point3) -> f64 {
+        self.shape.distance(p)
+    }
+
+    // 四面体上四个点差分求梯度
+    fn normal(&self, p: &Point3) -> Vec3 {
+        let mut n = Vec3::default_new();
+        for &(x, y, z) in [
+            (1.0, -1.0, -1.0),
+            (-1.0, -1.0, 1.0),
+            (-1.0, 1.0, -1.0),
+            (1.0, 1.0, 1.0),
+        ]
+        .iter()
+        {
+            let k = Vec3::new(x, y, z);
+            n += k * self.distance(&(*p + k * NORMAL_DELTA));
+        }
+        n.unit_vector()
+    }
+}
+
+impl Hit for Sdf {
+    fn hit(&self, r: &Ray, t_min: f64, t_max: f64, rec: &mut Hitrecord) -> bool {
+        // 先裁到包围盒里, 只在盒子里面走
+        let mut t0 = t_min;
+        let mut t1 = t_max;
+        for a in 0..3 {
+            let inv_d = 1.0 / r.direction()[a];
+            let mut near = (self.min[a] - r.origin()[a]) * inv_d;
+            let mut far = (self.max[a] - r.origin()[a]) * inv_d;
+            if inv_d < 0.0 {
+                std::mem::swap(&mut near, &mut far);
+            }
+            t0 = t0.max(near);
+            t1 = t1.min(far);
+            if t1 <= t0 {
+                return false;
+            }
+        }
+
+        // 按单位长度的方向走, 最后再换回 t
+        let len = r.direction().length();
+        let dir = r.direction() / len;
+        let mut s = t0 * len;
+        let end = t1 * len;
+        for _ in 0..MAX_STEPS {
+            let p = r.origin() + dir * s;
+            // 从里面出发 (折射进去的光线) 时距离是负的, 按绝对值走一样能找到出口
+            let d = self.distance(&p).abs();
+            if d < EPSILON {
+                rec.t = s / len;
+                rec.p = p;
+                let outward_normal = self.normal(&p);
+                rec.set_face_normal(&r, &outward_normal);
+                rec.u = 0.0;
+                rec.v = 0.0;
+                rec.mat_ptr = self.mp.clone();
+                return true;
+            }
+            s += d / self.lipschitz;
+            if s > end {
+                return false;
+            }
+        }
+        false
+    }
+}
+
+impl Boundingbox for Sdf {
+    fn boundingbox(&self, time0: f64, time1: f64, output_box: &mut Aabb) -> bool {
+        *output_box = Aabb::new(&self.min, &self.max);
+        true
+    }
+}
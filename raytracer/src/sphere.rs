@@ -3,16 +3,20 @@ pub use crate::aabb::Aabb;
 pub use crate::hittable::Boundingbox;
 pub use crate::hittable::Hit;
 pub use crate::hittable::Hitrecord;
+pub use crate::hittable::Lightsample;
 pub use crate::material::Material;
+pub use crate::onb::Onb;
+pub use crate::rand;
 pub use crate::ray::Ray;
 pub use crate::vec3::Color;
 pub use crate::vec3::Point3;
 pub use crate::vec3::Vec3;
+use std::sync::Arc;
 
 pub struct Sphere {
     pub center: Point3,
     pub radius: f64,
-    pub mat_ptr: Option<Box<Material>>,
+    pub mat_ptr: Option<Arc<Material>>,
 }
 
 impl Sphere {
@@ -24,14 +28,11 @@ impl Sphere {
         }
     }
 
-    pub fn new(cen: &Point3, r: f64, m: &Option<Box<Material>>) -> Sphere {
+    pub fn new(cen: &Point3, r: f64, m: &Option<Arc<Material>>) -> Sphere {
         Sphere {
             center: cen.copy(),
             radius: r,
-            mat_ptr: m
-                .as_ref()
-                .map(|in_mat_ptr| Box::new(in_mat_ptr.copy()))
-                .map(|in_mat_ptr| Box::new(in_mat_ptr.copy())),
+            mat_ptr: m.clone(),
         }
     }
 
@@ -39,11 +40,7 @@ impl Sphere {
         Sphere {
             center: self.center.copy(),
             radius: self.radius,
-            mat_ptr: self
-                .mat_ptr
-                .as_ref()
-                .map(|in_mat_ptr| Box::new(in_mat_ptr.copy()))
-                .map(|in_mat_ptr| Box::new(in_mat_ptr.copy())),
+            mat_ptr: self.mat_ptr.clone(),
         }
     }
 
@@ -54,6 +51,19 @@ impl Sphere {
         *u = phi / (2.0 * std::f64::consts::PI);
         *v = theta / std::f64::consts::PI;
     }
+
+    // 在球对 o 张成的圆锥内均匀取方向
+    pub fn random_to_sphere(radius: f64, distance_squared: f64) -> Vec3 {
+        let r1 = rand::random_double();
+        let r2 = rand::random_double();
+        let z = 1.0 + r2 * ((1.0 - radius * radius / distance_squared).sqrt() - 1.0);
+
+        let phi = 2.0 * std::f64::consts::PI * r1;
+        let x = phi.cos() * (1.0 - z * z).sqrt();
+        let y = phi.sin() * (1.0 - z * z).sqrt();
+
+        Vec3::new(x, y, z)
+    }
 }
 
 impl Hit for Sphere {
@@ -82,11 +92,7 @@ impl Hit for Sphere {
         let outward_normal = (rec.p.copy() - self.center.copy()) / self.radius;
         rec.set_face_normal(&r, &outward_normal);
         Sphere::get_sphere_uv(&outward_normal, &mut rec.u, &mut rec.v);
-        rec.mat_ptr = self
-            .mat_ptr
-            .as_ref()
-            .map(|in_mat_ptr| Box::new(in_mat_ptr.copy()))
-            .map(|in_mat_ptr| Box::new(in_mat_ptr.copy()));
+        rec.mat_ptr = self.mat_ptr.clone();
         true
     }
 }
@@ -100,3 +106,31 @@ impl Boundingbox for Sphere {
         true
     }
 }
+
+impl Lightsample for Sphere {
+    fn pdf_value(&self, o: &Point3, v: &Vec3) -> f64 {
+        let mut rec = Hitrecord::default_new();
+        if !self.hit(&Ray::new(&o, &v, 0.0), 0.001, f64::INFINITY, &mut rec) {
+            return 0.0;
+        }
+
+        let distance_squared = (self.center.copy() - o.copy()).length_squared();
+        if distance_squared <= self.radius * self.radius {
+            return 0.0;
+        }
+        let cos_theta_max = (1.0 - self.radius * self.radius / distance_squared).sqrt();
+        let solid_angle = 2.0 * std::f64::consts::PI * (1.0 - cos_theta_max);
+
+        1.0 / solid_angle
+    }
+
+    fn random(&self, o: &Point3) -> Vec3 {
+        let direction = self.center.copy() - o.copy();
+        let distance_squared = direction.length_squared();
+        if distance_squared <= self.radius * self.radius {
+            return Vec3::random_unit_vector();
+        }
+        let uvw = Onb::build_from_w(&direction);
+        uvw.local_vec(&Sphere::random_to_sphere(self.radius, distance_squared))
+    }
+}
@@ -8,6 +8,7 @@ pub use crate::solidcolor::Solidcolor;
 pub use crate::vec3::Color;
 pub use crate::vec3::Point3;
 pub use crate::vec3::Vec3;
+pub use crate::vertex_color::Vertexcolor;
 
 pub enum Texture {
     Solidcolor(Solidcolor),
@@ -15,6 +16,7 @@ pub enum Texture {
     Noisetexture(Noisetexture),
     Imagetexture(Imagetexture),
     Objtexture(Objtexture),
+    Vertexcolor(Vertexcolor),
 }
 
 pub trait Value {
@@ -31,6 +33,7 @@ impl Texture {
             Texture::Noisetexture(noisetexture) => Texture::Noisetexture(noisetexture.copy()),
             Texture::Imagetexture(imagetexture) => Texture::Imagetexture(imagetexture.copy()),
             Texture::Objtexture(objtexture) => Texture::Objtexture(objtexture.copy()),
+            Texture::Vertexcolor(vertexcolor) => Texture::Vertexcolor(vertexcolor.copy()),
         }
     }
 }
@@ -45,6 +48,7 @@ impl Value for Texture {
             Texture::Noisetexture(noisetexture) => Noisetexture::value(&noisetexture, u, v, &p),
             Texture::Imagetexture(imagetexture) => Imagetexture::value(&imagetexture, u, v, &p),
             Texture::Objtexture(objtexture) => Objtexture::value(&objtexture, u, v, &p),
+            Texture::Vertexcolor(vertexcolor) => Vertexcolor::value(&vertexcolor, u, v, &p),
         }
     }
 }
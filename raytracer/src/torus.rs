@@ -0,0 +1,161 @@
+#![allow(unused_variables, clippy::many_single_char_names)]
+pub use crate::aabb::Aabb;
+pub use crate::disk::Disk;
+pub use crate::hittable::Boundingbox;
+pub use crate::hittable::Hit;
+pub use crate::hittable::Hitrecord;
+pub use crate::material::Material;
+pub use crate::ray::Ray;
+pub use crate::vec3::Point3;
+pub use crate::vec3::Vec3;
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+// 圆环, 中心在 center, 绕 y 轴; major_radius 是圆管中心线的半径, minor_radius 是圆管的半径
+pub struct Torus {
+    pub center: Point3,
+    pub major_radius: f64,
+    pub minor_radius: f64,
+    pub mp: Option<Arc<Material>>,
+}
+
+fn poly_eval(c: &[f64], t: f64) -> f64 {
+    c.iter().rev().fold(0.0, |acc, &x| acc * t + x)
+}
+
+// c[0] + c[1] t + c[2] t^2 + ... 在 [a, b] 里的实根, 从小到大;
+// 导数的根把区间分成单调的几段, 每段两头变号就二分
+fn poly_roots(c: &[f64], a: f64, b: f64) -> Vec<f64> {
+    if c.len() == 2 {
+        let t = -c[0] / c[1];
+        return if t >= a && t <= b {
+            vec![t]
+        } else {
+            Vec::new()
+        };
+    }
+    let derivative: Vec<f64> = c
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(i, &x)| x * i as f64)
+        .collect();
+    let mut ends = vec![a];
+    ends.extend(poly_roots(&derivative, a, b));
+    ends.push(b);
+
+    let mut res = Vec::new();
+    for w in ends.windows(2) {
+        let (mut lo, mut hi) = (w[0], w[1]);
+        let negative = poly_eval(c, lo) < 0.0;
+        if negative == (poly_eval(c, hi) < 0.0) {
+            continue;
+        }
+        for _ in 0..64 {
+            let mid = 0.5 * (lo + hi);
+            if mid <= lo || mid >= hi {
+                break;
+            }
+            if (poly_eval(c, mid) < 0.0) == negative {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        res.push(0.5 * (lo + hi));
+    }
+    res
+}
+
+impl Torus {
+    pub fn new(
+        center: &Point3,
+        major_radius: f64,
+        minor_radius: f64,
+        mat: &Option<Arc<Material>>,
+    ) -> Torus {
+        Torus {
+            center: center.copy(),
+            major_radius,
+            minor_radius,
+            mp: mat.clone(),
+        }
+    }
+
+    pub fn copy(&self) -> Torus {
+        Torus::new(&self.center, self.major_radius, self.minor_radius, &self.mp)
+    }
+}
+
+impl Hit for Torus {
+    fn hit(&self, r: &Ray, t_min: f64, t_max: f64, rec: &mut Hitrecord) -> bool {
+        let big = self.major_radius;
+        let small = self.minor_radius;
+        // 方向归一化, 求出的 s 是距离, 最后再换回 t
+        let len = r.direction().length();
+        let dir = r.direction() / len;
+        let oc = r.origin() - self.center;
+
+        // 先和包围球求交, 把起点挪到进入包围球的地方, 四次方程的系数小一些, 解得更准
+        let bound = big + small;
+        let f = Vec3::dot(&oc, &dir);
+        let discriminant = f * f - (oc.length_squared() - bound * bound);
+        if discriminant < 0.0 {
+            return false;
+        }
+        let s0 = -f - discriminant.sqrt();
+        let s1 = -f + discriminant.sqrt();
+        let lo = s0.max(t_min * len) - s0;
+        let hi = s1.min(t_max * len) - s0;
+        if lo > hi {
+            return false;
+        }
+
+        // (|p|^2 - R^2 - r^2)^2 + 4 R^2 (p.y^2 - r^2) = 0, p = o + s d
+        let oc = oc + dir * s0;
+        let e = oc.length_squared() - big * big - small * small;
+        let f = Vec3::dot(&oc, &dir);
+        let coefficients = [
+            e * e + 4.0 * big * big * (oc.y() * oc.y() - small * small),
+            4.0 * f * e + 8.0 * big * big * oc.y() * dir.y(),
+            4.0 * f * f + 2.0 * e + 4.0 * big * big * dir.y() * dir.y(),
+            4.0 * f,
+            1.0,
+        ];
+        let s = match poly_roots(&coefficients, lo, hi).first() {
+            Some(&s) => s,
+            None => return false,
+        };
+
+        rec.t = (s + s0) / len;
+        rec.p = r.at(rec.t);
+        // 法线从圆管中心线上最近的点指向交点
+        let point = rec.p - self.center;
+        let ring = (point.x() * point.x() + point.z() * point.z()).sqrt();
+        let outward_normal = if ring > 0.0 {
+            let scale = big / ring;
+            Vec3::new(
+                point.x() - point.x() * scale,
+                point.y(),
+                point.z() - point.z() * scale,
+            )
+            .unit_vector()
+        } else {
+            Vec3::new(0.0, point.y().signum(), 0.0)
+        };
+        rec.set_face_normal(&r, &outward_normal);
+        rec.u = Disk::get_angle_u(point.x(), point.z());
+        rec.v = (point.y().atan2(ring - big) + PI) / (2.0 * PI);
+        rec.mat_ptr = self.mp.clone();
+        true
+    }
+}
+
+impl Boundingbox for Torus {
+    fn boundingbox(&self, time0: f64, time1: f64, output_box: &mut Aabb) -> bool {
+        let outer = self.major_radius + self.minor_radius;
+        let dir = Vec3::new(outer, self.minor_radius, outer);
+        *output_box = Aabb::new(&(self.center - dir), &(self.center + dir));
+        true
+    }
+}
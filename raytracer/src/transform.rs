@@ -0,0 +1,234 @@
+#![allow(unused_variables)]
+pub use crate::aabb::Aabb;
+pub use crate::func;
+pub use crate::hittable::Boundingbox;
+pub use crate::hittable::Hit;
+pub use crate::hittable::Hitrecord;
+pub use crate::hittable::Lightsample;
+pub use crate::hittable_list::Hittablelist;
+pub use crate::hittable_list::Object;
+pub use crate::material::Material;
+pub use crate::matrix::Matrix4;
+pub use crate::ray::Ray;
+pub use crate::vec3::Point3;
+pub use crate::vec3::Vec3;
+use std::sync::Arc;
+const INF: f64 = 1.79769e+308;
+
+// 用一个仿射矩阵把物体放进世界; 物体用 Arc 共享, 同一个网格可以摆很多份 (实例化),
+// mat_ptr 不为 None 时覆盖原来的材质
+pub struct Transform {
+    ptr: Arc<Object>,
+    matrix: Box<Matrix4>,        // 物体空间 -> 世界空间
+    inverse: Box<Matrix4>,       // 世界空间 -> 物体空间
+    normal_matrix: Box<Matrix4>, // inverse 的转置, 变换法线用
+    mat_ptr: Option<Arc<Material>>,
+}
+
+impl Transform {
+    // matrix 不可逆 (比如某个方向缩放为 0) 时返回 None
+    pub fn new(p: Object, matrix: &Matrix4) -> Option<Transform> {
+        Transform::new_shared(&Arc::new(p), matrix, &None)
+    }
+
+    pub fn new_shared(
+        p: &Arc<Object>,
+        matrix: &Matrix4,
+        mat: &Option<Arc<Material>>,
+    ) -> Option<Transform> {
+        let inverse = matrix.inverse()?;
+        Some(Transform::new_with_inverse(
+            p.clone(),
+            matrix,
+            &inverse,
+            mat,
+        ))
+    }
+
+    fn new_with_inverse(
+        p: Arc<Object>,
+        matrix: &Matrix4,
+        inverse: &Matrix4,
+        mat: &Option<Arc<Material>>,
+    ) -> Transform {
+        Transform {
+            ptr: p,
+            matrix: Box::new(*matrix),
+            inverse: Box::new(*inverse),
+            normal_matrix: Box::new(inverse.transpose()),
+            mat_ptr: mat.clone(),
+        }
+    }
+
+    // 平移和旋转一定可逆, 逆矩阵直接反着做
+    pub fn translate(p: Object, offset: &Vec3) -> Transform {
+        Transform::new_with_inverse(
+            Arc::new(p),
+            &Matrix4::translate(&offset),
+            &Matrix4::translate(&-*offset),
+            &None,
+        )
+    }
+
+    pub fn rotate_x(p: Object, angle: f64) -> Transform {
+        Transform::new_with_inverse(
+            Arc::new(p),
+            &Matrix4::rotate_x(angle),
+            &Matrix4::rotate_x(-angle),
+            &None,
+        )
+    }
+
+    pub fn rotate_y(p: Object, angle: f64) -> Transform {
+        Transform::new_with_inverse(
+            Arc::new(p),
+            &Matrix4::rotate_y(angle),
+            &Matrix4::rotate_y(-angle),
+            &None,
+        )
+    }
+
+    pub fn rotate_z(p: Object, angle: f64) -> Transform {
+        Transform::new_with_inverse(
+            Arc::new(p),
+            &Matrix4::rotate_z(angle),
+            &Matrix4::rotate_z(-angle),
+            &None,
+        )
+    }
+
+    pub fn scale(p: Object, factor: &Vec3) -> Option<Transform> {
+        Transform::new(p, &Matrix4::scale(&factor))
+    }
+
+    pub fn copy(&self) -> Transform {
+        Transform {
+            ptr: self.ptr.clone(),
+            matrix: self.matrix.clone(),
+            inverse: self.inverse.clone(),
+            normal_matrix: self.normal_matrix.clone(),
+            mat_ptr: self.mat_ptr.clone(),
+        }
+    }
+
+    // 用同样的变换包住另一个物体
+    pub fn wrap(&self, p: Object) -> Transform {
+        Transform {
+            ptr: Arc::new(p),
+            matrix: self.matrix.clone(),
+            inverse: self.inverse.clone(),
+            normal_matrix: self.normal_matrix.clone(),
+            mat_ptr: self.mat_ptr.clone(),
+        }
+    }
+
+    pub fn object(&self) -> &Object {
+        &self.ptr
+    }
+
+    pub fn matrix(&self) -> &Matrix4 {
+        &self.matrix
+    }
+
+    // 覆盖了材质的物体不当作光源: 原来的发光部分不再发光, 新材质的发光只靠材质采样打到
+    pub fn collect_lights(&self, lights: &mut Hittablelist) {
+        if self.mat_ptr.is_some() {
+            return;
+        }
+        let mut inner = Hittablelist::default_new();
+        self.ptr.collect_lights(&mut inner);
+        for light in inner.objects {
+            lights.add(Object::Transform(self.wrap(light)));
+        }
+    }
+}
+
+impl Hit for Transform {
+    fn hit(&self, r: &Ray, t_min: f64, t_max: f64, rec: &mut Hitrecord) -> bool {
+        // 方向不归一化, 两个空间里的 t 就是同一个
+        let object_r = Ray::new(
+            &self.inverse.point(&r.origin()),
+            &self.inverse.vector(&r.direction()),
+            r.time(),
+        );
+        if !self.ptr.hit(&object_r, t_min, t_max, rec) {
+            return false;
+        }
+
+        // 里面算出的法线已经朝着光线来的方向, 线性变换不改变这一点, front_face 沿用
+        rec.p = self.matrix.point(&rec.p);
+        rec.normal = self.normal_matrix.vector(&rec.normal).unit_vector();
+        if let Some(in_mat) = &self.mat_ptr {
+            rec.mat_ptr = Some(in_mat.clone());
+        }
+        true
+    }
+}
+
+impl Boundingbox for Transform {
+    fn boundingbox(&self, time0: f64, time1: f64, output_box: &mut Aabb) -> bool {
+        let mut bbox = Aabb::default_new();
+        if !self.ptr.boundingbox(time0, time1, &mut bbox) {
+            return false;
+        }
+
+        let mut min = Point3::new(INF, INF, INF);
+        let mut max = Point3::new(-INF, -INF, -INF);
+        for i in 0..2 {
+            for j in 0..2 {
+                for k in 0..2 {
+                    let x = (i as f64) * bbox.max().x() + ((1 - i) as f64) * bbox.min().x();
+                    let y = (j as f64) * bbox.max().y() + ((1 - j) as f64) * bbox.min().y();
+                    let z = (k as f64) * bbox.max().z() + ((1 - k) as f64) * bbox.min().z();
+                    let tester = self.matrix.point(&Point3::new(x, y, z));
+                    for c in 0..3 {
+                        min[c] = func::fmin(min[c], tester[c]);
+                        max[c] = func::fmax(max[c], tester[c]);
+                    }
+                }
+            }
+        }
+        *output_box = Aabb::new(&min, &max);
+        true
+    }
+}
+
+impl Lightsample for Transform {
+    // 物体空间里的立体角密度先换成面积密度, 按变换对面积的缩放换到世界空间, 再换回立体角;
+    // 刚体变换下各项相互抵消, 缩放时才有差别
+    fn pdf_value(&self, o: &Point3, v: &Vec3) -> f64 {
+        let object_o = self.inverse.point(&o);
+        let object_v = self.inverse.vector(&v);
+        let object_pdf = self.ptr.pdf_value(&object_o, &object_v);
+        if object_pdf <= 0.0 {
+            return 0.0;
+        }
+        let mut rec = Hitrecord::default_new();
+        if !self
+            .ptr
+            .hit(&Ray::new(&object_o, &object_v, 0.0), 0.001, INF, &mut rec)
+        {
+            return 0.0;
+        }
+
+        let object_d = object_v * rec.t;
+        let world_d = v.copy() * rec.t;
+        let object_cosine = Vec3::dot(&rec.normal, &object_d).abs() / object_d.length();
+        let normal = self.normal_matrix.vector(&rec.normal);
+        let world_cosine =
+            Vec3::dot(&normal, &world_d).abs() / (normal.length() * world_d.length());
+        if world_cosine <= 0.0 {
+            return 0.0;
+        }
+        // 物体表面上一小块面积变到世界空间后放大的倍数
+        let area_scale = self.matrix.determinant3().abs() * normal.length();
+
+        let area_pdf = object_pdf * object_cosine / object_d.length_squared() / area_scale;
+        area_pdf * world_d.length_squared() / world_cosine
+    }
+
+    fn random(&self, o: &Point3) -> Vec3 {
+        self.matrix
+            .vector(&self.ptr.random(&self.inverse.point(&o)))
+    }
+}
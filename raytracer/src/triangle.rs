@@ -5,126 +5,139 @@ pub use crate::aabb::Aabb;
 pub use crate::hittable::Boundingbox;
 pub use crate::hittable::Hit;
 pub use crate::hittable::Hitrecord;
+pub use crate::hittable::Lightsample;
 pub use crate::material::Material;
+pub use crate::rand;
 pub use crate::ray::Ray;
 pub use crate::vec3::Color;
 pub use crate::vec3::Point3;
 pub use crate::vec3::Vec3;
+use std::sync::Arc;
 
 pub struct Triangle {
     pub ver: [Point3; 3], // 3 vertices of triangle
     pub normal: Vec3,
-    pub mat: Option<Box<Material>>,
-    pub cen: Point3, // 三角形中心
-    pub area: f64,
-    pub v: Vec3,
-    pub w: Vec3,
-    pub ab: Vec3,
-    pub ac: Vec3,
+    pub mat: Option<Arc<Material>>,
+    pub area: f64,                      // 退化的三角形面积为 0, 打不到
+    pub ab: Vec3,                       // ver[1] - ver[0]
+    pub ac: Vec3,                       // ver[2] - ver[0]
+    pub vn: Option<Box<[Vec3; 3]>>,     // 顶点法线, 有的话插值成着色法线
+    pub vt: Option<Box<[[f64; 2]; 3]>>, // 顶点纹理坐标, 有的话插值成 u, v
 }
 
 impl Triangle {
-    pub fn new(ver: &[Point3; 3], mat: &Option<Box<Material>>) -> Triangle {
-        let normal = (Vec3::cross(&ver[0], &ver[1])
-            + Vec3::cross(&ver[1], &ver[2])
-            + Vec3::cross(&ver[2], &ver[0]))
-        .unit_vector();
-        let cen = (ver[0].copy() + ver[1].copy() + ver[2].copy()) / 3.;
-
-        let l0 = ((ver[0][0] - ver[1][0]).powi(2)
-            + (ver[0][1] - ver[1][1]).powi(2)
-            + (ver[0][2] - ver[1][2]).powi(2))
-        .sqrt();
-        let l1 = ((ver[1][0] - ver[2][1]).powi(2)
-            + (ver[1][1] - ver[2][1]).powi(2)
-            + (ver[1][2] - ver[2][2]).powi(2))
-        .sqrt();
-        let l2 = ((ver[2][0] - ver[0][0]).powi(2)
-            + (ver[2][1] - ver[0][1]).powi(2)
-            + (ver[2][2] - ver[0][2]).powi(2))
-        .sqrt();
-        let p = (l0 + l1 + l2) / 2.;
-        let area = (p * (p - l0) * (p - l1) * (p - l2)).sqrt();
-
-        let mut v = Vec3::cross(&normal, &(ver[1] - ver[0]));
-        v /= Vec3::dot(&(ver[2] - ver[0]), &v);
-        let mut w = Vec3::cross(&normal, &(ver[2] - ver[0]));
-        w /= Vec3::dot(&(ver[1] - ver[0]), &w);
+    pub fn new(ver: &[Point3; 3], mat: &Option<Arc<Material>>) -> Triangle {
+        let ab = ver[1] - ver[0];
+        let ac = ver[2] - ver[0];
+        let cross = Vec3::cross(&ab, &ac);
+        let area = cross.length() / 2.;
+        let normal = if area > 0. {
+            cross / (2. * area)
+        } else {
+            Vec3::default_new()
+        };
 
         Triangle {
             ver: [ver[0].copy(), ver[1].copy(), ver[2].copy()],
             normal,
-            mat: mat
-                .as_ref()
-                .map(|in_mat_ptr| Box::new(in_mat_ptr.copy()))
-                .map(|in_mat_ptr| Box::new(in_mat_ptr.copy())),
+            mat: mat.clone(),
             area,
-            cen,
-            v,
-            w,
-            ab: ver[1] - ver[0],
-            ac: ver[2] - ver[0],
+            ab,
+            ac,
+            vn: None,
+            vt: None,
         }
     }
 
+    pub fn with_normals(mut self, vn: &[Vec3; 3]) -> Triangle {
+        self.vn = Some(Box::new([vn[0].copy(), vn[1].copy(), vn[2].copy()]));
+        self
+    }
+
+    pub fn with_texcoords(mut self, vt: &[[f64; 2]; 3]) -> Triangle {
+        self.vt = Some(Box::new(*vt));
+        self
+    }
+
     pub fn copy(&self) -> Triangle {
         let ver: [Point3; 3] = [self.ver[0].copy(), self.ver[1].copy(), self.ver[2].copy()];
         Triangle {
             ver,
             normal: self.normal.copy(),
-            mat: self
-                .mat
-                .as_ref()
-                .map(|in_mat| Box::new(in_mat.copy()))
-                .map(|in_mat| Box::new(in_mat.copy())),
-            cen: self.cen.copy(),
+            mat: self.mat.clone(),
             area: self.area,
-            v: self.v.copy(),
-            w: self.w.copy(),
             ab: self.ab.copy(),
             ac: self.ac.copy(),
+            vn: self
+                .vn
+                .as_ref()
+                .map(|vn| Box::new([vn[0].copy(), vn[1].copy(), vn[2].copy()])),
+            vt: self.vt.clone(),
+        }
+    }
+}
+
+impl Triangle {
+    // alpha, beta, gamma 分别是 ver[0], ver[1], ver[2] 的重心坐标
+    fn shade(&self, alpha: f64, beta: f64, gamma: f64, rec: &mut Hitrecord) {
+        if let Some(vn) = &self.vn {
+            let shading = vn[0] * alpha + vn[1] * beta + vn[2] * gamma;
+            if shading.length_squared() > 0.0 {
+                // 着色法线翻到和几何法线同一侧, front_face 仍按几何法线算
+                let shading = shading.unit_vector();
+                rec.normal = if Vec3::dot(&shading, &rec.normal) < 0.0 {
+                    -shading
+                } else {
+                    shading
+                };
+            }
+        }
+        match &self.vt {
+            Some(vt) => {
+                rec.u = alpha * vt[0][0] + beta * vt[1][0] + gamma * vt[2][0];
+                rec.v = alpha * vt[0][1] + beta * vt[1][1] + gamma * vt[2][1];
+            }
+            None => {
+                rec.u = alpha;
+                rec.v = beta;
+            }
         }
     }
 }
 
 impl Hit for Triangle {
     fn hit(&self, r: &Ray, t_min: f64, t_max: f64, rec: &mut Hitrecord) -> bool {
-        let orig = r.origin();
+        // Möller–Trumbore: 直接解 orig + t * dir = ver[0] + beta * ab + gamma * ac;
+        // 边界上的点两侧的三角形都算打中, 网格接缝处不会漏光
         let dir = r.direction();
-        let n = self.normal.copy();
-        let cen = self.cen.copy();
-        let t = ((cen.x() - orig.x()) * n.x()
-            + (cen.y() - orig.y()) * n.y()
-            + (cen.z() - orig.z()) * n.z())
-            / (dir.x() * n.x() + dir.y() * n.y() + dir.z() * n.z());
-        if t.is_nan() || t < t_min || t > t_max {
-            // 直线与三角形平行
+        let pvec = Vec3::cross(&dir, &self.ac);
+        let inv_det = 1. / Vec3::dot(&self.ab, &pvec);
+        if !inv_det.is_finite() {
+            // 光线和三角形平行, 或者三角形退化成线段/点
             return false;
         }
 
-        let ap = (orig + dir * t) - self.ver[0];
-        let gamma = Vec3::dot(&ap, &self.v);
-        if gamma.is_sign_positive() && gamma < 1. {
-            let beta = Vec3::dot(&ap, &self.w);
-            if beta.is_sign_positive() && beta < 1. {
-                let alpha = 1. - gamma - beta;
-                if alpha.is_sign_positive() && alpha < 1. {
-                    rec.t = t;
-                    rec.p = r.at(rec.t);
-                    let outward_normal = n.unit_vector();
-                    rec.set_face_normal(&r, &outward_normal);
-                    rec.mat_ptr = self
-                        .mat
-                        .as_ref()
-                        .map(|in_mat| Box::new(in_mat.copy()))
-                        .map(|in_mat| Box::new(in_mat.copy()));
-                    rec.u = alpha;
-                    rec.v = beta;
-                    return true;
-                }
-            }
+        let tvec = r.origin() - self.ver[0];
+        let beta = Vec3::dot(&tvec, &pvec) * inv_det;
+        if !(0. ..=1.).contains(&beta) {
+            return false;
         }
-        false
+        let qvec = Vec3::cross(&tvec, &self.ab);
+        let gamma = Vec3::dot(&dir, &qvec) * inv_det;
+        if gamma < 0. || beta + gamma > 1. {
+            return false;
+        }
+        let t = Vec3::dot(&self.ac, &qvec) * inv_det;
+        if t < t_min || t > t_max {
+            return false;
+        }
+
+        rec.t = t;
+        rec.p = r.at(rec.t);
+        rec.set_face_normal(&r, &self.normal);
+        rec.mat_ptr = self.mat.clone();
+        self.shade(1. - beta - gamma, beta, gamma, rec);
+        true
     }
 }
 
@@ -143,7 +156,35 @@ impl Boundingbox for Triangle {
                 }
             }
         }
-        *output_box = Aabb::new(&min, &max);
+        // 和轴对齐的三角形包围盒厚度为 0, 像矩形一样稍微加厚一点
+        let pad = Vec3::new(0.0001, 0.0001, 0.0001);
+        *output_box = Aabb::new(&(min - pad), &(max + pad));
         true
     }
 }
+
+impl Lightsample for Triangle {
+    fn pdf_value(&self, o: &Point3, v: &Vec3) -> f64 {
+        let mut rec = Hitrecord::default_new();
+        if !self.hit(&Ray::new(&o, &v, 0.0), 0.001, INFINITY, &mut rec) {
+            return 0.0;
+        }
+
+        let distance_squared = rec.t * rec.t * v.length_squared();
+        let cosine = (Vec3::dot(&v, &self.normal) / v.length()).abs();
+
+        distance_squared / (cosine * self.area)
+    }
+
+    fn random(&self, o: &Point3) -> Vec3 {
+        // 均匀取三角形上一点
+        let mut r1 = rand::random_double();
+        let mut r2 = rand::random_double();
+        if r1 + r2 > 1. {
+            r1 = 1. - r1;
+            r2 = 1. - r2;
+        }
+        let random_point = self.ver[0] + self.ab * r1 + self.ac * r2;
+        random_point - o.copy()
+    }
+}
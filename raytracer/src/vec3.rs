@@ -117,6 +117,19 @@ impl Vec3 {
         }
     }
 
+    // 以 z 轴为法线, 按 cos 加权的半球方向
+    pub fn random_cosine_direction() -> Vec3 {
+        let r1 = rand::random_double();
+        let r2 = rand::random_double();
+        let z = (1.0 - r2).sqrt();
+
+        let phi = 2.0 * std::f64::consts::PI * r1;
+        let x = phi.cos() * r2.sqrt();
+        let y = phi.sin() * r2.sqrt();
+
+        Vec3::new(x, y, z)
+    }
+
     pub fn random_in_unit_disk() -> Vec3 {
         loop {
             let p = Vec3::new(
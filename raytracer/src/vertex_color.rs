@@ -0,0 +1,33 @@
+#![allow(unused_variables)]
+pub use crate::texture::Value;
+pub use crate::vec3::Color;
+pub use crate::vec3::Point3;
+use std::sync::Arc;
+
+// 三角形三个顶点的颜色, 按重心坐标插值; 只能用在没有纹理坐标的三角形上,
+// 这时三角形给出的 u, v 就是 ver[0], ver[1] 的重心坐标.
+// 颜色整个网格只存一份, 每个三角形只记自己三个顶点的下标
+pub struct Vertexcolor {
+    pub colors: Arc<Vec<Color>>,
+    pub indices: [usize; 3],
+}
+
+impl Vertexcolor {
+    pub fn new(colors: &Arc<Vec<Color>>, indices: &[usize; 3]) -> Vertexcolor {
+        Vertexcolor {
+            colors: colors.clone(),
+            indices: *indices,
+        }
+    }
+
+    pub fn copy(&self) -> Vertexcolor {
+        Vertexcolor::new(&self.colors, &self.indices)
+    }
+}
+
+impl Value for Vertexcolor {
+    fn value(&self, u: f64, v: f64, p: &Point3) -> Color {
+        let color = |k: usize| self.colors[self.indices[k]];
+        color(0) * u + color(1) * v + color(2) * (1.0 - u - v)
+    }
+}
@@ -0,0 +1,193 @@
+use raytracer::animation::interpolate;
+use raytracer::{
+    frame_path, Aabb, Animated, Boundingbox, Camerakey, Cameratrack, Error, Hit, Hitrecord,
+    Interpolation, Keyframe, Object, Point3, Ray, Scene, Sphere, Vec3,
+};
+
+fn key(time: f64, translate: [f64; 3], rotate_y: f64) -> Keyframe {
+    Keyframe {
+        time,
+        translate: Vec3::new(translate[0], translate[1], translate[2]),
+        rotate: Vec3::new(0.0, rotate_y, 0.0),
+        scale: Vec3::new(1.0, 1.0, 1.0),
+    }
+}
+
+fn unit_sphere_at(x: f64) -> Object {
+    Object::Sphere(Sphere::new(&Point3::new(x, 0.0, 0.0), 1.0, &None))
+}
+
+#[test]
+fn interpolation_holds_ends_and_passes_through_keys() {
+    let times = [0.0, 1.0, 3.0];
+    let values = [0.0, 10.0, 30.0];
+    let at = |t: f64, interpolation| {
+        interpolate(&times, t, interpolation, |i| Vec3::new(values[i], 0.0, 0.0)).x()
+    };
+    for &interpolation in [Interpolation::Linear, Interpolation::Smooth].iter() {
+        assert!((at(-1.0, interpolation) - 0.0).abs() < 1e-12);
+        assert!((at(1.0, interpolation) - 10.0).abs() < 1e-12);
+        assert!((at(5.0, interpolation) - 30.0).abs() < 1e-12);
+    }
+    assert!((at(2.0, Interpolation::Linear) - 20.0).abs() < 1e-12);
+    // 首尾缓入缓出, 中间关键帧处的速度是前后两帧连线的斜率
+    let middle = at(2.0, Interpolation::Smooth);
+    assert!(middle > 10.0 && middle < 30.0);
+    assert!(at(0.1, Interpolation::Smooth) < at(0.1, Interpolation::Linear));
+    let slope = (at(1.001, Interpolation::Smooth) - at(0.999, Interpolation::Smooth)) / 0.002;
+    assert!((slope - 10.0).abs() < 0.05, "{}", slope);
+}
+
+#[test]
+fn poses_carry_their_own_inverse() {
+    let mut keys = vec![
+        key(0.0, [1.0, 2.0, 3.0], 30.0),
+        key(1.0, [-4.0, 0.5, 2.0], 120.0),
+    ];
+    keys[1].rotate = Vec3::new(20.0, 120.0, -45.0);
+    keys[1].scale = Vec3::new(2.0, 0.5, 3.0);
+    let moving = Animated::new(unit_sphere_at(0.0), keys, Interpolation::Smooth);
+    for &time in [-1.0, 0.0, 0.3, 0.7, 1.0, 2.0].iter() {
+        let (matrix, inverse) = moving.pose_at(time).unwrap();
+        let product = matrix * inverse;
+        for i in 0..4 {
+            for j in 0..4 {
+                let expected = if i == j { 1.0 } else { 0.0 };
+                assert!((product.m[i][j] - expected).abs() < 1e-9, "t = {}", time);
+            }
+        }
+    }
+
+    let mut flat = key(1.0, [0.0, 0.0, 0.0], 0.0);
+    flat.scale = Vec3::new(1.0, 0.0, 1.0);
+    let vanishing = Animated::new(
+        unit_sphere_at(0.0),
+        vec![key(0.0, [0.0, 0.0, 0.0], 0.0), flat],
+        Interpolation::Linear,
+    );
+    assert!(vanishing.pose_at(0.5).is_some());
+    assert!(vanishing.pose_at(1.0).is_none());
+}
+
+#[test]
+fn rays_see_the_pose_at_their_own_time() {
+    let moving = Animated::new(
+        unit_sphere_at(0.0),
+        vec![
+            key(0.0, [0.0, 0.0, 0.0], 0.0),
+            key(1.0, [10.0, 0.0, 0.0], 0.0),
+        ],
+        Interpolation::Linear,
+    );
+    for &(time, x) in [(0.0, 0.0), (0.5, 5.0), (1.0, 10.0), (2.0, 10.0)].iter() {
+        let mut rec = Hitrecord::default_new();
+        let r = Ray::new(&Point3::new(x, 5.0, 0.0), &Vec3::new(0.0, -1.0, 0.0), time);
+        assert!(
+            moving.hit(&r, 0.001, f64::INFINITY, &mut rec),
+            "t = {}",
+            time
+        );
+        assert!((rec.t - 4.0).abs() < 1e-9);
+        assert!((rec.p - Point3::new(x, 1.0, 0.0)).length() < 1e-9);
+        assert!((rec.normal.y() - 1.0).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn bounding_box_covers_the_whole_motion() {
+    // 绕 y 轴转一圈, 球心离轴 3
+    let spinning = Animated::new(
+        unit_sphere_at(3.0),
+        vec![
+            key(0.0, [0.0, 0.0, 0.0], 0.0),
+            key(2.0, [0.0, 0.0, 0.0], 360.0),
+        ],
+        Interpolation::Smooth,
+    );
+    let mut bbox = Aabb::default_new();
+    assert!(spinning.boundingbox(0.0, 2.0, &mut bbox));
+    for i in 0..=200 {
+        let angle = std::f64::consts::PI * 2.0 * i as f64 / 200.0;
+        for &(x, z) in [(4.0, 0.0), (2.0, 0.0), (3.0, 1.0), (3.0, -1.0)].iter() {
+            let p = Point3::new(
+                x * angle.cos() + z * angle.sin(),
+                0.0,
+                -x * angle.sin() + z * angle.cos(),
+            );
+            for c in 0..3 {
+                assert!(
+                    p[c] >= bbox.min()[c] && p[c] <= bbox.max()[c],
+                    "{:?}",
+                    (x, z)
+                );
+            }
+        }
+    }
+    // 只看前一小段时间时盒子也跟着变小
+    let mut early = Aabb::default_new();
+    assert!(spinning.boundingbox(0.0, 0.01, &mut early));
+    assert!(early.area() < bbox.area() / 4.0);
+}
+
+#[test]
+fn camera_track_moves_the_eye() {
+    let key = |time: f64, z: f64| Camerakey {
+        time,
+        lookfrom: Point3::new(0.0, 0.0, z),
+        lookat: Point3::new(0.0, 0.0, 0.0),
+        vup: Vec3::new(0.0, 1.0, 0.0),
+        vfov: 40.0,
+        aperture: 0.0,
+        focus_dist: 10.0,
+    };
+    let track = Cameratrack::new(
+        vec![key(0.0, 10.0), key(1.0, 20.0)],
+        Interpolation::Linear,
+        1.0,
+    );
+    let camera = track.camera_at(0.25);
+    assert!((camera.origin - Point3::new(0.0, 0.0, 12.5)).length() < 1e-12);
+    let r = camera.get_ray(0.5, 0.5);
+    assert!((r.time() - 0.25).abs() < 1e-12);
+    // 关键帧之后相机停在最后一帧
+    let after = track.camera_at(3.0);
+    assert!((after.origin - Point3::new(0.0, 0.0, 20.0)).length() < 1e-12);
+    assert!((after.get_ray(0.5, 0.5).time() - 3.0).abs() < 1e-12);
+}
+
+#[test]
+fn frames_set_the_shutter_interval() {
+    let text = r#"{"camera": {"lookfrom": [0, 0, 10], "lookat": [0, 0, 0], "vfov": 40,
+        "keyframes": [{"time": 0, "lookfrom": [0, 0, 10], "lookat": [0, 0, 0]},
+                      {"time": 1, "lookfrom": [0, 0, 20], "lookat": [0, 0, 0]}]},
+        "animation": {"fps": 24, "frames": [0, 23], "shutter": 0.5},
+        "objects": [{"type": "animated", "keyframes": [{"time": 0}, {"time": 1, "rotate": [0, 90, 0]}],
+                     "object": {"type": "sphere", "center": [1, 0, 0], "radius": 1,
+                                "material": {"type": "lambertian", "albedo": [0.5, 0.5, 0.5]}}}]}"#;
+    let mut scene = Scene::parse(text, 1.0).unwrap();
+    scene.set_frame(12).unwrap();
+    assert!((scene.camera.time0 - 0.5).abs() < 1e-12);
+    assert!((scene.camera.time1 - 12.5 / 24.0).abs() < 1e-12);
+    let r = scene.camera.get_ray(0.5, 0.5);
+    assert!(r.time() >= scene.camera.time0 && r.time() <= scene.camera.time1);
+    match scene.set_frame(24) {
+        Err(Error::Scene(message)) => assert!(message.contains("outside"), "{}", message),
+        _ => panic!("expected a scene error"),
+    }
+    assert!(scene.check_frames(0, 23).is_ok());
+    assert!(scene.check_frames(20, 30).is_err());
+
+    let bad = text.replace(r#"{"time": 1, "rotate""#, r#"{"time": 0, "rotate""#);
+    assert!(Scene::parse(&bad, 1.0).is_err());
+}
+
+#[test]
+fn numbered_frame_files() {
+    assert_eq!(
+        frame_path("output/spin_####.png", 7),
+        "output/spin_0007.png"
+    );
+    assert_eq!(frame_path("out/f##.jpg", 123), "out/f123.jpg");
+    assert_eq!(frame_path("output/spin.png", 7), "output/spin_0007.png");
+    assert_eq!(frame_path("./out.d/spin", 7), "./out.d/spin_0007");
+}
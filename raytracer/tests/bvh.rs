@@ -0,0 +1,146 @@
+mod common;
+
+use common::hit;
+use raytracer::{
+    rand, Aabb, Boundingbox, Bvhnode, Bvhsplit, ConstantMedium, Density, Hit, Hittablelist, Object,
+    Point3, Ray, Sphere, Vec3,
+};
+
+#[test]
+fn empty_list_builds_an_empty_node() {
+    for &split in [Bvhsplit::Median, Bvhsplit::Sah].iter() {
+        let bvhnode = Bvhnode::build(Hittablelist::default_new(), 0.0, 1.0, split).unwrap();
+        assert!(hit(
+            &bvhnode,
+            Point3::new(0.0, 0.0, 5.0),
+            Vec3::new(0.0, 0.0, -1.0)
+        )
+        .is_none());
+        assert!(!bvhnode.boundingbox(0.0, 1.0, &mut Aabb::default_new()));
+    }
+}
+
+#[test]
+fn unbounded_objects_are_rejected() {
+    let mut list = Hittablelist::default_new();
+    list.add(Object::Sphere(Sphere::new(
+        &Point3::new(0.0, 0.0, 0.0),
+        1.0,
+        &None,
+    )));
+    list.add(Object::ConstantMedium(ConstantMedium::new_with_density(
+        &None,
+        Density::Constant(1.0),
+        &None,
+    )));
+    for &split in [Bvhsplit::Median, Bvhsplit::Sah].iter() {
+        match Bvhnode::build(list.copy(), 0.0, 1.0, split) {
+            Err(message) => assert!(message.contains("#1"), "{}", message),
+            Ok(_) => panic!("expected an error"),
+        }
+    }
+}
+
+fn random_spheres(n: usize) -> Hittablelist {
+    let mut list = Hittablelist::default_new();
+    for _ in 0..n {
+        let center = Point3::new(
+            rand::random_double_between(-10.0, 10.0),
+            rand::random_double_between(-10.0, 10.0),
+            rand::random_double_between(-10.0, 10.0),
+        );
+        list.add(Object::Sphere(Sphere::new(
+            &center,
+            rand::random_double_between(0.1, 1.0),
+            &None,
+        )));
+    }
+    list
+}
+
+fn random_rays(n: usize) -> Vec<Ray> {
+    (0..n)
+        .map(|_| {
+            let origin = Point3::new(
+                rand::random_double_between(-15.0, 15.0),
+                rand::random_double_between(-15.0, 15.0),
+                rand::random_double_between(-15.0, 15.0),
+            );
+            let target = Point3::new(
+                rand::random_double_between(-10.0, 10.0),
+                rand::random_double_between(-10.0, 10.0),
+                rand::random_double_between(-10.0, 10.0),
+            );
+            Ray::new(&origin, &(target - origin), 0.0)
+        })
+        .collect()
+}
+
+fn closest(object: &dyn Hit, r: &Ray) -> Option<f64> {
+    hit(object, r.origin(), r.direction()).map(|rec| rec.t)
+}
+
+#[test]
+fn flat_bvh_matches_a_linear_scan() {
+    rand::seed(17);
+    let linear = random_spheres(300);
+    let rays = random_rays(2000);
+    for &split in [Bvhsplit::Median, Bvhsplit::Sah].iter() {
+        let mut flat = linear.copy();
+        flat.build_bvh(0.0, 1.0, split);
+        assert!(flat.bvh.is_some());
+        let mut hits = 0;
+        for r in &rays {
+            let expected = closest(&linear, r);
+            match (expected, closest(&flat, r)) {
+                (Some(a), Some(b)) => {
+                    assert!((a - b).abs() < 1e-9, "{:?}: {} vs {}", split, a, b);
+                    hits += 1;
+                }
+                (None, None) => {}
+                (a, b) => panic!("{:?}: {:?} vs {:?}", split, a, b),
+            }
+        }
+        assert!(hits > 100);
+    }
+}
+
+#[test]
+fn stats_count_leaves_as_nodes() {
+    rand::seed(23);
+    let mut list = random_spheres(64);
+    let tree = Bvhnode::build(list.copy(), 0.0, 1.0, Bvhsplit::Sah).unwrap();
+    let stats = tree.stats();
+    // 二叉树: 叶子比内部节点多一个, 每个物体一片叶子
+    assert_eq!(stats.leaf_count, 64);
+    assert_eq!(stats.node_count, 2 * stats.leaf_count - 1);
+    assert!(stats.depth >= 7);
+
+    list.build_bvh(0.0, 1.0, Bvhsplit::Median);
+    let flat = list.bvh.as_ref().unwrap();
+    let stats = flat.stats();
+    assert_eq!(stats.node_count, flat.nodes.len());
+    assert_eq!(stats.node_count, 2 * stats.leaf_count - 1);
+    assert!(stats.depth >= 6);
+}
+
+#[test]
+fn sah_and_median_trees_agree() {
+    rand::seed(29);
+    let list = random_spheres(300);
+    let rays = random_rays(2000);
+    let median = Bvhnode::build(list.copy(), 0.0, 1.0, Bvhsplit::Median).unwrap();
+    let sah = Bvhnode::build(list, 0.0, 1.0, Bvhsplit::Sah).unwrap();
+    let mut hits = 0;
+    for r in &rays {
+        match (closest(&median, r), closest(&sah, r)) {
+            (Some(a), Some(b)) => {
+                assert!((a - b).abs() < 1e-9, "{} vs {}", a, b);
+                hits += 1;
+            }
+            (None, None) => {}
+            (a, b) => panic!("{:?} vs {:?}", a, b),
+        }
+    }
+    assert!(hits > 100);
+}
@@ -0,0 +1,12 @@
+use raytracer::{Hit, Hitrecord, Point3, Ray, Vec3};
+
+// 时间 0 的光线打 object, 返回最近的交点
+pub fn hit(object: &dyn Hit, origin: Point3, direction: Vec3) -> Option<Hitrecord> {
+    let mut rec = Hitrecord::default_new();
+    let r = Ray::new(&origin, &direction, 0.0);
+    if object.hit(&r, 0.001, f64::INFINITY, &mut rec) {
+        Some(rec)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,88 @@
+use raytracer::{Boxx, Csg, Csgop, Hit, Hitrecord, Intervals, Object, Point3, Ray, Sphere, Vec3};
+
+fn sphere(x: f64, radius: f64) -> Object {
+    Object::Sphere(Sphere::new(&Point3::new(x, 0.0, 0.0), radius, &None))
+}
+
+// 从 (origin_x, 0, 0) 沿 +x 打, 返回每段进出点的 x
+fn spans(object: &dyn Intervals, origin_x: f64) -> Vec<(f64, f64)> {
+    let r = Ray::new(
+        &Point3::new(origin_x, 0.0, 0.0),
+        &Vec3::new(1.0, 0.0, 0.0),
+        0.0,
+    );
+    object
+        .intervals(&r, 0.001)
+        .iter()
+        .map(|s| (s.enter.t + origin_x, s.exit.t + origin_x))
+        .collect()
+}
+
+fn assert_spans(actual: Vec<(f64, f64)>, expected: &[(f64, f64)]) {
+    assert_eq!(actual.len(), expected.len(), "{:?}", actual);
+    for (a, e) in actual.iter().zip(expected.iter()) {
+        assert!(
+            (a.0 - e.0).abs() < 1e-6 && (a.1 - e.1).abs() < 1e-6,
+            "{:?}",
+            actual
+        );
+    }
+}
+
+#[test]
+fn boolean_operations_on_intervals() {
+    // 两个球 [-2, 0] 和 [-1, 3]
+    let csg = |op| Csg::new(op, sphere(-1.0, 1.0), sphere(1.0, 2.0));
+    assert_spans(spans(&csg(Csgop::Union), -10.0), &[(-2.0, 3.0)]);
+    assert_spans(spans(&csg(Csgop::Intersection), -10.0), &[(-1.0, 0.0)]);
+    assert_spans(spans(&csg(Csgop::Difference), -10.0), &[(-2.0, -1.0)]);
+
+    // 挖空的球壳有两段, 差集第二段从里面那个球的出口开始
+    let shell = Csg::new(Csgop::Difference, sphere(0.0, 2.0), sphere(0.0, 1.0));
+    assert_spans(spans(&shell, -10.0), &[(-2.0, -1.0), (1.0, 2.0)]);
+    // 起点在壳里面时第一段没有入口
+    let inside = spans(&shell, -1.5);
+    assert!(inside[0].0 == -f64::INFINITY);
+    assert!((inside[0].1 + 1.0).abs() < 1e-6);
+}
+
+#[test]
+fn difference_exposes_the_inner_surface() {
+    let shell = Object::Csg(Csg::new(
+        Csgop::Difference,
+        sphere(0.0, 2.0),
+        sphere(0.0, 1.0),
+    ));
+    let mut rec = Hitrecord::default_new();
+    // 从洞里往外打, 先碰到挖掉的球的出口, 对壳来说是正面
+    let r = Ray::new(&Point3::new(0.0, 0.0, 0.0), &Vec3::new(1.0, 0.0, 0.0), 0.0);
+    assert!(shell.hit(&r, 0.001, f64::INFINITY, &mut rec));
+    assert!((rec.t - 1.0).abs() < 1e-9);
+    assert!(rec.front_face);
+    assert!((rec.normal.x() + 1.0).abs() < 1e-9);
+    // t_max 在壳前面就打不到
+    assert!(!shell.hit(&r, 0.001, 0.5, &mut rec));
+}
+
+#[test]
+fn box_faces_point_outward() {
+    // 每个面从外面打都是正面, 从里面打都是背面
+    let boxx = Boxx::new(
+        &Point3::new(-1.0, -1.0, -1.0),
+        &Point3::new(1.0, 1.0, 1.0),
+        &None,
+    );
+    for i in 0..3 {
+        for &sign in [-1.0, 1.0].iter() {
+            let mut dir = Vec3::default_new();
+            dir[i] = sign;
+            let mut rec = Hitrecord::default_new();
+            let outside = Ray::new(&(dir * -5.0), &dir, 0.0);
+            assert!(boxx.hit(&outside, 0.001, f64::INFINITY, &mut rec));
+            assert!(rec.front_face);
+            let inside = Ray::new(&Point3::default_new(), &dir, 0.0);
+            assert!(boxx.hit(&inside, 0.001, f64::INFINITY, &mut rec));
+            assert!(!rec.front_face);
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use raytracer::{save, Bvhsplit, Error, Image, Imagetexture, Obj, Objtexture, Scene};
+
+fn missing(name: &str) -> String {
+    std::env::temp_dir()
+        .join("raytracer_no_such_dir")
+        .join(name)
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[test]
+fn missing_files_report_their_paths() {
+    let path = missing("mesh.obj");
+    match Obj::new(&path, &None, 0.0, 1.0, Bvhsplit::Sah) {
+        Err(Error::Obj { path: p, .. }) => assert_eq!(p, path),
+        _ => panic!("expected an obj error"),
+    }
+
+    let path = missing("scene.json");
+    match Scene::load(&path, 1.0) {
+        Err(Error::Io { path: p, .. }) => assert_eq!(p, path),
+        _ => panic!("expected an io error"),
+    }
+
+    let path = missing("earth.jpg");
+    match Imagetexture::new(&path) {
+        Err(Error::Image { path: p, .. }) => assert_eq!(p, path),
+        _ => panic!("expected an image error"),
+    }
+    match Objtexture::new(&path) {
+        Err(Error::Image { path: p, .. }) => assert_eq!(p, path),
+        _ => panic!("expected an image error"),
+    }
+}
+
+#[test]
+fn missing_textures_fail_the_scene() {
+    let path = missing("earth.jpg");
+    let text = format!(
+        r#"{{"camera": {{"lookfrom": [0, 0, -5], "lookat": [0, 0, 0], "vfov": 40}},
+        "textures": {{"earth": {{"type": "image", "file": "{}"}}}},
+        "objects": [{{"type": "sphere", "center": [0, 0, 0], "radius": 1,
+                      "material": {{"type": "lambertian", "albedo": "earth"}}}}]}}"#,
+        path
+    );
+    match Scene::parse(&text, 1.0) {
+        Err(e) => assert!(e.to_string().contains(&path), "{}", e),
+        Ok(_) => panic!("expected an error"),
+    }
+}
+
+#[test]
+fn unwritable_output_is_an_io_error() {
+    let path = missing("out.png");
+    let img = Image::new(2, 2);
+    match save(&img, &path, image::ImageOutputFormat::Png) {
+        Err(Error::Io { path: p, .. }) => assert_eq!(p, path),
+        _ => panic!("expected an io error"),
+    }
+}
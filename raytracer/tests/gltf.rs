@@ -0,0 +1,104 @@
+mod common;
+
+use common::hit;
+use raytracer::{Bvhsplit, Error, Gltf, Point3, Scene, Vec3};
+
+fn sample(name: &str) -> String {
+    format!("{}/../image/gltf/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+fn load(name: &str) -> Gltf {
+    Gltf::load(&sample(name), &None, 0.0, 1.0, Bvhsplit::Sah).unwrap()
+}
+
+#[test]
+fn separate_and_binary_files_match() {
+    // 地面 2 + 三个立方体各 12 + 灯 2
+    for name in ["scene.gltf", "scene.glb"].iter() {
+        let gltf = load(name);
+        assert_eq!(gltf.triangle_count, 40);
+
+        let camera = gltf.camera.as_ref().unwrap();
+        assert!((camera.lookfrom.copy() - Point3::new(0.0, 2.0, 6.0)).length() < 1e-9);
+        assert!((camera.vfov - 0.7_f64.to_degrees()).abs() < 1e-9);
+        // 相机绕 x 轴往下转了 15 度
+        let dir = (camera.lookat.copy() - camera.lookfrom.copy()).unit_vector();
+        assert!((dir.y() + 15_f64.to_radians().sin()).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn node_transforms_are_applied() {
+    let gltf = load("scene.glb");
+
+    let floor = hit(
+        &gltf.mesh,
+        Point3::new(0.0, 5.0, -3.0),
+        Vec3::new(0.0, -1.0, 0.0),
+    )
+    .unwrap();
+    assert!(floor.p.y().abs() < 1e-9);
+    assert!((floor.normal.y() - 1.0).abs() < 1e-9);
+
+    // 平移到 (-1.3, 0.5, 0) 的单位立方体, 顶面在 y = 1
+    let cube = hit(
+        &gltf.mesh,
+        Point3::new(-1.3, 5.0, 0.0),
+        Vec3::new(0.0, -1.0, 0.0),
+    )
+    .unwrap();
+    assert!((cube.p.y() - 1.0).abs() < 1e-9);
+
+    // group 节点的矩阵缩放 0.8 后平移到 (1.3, 0.4, 0), 顶面在 y = 0.8
+    let chrome = hit(
+        &gltf.mesh,
+        Point3::new(1.3, 5.0, 0.0),
+        Vec3::new(0.0, -1.0, 0.0),
+    )
+    .unwrap();
+    assert!((chrome.p.y() - 0.8).abs() < 1e-9);
+}
+
+#[test]
+fn data_uri_without_scenes() {
+    let gltf = load("triangle.gltf");
+    assert_eq!(gltf.triangle_count, 1);
+    assert!(gltf.camera.is_none());
+
+    let rec = hit(
+        &gltf.mesh,
+        Point3::new(0.2, 0.2, 5.0),
+        Vec3::new(0.0, 0.0, -1.0),
+    )
+    .unwrap();
+    assert!((rec.t - 6.0).abs() < 1e-9);
+    assert!(hit(
+        &gltf.mesh,
+        Point3::new(0.8, 0.8, 5.0),
+        Vec3::new(0.0, 0.0, -1.0)
+    )
+    .is_none());
+}
+
+#[test]
+fn scene_uses_gltf_camera() {
+    let text = format!(
+        r#"{{ "objects": [{{ "type": "gltf", "file": "{}" }}] }}"#,
+        sample("scene.glb")
+    );
+    assert!(Scene::parse(&text, 16.0 / 9.0).is_ok());
+
+    let text = format!(
+        r#"{{ "objects": [{{ "type": "gltf", "file": "{}" }}] }}"#,
+        sample("triangle.gltf")
+    );
+    assert!(Scene::parse(&text, 16.0 / 9.0).is_err());
+}
+
+#[test]
+fn missing_file_is_an_error() {
+    match Gltf::load(&sample("missing.glb"), &None, 0.0, 1.0, Bvhsplit::Sah) {
+        Err(Error::Io { path, .. }) => assert!(path.ends_with("missing.glb")),
+        _ => panic!("expected an io error"),
+    }
+}
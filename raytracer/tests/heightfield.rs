@@ -0,0 +1,125 @@
+mod common;
+
+use common::hit;
+use raytracer::{Error, Heightfield, Hittablelist, Object, Point3, Triangle, Vec3};
+
+const NX: usize = 9;
+const NZ: usize = 7;
+
+fn height(i: usize, j: usize) -> f64 {
+    ((i * 7 + j * 13) % 10) as f64 / 10.0
+}
+
+fn heightfield() -> Heightfield {
+    let heights = (0..NZ)
+        .flat_map(|j| (0..NX).map(move |i| height(i, j)))
+        .collect();
+    Heightfield::from_heights(
+        NX,
+        NZ,
+        heights,
+        &Point3::new(-1.0, 0.5, 2.0),
+        &Vec3::new(4.5, 2.0, 3.5),
+        &None,
+    )
+}
+
+#[test]
+fn grid_traversal_matches_brute_force() {
+    let field = heightfield();
+    // 同样的采样点一个个拼成三角形, 逐个求交
+    let vertex = |i: usize, j: usize| {
+        Point3::new(
+            -1.0 + (i as f64 + 0.5) * 0.5,
+            0.5 + 2.0 * height(i, j),
+            2.0 + (j as f64 + 0.5) * 0.5,
+        )
+    };
+    let mut triangles = Hittablelist::default_new();
+    for j in 0..NZ - 1 {
+        for i in 0..NX - 1 {
+            let (a, b, c, d) = (
+                vertex(i, j),
+                vertex(i + 1, j),
+                vertex(i, j + 1),
+                vertex(i + 1, j + 1),
+            );
+            triangles.add(Object::Triangle(Triangle::new(&[a, c, b], &None)));
+            triangles.add(Object::Triangle(Triangle::new(&[b, c, d], &None)));
+        }
+    }
+
+    let mut checked = 0;
+    for k in 0..2000 {
+        // 从四周和上方朝地形中间附近打
+        let a = k as f64 * 2.399;
+        let origin = Point3::new(
+            1.25 + 6.0 * a.cos(),
+            1.0 + (k % 7) as f64,
+            3.75 + 6.0 * a.sin(),
+        );
+        let target = Point3::new(
+            1.25 + ((k * 37) % 100) as f64 / 100.0 * 4.0 - 2.0,
+            1.0,
+            3.75 + ((k * 53) % 100) as f64 / 100.0 * 3.0 - 1.5,
+        );
+        let dir = target - origin;
+        match (hit(&field, origin, dir), hit(&triangles, origin, dir)) {
+            (Some(a), Some(b)) => {
+                assert!((a.t - b.t).abs() < 1e-9, "ray {}", k);
+                checked += 1;
+            }
+            (None, None) => {}
+            _ => panic!("ray {} disagrees", k),
+        }
+    }
+    assert!(checked > 500);
+}
+
+#[test]
+fn uv_lines_up_with_image_pixels() {
+    let field = heightfield();
+    for &(i, j) in [(0, 0), (3, 2), (8, 6), (5, 0)].iter() {
+        // 从正上方打到采样点上
+        let x = -1.0 + (i as f64 + 0.5) * 0.5;
+        let z = 2.0 + (j as f64 + 0.5) * 0.5;
+        let rec = hit(&field, Point3::new(x, 10.0, z), Vec3::new(0.0, -1.0, 0.0)).unwrap();
+        assert!((rec.p.y() - (0.5 + 2.0 * height(i, j))).abs() < 1e-9);
+        // Imagetexture 取的是第 (u * 宽, (1 - v) * 高) 个像素
+        assert_eq!((rec.u * NX as f64) as usize, i);
+        assert_eq!(((1.0 - rec.v) * NZ as f64) as usize, j);
+        assert!(rec.normal.y() > 0.0);
+    }
+}
+
+#[test]
+fn loads_the_sample_terrain() {
+    let path = format!(
+        "{}/../image/heightfield/terrain.png",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    let field = Heightfield::new(
+        &path,
+        &Point3::new(-8.0, 0.0, -8.0),
+        &Vec3::new(16.0, 3.0, 16.0),
+        &None,
+    )
+    .unwrap();
+    let rec = hit(
+        &field,
+        Point3::new(0.0, 10.0, 0.0),
+        Vec3::new(0.0, -1.0, 0.0),
+    )
+    .unwrap();
+    assert!(rec.p.y() >= 0.0 && rec.p.y() <= 3.0);
+
+    match Heightfield::new(
+        "missing.png",
+        &Point3::default_new(),
+        &Vec3::default_new(),
+        &None,
+    ) {
+        Err(Error::Image { path, .. }) => assert_eq!(path, "missing.png"),
+        _ => panic!("expected an image error"),
+    }
+}
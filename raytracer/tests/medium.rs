@@ -0,0 +1,144 @@
+use raytracer::{
+    rand, Boxx, ConstantMedium, Csg, Csgop, Density, Error, Hit, Hitrecord, Object, Perlin, Point3,
+    Ray, Scene, Vec3,
+};
+use std::sync::Arc;
+
+// 沿 x 方向 2 x 1 x 1 个体素, 值 0 和 2: 密度在 x < 0.5 为 0, 0.5..1.5 线性升到 2, 之后是 2
+fn ramp() -> Density {
+    Density::Grid {
+        min: Point3::new(0.0, 0.0, 0.0),
+        max: Point3::new(2.0, 1.0, 1.0),
+        size: [2, 1, 1],
+        values: Arc::new(vec![0.0, 2.0]),
+    }
+}
+
+fn unit_box(x0: f64, x1: f64) -> Object {
+    Object::Boxx(Boxx::new(
+        &Point3::new(x0, 0.0, 0.0),
+        &Point3::new(x1, 1.0, 1.0),
+        &None,
+    ))
+}
+
+fn medium(boundary: Object, density: Density) -> ConstantMedium {
+    ConstantMedium::new_with_density(&Some(Box::new(boundary)), density, &None)
+}
+
+// 沿 +x 穿过介质, 一次都没碰撞的比例
+fn transmittance(medium: &ConstantMedium, n: usize) -> f64 {
+    let r = Ray::new(&Point3::new(-1.0, 0.5, 0.5), &Vec3::new(2.0, 0.0, 0.0), 0.0);
+    let mut passed = 0;
+    for _ in 0..n {
+        let mut rec = Hitrecord::default_new();
+        if !medium.hit(&r, 0.001, f64::INFINITY, &mut rec) {
+            passed += 1;
+        }
+    }
+    passed as f64 / n as f64
+}
+
+#[test]
+fn grid_is_interpolated_between_voxel_centers() {
+    let d = ramp();
+    assert!((d.majorant() - 2.0).abs() < 1e-12);
+    assert!(d.value(&Point3::new(0.2, 0.5, 0.5)).abs() < 1e-12);
+    assert!((d.value(&Point3::new(1.0, 0.5, 0.5)) - 1.0).abs() < 1e-12);
+    assert!((d.value(&Point3::new(1.8, 0.1, 0.9)) - 2.0).abs() < 1e-12);
+    assert!(d.value(&Point3::new(2.5, 0.5, 0.5)).abs() < 1e-12);
+}
+
+#[test]
+fn noise_stays_under_its_majorant() {
+    rand::seed(5);
+    let d = Density::Noise {
+        noise: Arc::new(Perlin::default_new()),
+        scale: 3.0,
+        depth: 7,
+        threshold: 0.2,
+        density: 0.5,
+    };
+    let mut empty = 0;
+    for i in 0..2000 {
+        let p = Point3::new(
+            i as f64 * 0.013,
+            (i % 37) as f64 * 0.07,
+            (i % 11) as f64 * 0.3,
+        );
+        let v = d.value(&p);
+        assert!((0.0..=d.majorant()).contains(&v), "{}", v);
+        if v <= 0.0 {
+            empty += 1;
+        }
+    }
+    // 低于阈值的地方是空的
+    assert!(empty > 0);
+}
+
+#[test]
+fn delta_tracking_matches_optical_depth() {
+    rand::seed(7);
+    // 密度积分 0 + 1 + 1 = 2
+    let t = transmittance(&medium(unit_box(0.0, 2.0), ramp()), 40000);
+    assert!((t - (-2.0f64).exp()).abs() < 0.01, "{}", t);
+
+    // 常数密度和原来的指数采样一样
+    let t = transmittance(&medium(unit_box(0.0, 2.0), Density::Constant(0.5)), 40000);
+    assert!((t - (-1.0f64).exp()).abs() < 0.01, "{}", t);
+}
+
+#[test]
+fn collisions_stay_inside_non_convex_boundaries() {
+    rand::seed(11);
+    // 中间挖掉 [0.8, 1.2] 的两段盒子, 光学厚度 2 * 0.8 * 1.0
+    let boundary = Object::Csg(Csg::new(
+        Csgop::Difference,
+        unit_box(0.0, 2.0),
+        unit_box(0.8, 1.2),
+    ));
+    let m = medium(boundary, Density::Constant(1.0));
+    let r = Ray::new(&Point3::new(-1.0, 0.5, 0.5), &Vec3::new(1.0, 0.0, 0.0), 0.0);
+    for _ in 0..5000 {
+        let mut rec = Hitrecord::default_new();
+        if m.hit(&r, 0.001, f64::INFINITY, &mut rec) {
+            let x = rec.p.x();
+            assert!(
+                (0.0..=0.8).contains(&x) || (1.2..=2.0).contains(&x),
+                "{}",
+                x
+            );
+        }
+    }
+    let t = transmittance(&m, 40000);
+    assert!((t - (-1.6f64).exp()).abs() < 0.01, "{}", t);
+}
+
+#[test]
+fn scene_density_fields() {
+    let scene = |density: &str| {
+        Scene::parse(
+            &format!(
+                r#"{{"camera": {{"lookfrom": [0, 0, -5], "lookat": [0, 0, 0], "vfov": 40}},
+                "objects": [{{"type": "constant_medium", "albedo": [0.8, 0.8, 0.8], "density": {},
+                "boundary": {{"type": "sphere", "center": [0, 0, 0], "radius": 1,
+                "material": {{"type": "dielectric", "ir": 1.0}}}}}}]}}"#,
+                density
+            ),
+            1.0,
+        )
+    };
+    assert!(scene("0.5").is_ok());
+    assert!(scene(r#"{"type": "noise", "density": 0.5, "scale": 2, "threshold": 0.1}"#).is_ok());
+    assert!(scene(
+        r#"{"type": "grid", "min": [-1, -1, -1], "max": [1, 1, 1], "size": [2, 1, 1], "values": [0, 1]}"#
+    )
+    .is_ok());
+    match scene(
+        r#"{"type": "grid", "min": [-1, -1, -1], "max": [1, 1, 1], "size": [2, 2, 1], "values": [0, 1]}"#,
+    ) {
+        Err(Error::Scene(message)) => assert!(message.contains("expected 4"), "{}", message),
+        _ => panic!("expected a scene error"),
+    }
+    assert!(scene(r#"{"type": "noise", "density": 0.5, "scale": 2, "threshold": 1.0}"#).is_err());
+}
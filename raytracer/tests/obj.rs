@@ -0,0 +1,100 @@
+mod common;
+
+use common::hit;
+use raytracer::{
+    Bvhsplit, Color, Emitted, Error, Lambertian, Material, Obj, Point3, Ray, Scatter,
+    Scatterrecord, Vec3,
+};
+use std::sync::Arc;
+
+#[test]
+fn file_without_faces_is_an_error() {
+    let path = std::env::temp_dir().join("raytracer_points.obj");
+    std::fs::write(&path, "v 0 0 0\nv 1 0 0\nv 0 1 0\n").unwrap();
+    let path = path.to_str().unwrap();
+    match Obj::new(path, &None, 0.0, 1.0, Bvhsplit::Sah) {
+        Err(Error::Scene(message)) => {
+            assert!(message.contains(path), "{}", message);
+            assert!(message.contains("no triangles"), "{}", message);
+        }
+        _ => panic!("expected a scene error"),
+    }
+}
+
+#[test]
+fn missing_mtl_is_an_error_unless_overridden() {
+    let path = std::env::temp_dir().join("raytracer_missing_mtl.obj");
+    std::fs::write(
+        &path,
+        "mtllib raytracer_no_such.mtl\nv 0 0 0\nv 1 0 0\nv 0 1 0\nusemtl red\nf 1 2 3\n",
+    )
+    .unwrap();
+    let path = path.to_str().unwrap();
+    match Obj::new(path, &None, 0.0, 1.0, Bvhsplit::Sah) {
+        Err(Error::Obj { path: p, .. }) => assert_eq!(p, path),
+        _ => panic!("expected an obj error"),
+    }
+    let red = Some(Arc::new(Material::Lambertian(Lambertian::new(
+        &Color::new(0.8, 0.1, 0.1),
+    ))));
+    assert!(Obj::new(path, &red, 0.0, 1.0, Bvhsplit::Sah).is_ok());
+}
+
+#[test]
+fn mtl_materials_are_mapped() {
+    let dir = std::env::temp_dir();
+    std::fs::write(
+        dir.join("raytracer_mapping.mtl"),
+        "newmtl matte\nKd 0.2 0.4 0.6\nillum 2\n\
+         newmtl mirror\nKd 0 0 0\nKs 0.9 0.8 0.7\nNs 900\nillum 3\n\
+         newmtl glass\nKd 1 1 1\nNi 1.7\nd 0.1\nillum 4\n\
+         newmtl lamp\nKd 0 0 0\nKe 6 5 4\nillum 2\n",
+    )
+    .unwrap();
+    // 每种材质一个三角形, 第 i 个在 x ∈ [2i, 2i + 1]
+    let mut text = String::from("mtllib raytracer_mapping.mtl\n");
+    for (i, name) in ["matte", "mirror", "glass", "lamp"].iter().enumerate() {
+        let x = 2.0 * i as f64;
+        text += &format!(
+            "v {} 0 0\nv {} 0 0\nv {} 1 0\nusemtl {}\nf {} {} {}\n",
+            x,
+            x + 1.0,
+            x,
+            name,
+            3 * i + 1,
+            3 * i + 2,
+            3 * i + 3
+        );
+    }
+    let path = dir.join("raytracer_mapping.obj");
+    std::fs::write(&path, text).unwrap();
+    let obj = Obj::new(path.to_str().unwrap(), &None, 0.0, 1.0, Bvhsplit::Sah).unwrap();
+
+    let material = |i: usize| {
+        let origin = Point3::new(2.0 * i as f64 + 0.25, 0.25, 1.0);
+        let rec = hit(&obj, origin, Vec3::new(0.0, 0.0, -1.0)).unwrap();
+        let mut srec = Scatterrecord::default_new();
+        let r = Ray::new(&origin, &Vec3::new(0.0, -0.1, -1.0), 0.0);
+        let scattered = rec.mat_ptr.as_ref().unwrap().scatter(&r, &rec, &mut srec);
+        (rec.mat_ptr.unwrap(), scattered, srec)
+    };
+
+    let (matte, _, srec) = material(0);
+    assert!(matches!(*matte, Material::Lambertian(_)));
+    assert!((srec.attenuation - Color::new(0.2, 0.4, 0.6)).length() < 1e-6);
+
+    let (mirror, scattered, srec) = material(1);
+    assert!(matches!(*mirror, Material::Metal(_)));
+    assert!(scattered && srec.is_specular);
+    assert!((srec.attenuation - Color::new(0.9, 0.8, 0.7)).length() < 1e-6);
+
+    match &*material(2).0 {
+        Material::Dielectric(glass) => assert!((glass.ir - 1.7).abs() < 1e-6),
+        _ => panic!("expected a dielectric"),
+    }
+
+    let (lamp, scattered, _) = material(3);
+    assert!(!scattered);
+    let emitted = lamp.emitted(0.0, 0.0, &Point3::new(6.25, 0.25, 0.0));
+    assert!((emitted - Color::new(6.0, 5.0, 4.0)).length() < 1e-6);
+}
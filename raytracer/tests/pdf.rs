@@ -0,0 +1,78 @@
+use raytracer::{
+    rand, Color, Hitrecord, Lambertian, Lightsample, Metal, Point3, Quad, Ray, Scatter,
+    Scatterrecord, Sphere, Vec3, XZrect,
+};
+
+// 在整个球面上均匀取方向估计 ∫ pdf dω, 光源采样的 pdf 积分应该是 1
+fn integrate(light: &dyn Lightsample, o: &Point3, n: usize) -> f64 {
+    let mut sum = 0.0;
+    for _ in 0..n {
+        sum += light.pdf_value(o, &Vec3::random_unit_vector());
+    }
+    sum / n as f64 * 4.0 * std::f64::consts::PI
+}
+
+// 按 random 采到的方向都打得到光源
+fn samples_have_positive_pdf(light: &dyn Lightsample, o: &Point3) {
+    for _ in 0..1000 {
+        let v = light.random(o);
+        let pdf = light.pdf_value(o, &v);
+        assert!(pdf > 0.0 && pdf.is_finite(), "{}", pdf);
+    }
+}
+
+#[test]
+fn light_pdfs_integrate_to_one() {
+    rand::seed(31);
+    let o = Point3::new(0.0, 0.0, 0.0);
+    let sphere = Sphere::new(&Point3::new(0.0, 0.5, -3.0), 1.0, &None);
+    let quad = Quad::new(
+        &Point3::new(-1.0, 2.0, -1.0),
+        &Vec3::new(2.0, 0.5, 0.0),
+        &Vec3::new(0.0, 0.0, 2.0),
+        &None,
+    );
+    let rect = XZrect::new(&None, -1.0, 2.0, -2.0, 1.0, 1.5);
+    let lights: [(&str, &dyn Lightsample); 3] =
+        [("sphere", &sphere), ("quad", &quad), ("xz_rect", &rect)];
+    for (name, light) in lights.iter() {
+        let total = integrate(*light, &o, 400000);
+        assert!((total - 1.0).abs() < 0.03, "{}: {}", name, total);
+        samples_have_positive_pdf(*light, &o);
+    }
+}
+
+#[test]
+fn lambertian_pdf_matches_its_sampling() {
+    rand::seed(37);
+    let lambertian = Lambertian::new(&Color::new(0.4, 0.6, 0.8));
+    let mut rec = Hitrecord::default_new();
+    rec.normal = Vec3::new(1.0, 2.0, 2.0).unit_vector();
+    let r_in = Ray::new(&Point3::new(1.0, 1.0, 1.0), &-rec.normal, 0.0);
+
+    let n = 400000;
+    let mut sum = 0.0;
+    for _ in 0..n {
+        let scattered = Ray::new(&rec.p, &Vec3::random_unit_vector(), 0.0);
+        sum += lambertian.scattering_pdf(&r_in, &rec, &scattered);
+    }
+    let total = sum / n as f64 * 4.0 * std::f64::consts::PI;
+    assert!((total - 1.0).abs() < 0.01, "{}", total);
+
+    for _ in 0..1000 {
+        let mut srec = Scatterrecord::default_new();
+        assert!(lambertian.scatter(&r_in, &rec, &mut srec));
+        assert!(!srec.is_specular);
+        let pdf = lambertian.scattering_pdf(&r_in, &rec, &srec.scattered);
+        assert!((srec.pdf - pdf).abs() < 1e-9);
+        // 按 cos 采样时路径权重就是 albedo
+        let weighted = lambertian.eval(&r_in, &rec, &srec.scattered) / pdf;
+        assert!((weighted - srec.attenuation).length() < 1e-9);
+    }
+
+    // 镜面材质跳过 MIS
+    let metal = Metal::new(&Color::new(0.8, 0.8, 0.8), 0.0);
+    let mut srec = Scatterrecord::default_new();
+    assert!(metal.scatter(&r_in, &rec, &mut srec));
+    assert!(srec.is_specular);
+}
@@ -0,0 +1,80 @@
+use raytracer::{
+    rand, Color, Henyeygreenstein, Hitrecord, Point3, Ray, Scatter, Scatterrecord, Scene, Vec3,
+};
+
+#[test]
+fn phase_integrates_to_one() {
+    let n = 200000;
+    for &g in [-0.7, 0.0, 0.3, 0.9].iter() {
+        // 绕入射方向对称, 球面积分化成 2π ∫ p(cos) dcos
+        let mut sum = 0.0;
+        for i in 0..n {
+            let cos_theta = -1.0 + 2.0 * (i as f64 + 0.5) / n as f64;
+            sum += Henyeygreenstein::phase(g, cos_theta) * 2.0 / n as f64;
+        }
+        sum *= 2.0 * std::f64::consts::PI;
+        assert!((sum - 1.0).abs() < 1e-3, "g = {}: {}", g, sum);
+    }
+}
+
+#[test]
+fn sampling_agrees_with_pdf_and_eval() {
+    rand::seed(3);
+    let g = 0.6;
+    let hg = Henyeygreenstein::new_from_color(&Color::new(0.5, 0.7, 0.9), g);
+    let rec = Hitrecord::default_new();
+    let r_in = Ray::new(&Point3::new(0.0, 0.0, 0.0), &Vec3::new(1.0, 2.0, -2.0), 0.0);
+    let forward = r_in.direction().unit_vector();
+
+    let n = 100000;
+    let mut mean_cos = 0.0;
+    for _ in 0..n {
+        let mut srec = Scatterrecord::default_new();
+        assert!(hg.scatter(&r_in, &rec, &mut srec));
+        let pdf = hg.scattering_pdf(&r_in, &rec, &srec.scattered);
+        assert!((srec.pdf - pdf).abs() < 1e-9 * pdf.max(1.0));
+        // 正好按相函数采样, 路径权重就是 albedo
+        let weighted = hg.eval(&r_in, &rec, &srec.scattered) / pdf;
+        assert!((weighted - srec.attenuation).length() < 1e-9);
+        mean_cos += Vec3::dot(&forward, &srec.scattered.direction().unit_vector());
+    }
+    // Henyey-Greenstein 的平均余弦就是 g
+    mean_cos /= n as f64;
+    assert!((mean_cos - g).abs() < 0.01, "{}", mean_cos);
+}
+
+#[test]
+fn forward_scattering_prefers_the_incoming_direction() {
+    let r_in = Ray::new(&Point3::new(0.0, 0.0, 0.0), &Vec3::new(0.0, 0.0, 1.0), 0.0);
+    let hg = Henyeygreenstein::new_from_color(&Color::new(1.0, 1.0, 1.0), 0.8);
+    let rec = Hitrecord::default_new();
+    let ahead = Ray::new(&Point3::new(0.0, 0.0, 0.0), &Vec3::new(0.0, 0.0, 1.0), 0.0);
+    let behind = Ray::new(&Point3::new(0.0, 0.0, 0.0), &Vec3::new(0.0, 0.0, -1.0), 0.0);
+    assert!(
+        hg.scattering_pdf(&r_in, &rec, &ahead) > 100.0 * hg.scattering_pdf(&r_in, &rec, &behind)
+    );
+    // g = 0 时各个方向都是 1 / 4π
+    let iso = Henyeygreenstein::new_from_color(&Color::new(1.0, 1.0, 1.0), 0.0);
+    let quarter = 1.0 / (4.0 * std::f64::consts::PI);
+    assert!((iso.scattering_pdf(&r_in, &rec, &ahead) - quarter).abs() < 1e-12);
+    assert!((iso.scattering_pdf(&r_in, &rec, &behind) - quarter).abs() < 1e-12);
+}
+
+#[test]
+fn scene_asymmetry_parameter() {
+    let scene = |g: &str| {
+        Scene::parse(
+            &format!(
+                r#"{{"camera": {{"lookfrom": [0, 0, -5], "lookat": [0, 0, 0], "vfov": 40}},
+                "objects": [{{"type": "constant_medium", "albedo": [0.8, 0.8, 0.8], "density": 0.5, "g": {},
+                "boundary": {{"type": "sphere", "center": [0, 0, 0], "radius": 1,
+                "material": {{"type": "dielectric", "ir": 1.0}}}}}}]}}"#,
+                g
+            ),
+            1.0,
+        )
+    };
+    assert!(scene("0.8").is_ok());
+    assert!(scene("-0.3").is_ok());
+    assert!(scene("1.0").is_err());
+}
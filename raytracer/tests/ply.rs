@@ -0,0 +1,108 @@
+mod common;
+
+use common::hit;
+use raytracer::{Bvhsplit, Color, Error, Ply, Point3, Ray, Scatter, Scatterrecord, Vec3};
+
+fn sample(name: &str) -> String {
+    format!("{}/../image/ply/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+fn load(path: &str) -> Result<Ply, Error> {
+    Ply::load(path, &None, 0.0, 1.0, Bvhsplit::Sah)
+}
+
+#[test]
+fn ascii_and_binary_files_match() {
+    let directions = [
+        Vec3::new(0.01, 0.02, -1.0),
+        Vec3::new(0.1, -0.08, -1.0),
+        Vec3::new(-0.05, 0.12, -1.0),
+    ];
+    let plys: Vec<Ply> = ["sphere_ascii.ply", "sphere_le.ply", "sphere_be.ply"]
+        .iter()
+        .map(|name| load(&sample(name)).unwrap())
+        .collect();
+    for ply in &plys {
+        assert_eq!(ply.triangle_count, 320);
+    }
+    for direction in directions.iter() {
+        let a = hit(&plys[0].mesh, Point3::new(0.0, 0.0, 5.0), *direction).unwrap();
+        for ply in &plys[1..] {
+            let b = hit(&ply.mesh, Point3::new(0.0, 0.0, 5.0), *direction).unwrap();
+            // ASCII 文件里的坐标只有 6 位小数
+            assert!((a.t - b.t).abs() < 1e-5);
+            assert!((a.normal - b.normal).length() < 1e-5);
+        }
+    }
+}
+
+#[test]
+fn polygons_are_triangulated() {
+    let path = std::env::temp_dir().join("raytracer_quad.ply");
+    std::fs::write(
+        &path,
+        "ply\nformat ascii 1.0\nelement vertex 4\nproperty float x\nproperty float y\nproperty float z\n\
+         element face 1\nproperty list uchar uint vertex_index\nend_header\n\
+         0 0 0\n1 0 0\n1 1 0\n0 1 0\n4 0 1 2 3\n",
+    )
+    .unwrap();
+    let ply = load(path.to_str().unwrap()).unwrap();
+    assert_eq!(ply.triangle_count, 2);
+    // 两个三角形各打一下
+    assert!(hit(
+        &ply.mesh,
+        Point3::new(0.8, 0.2, 1.0),
+        Vec3::new(0.0, 0.0, -1.0)
+    )
+    .is_some());
+    assert!(hit(
+        &ply.mesh,
+        Point3::new(0.2, 0.8, 1.0),
+        Vec3::new(0.0, 0.0, -1.0)
+    )
+    .is_some());
+}
+
+#[test]
+fn truncated_file_is_an_error() {
+    let data = std::fs::read(sample("sphere_le.ply")).unwrap();
+    let path = std::env::temp_dir().join("raytracer_truncated.ply");
+    std::fs::write(&path, &data[..data.len() - 10]).unwrap();
+    match load(path.to_str().unwrap()) {
+        Err(Error::Ply { message, .. }) => assert_eq!(message, "unexpected end of file"),
+        _ => panic!("expected a ply error"),
+    }
+}
+
+#[test]
+fn vertex_normals_and_colors_are_interpolated() {
+    let path = std::env::temp_dir().join("raytracer_colored.ply");
+    std::fs::write(
+        &path,
+        "ply\nformat ascii 1.0\nelement vertex 3\nproperty float x\nproperty float y\nproperty float z\n\
+         property float nx\nproperty float ny\nproperty float nz\n\
+         property uchar red\nproperty uchar green\nproperty uchar blue\n\
+         element face 1\nproperty list uchar uint vertex_index\nend_header\n\
+         0 0 0 0 0 1 255 0 0\n1 0 0 1 0 1 0 255 0\n0 1 0 0 1 1 0 0 255\n3 0 1 2\n",
+    )
+    .unwrap();
+    let ply = load(path.to_str().unwrap()).unwrap();
+    // 三个顶点的权重依次是 0.5, 0.2, 0.3
+    let rec = hit(
+        &ply.mesh,
+        Point3::new(0.2, 0.3, 1.0),
+        Vec3::new(0.0, 0.0, -1.0),
+    )
+    .unwrap();
+
+    // 着色法线偏离面法线 (0, 0, 1), 往权重大的第三个顶点那边偏得多
+    assert!((rec.normal.length() - 1.0).abs() < 1e-9);
+    assert!(rec.normal.z() < 0.99);
+    assert!(rec.normal.x() > 0.0 && rec.normal.y() > rec.normal.x());
+
+    let mut srec = Scatterrecord::default_new();
+    let r = Ray::new(&Point3::new(0.2, 0.3, 1.0), &Vec3::new(0.0, 0.0, -1.0), 0.0);
+    assert!(rec.mat_ptr.as_ref().unwrap().scatter(&r, &rec, &mut srec));
+    let expected = Color::new(0.5, 0.2, 0.3);
+    assert!((srec.attenuation - expected).length() < 1e-9);
+}
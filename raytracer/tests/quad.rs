@@ -0,0 +1,44 @@
+mod common;
+
+use common::hit;
+use raytracer::{Lightsample, Point3, Quad, Vec3};
+
+#[test]
+fn tilted_quad_uv_and_bounds() {
+    // 绕 x 轴斜 45 度的 2 x 2 平行四边形
+    let quad = Quad::new(
+        &Point3::new(0.0, 0.0, 0.0),
+        &Vec3::new(2.0, 0.0, 0.0),
+        &Vec3::new(0.0, 1.0, 1.0),
+        &None,
+    );
+    let down = Vec3::new(0.0, -1.0, 0.0);
+    let rec = hit(&quad, Point3::new(0.5, 5.0, 0.25), down).unwrap();
+    assert!((rec.p.y() - 0.25).abs() < 1e-12);
+    assert!((rec.u - 0.25).abs() < 1e-12);
+    assert!((rec.v - 0.25).abs() < 1e-12);
+    // 外法线 u x v 朝 (0, -1, 1), 从上面打到的是背面
+    assert!(!rec.front_face);
+
+    assert!(hit(&quad, Point3::new(2.5, 5.0, 0.5), down).is_none());
+    assert!(hit(&quad, Point3::new(0.5, 5.0, 1.5), down).is_none());
+    // 和平面平行
+    assert!(hit(&quad, Point3::new(-1.0, 0.5, 0.5), Vec3::new(1.0, 0.0, 0.0)).is_none());
+}
+
+#[test]
+fn sampled_directions_hit_the_quad() {
+    let quad = Quad::new(
+        &Point3::new(-1.0, 3.0, -1.0),
+        &Vec3::new(2.0, 0.5, 0.0),
+        &Vec3::new(0.0, 0.0, 2.0),
+        &None,
+    );
+    let o = Point3::new(0.0, 0.0, 0.0);
+    for _ in 0..100 {
+        let v = quad.random(&o);
+        let pdf = quad.pdf_value(&o, &v);
+        assert!(pdf > 0.0 && pdf.is_finite());
+    }
+    assert!(quad.pdf_value(&o, &Vec3::new(0.0, -1.0, 0.0)).abs() < 1e-12);
+}
@@ -0,0 +1,51 @@
+use raytracer::{render, RenderSettings, Scene};
+
+const SCENE: &str = r#"{
+    "camera": { "lookfrom": [0, 0, 5], "lookat": [0, 0, 0], "vfov": 40 },
+    "background": [0.7, 0.8, 1.0],
+    "materials": { "ground": { "type": "lambertian", "albedo": [0.5, 0.5, 0.5] } },
+    "objects": [{ "type": "sphere", "center": [0, 0, 0], "radius": 1, "material": "ground" }]
+}"#;
+
+fn settings(threads: usize) -> RenderSettings {
+    let mut settings = RenderSettings::new(32, 18);
+    settings.samples_per_pixel = 4;
+    settings.max_depth = 8;
+    settings.threads = threads;
+    settings.seed = Some(42);
+    settings
+}
+
+#[test]
+fn renders_requested_size() {
+    let settings = settings(2);
+    let scene = Scene::parse(SCENE, settings.aspect_ratio()).unwrap();
+    let img = render(&scene, settings);
+    assert_eq!(img.dimensions(), (32, 18));
+    // 四角是背景, 中心是球
+    assert_ne!(img.get_pixel(0, 0), img.get_pixel(16, 9));
+}
+
+#[test]
+fn same_seed_same_image() {
+    let scene = Scene::parse(SCENE, settings(3).aspect_ratio()).unwrap();
+    let a = render(&scene, settings(3));
+    let b = render(&scene, settings(3));
+    assert_eq!(a.into_raw(), b.into_raw());
+}
+
+#[test]
+fn single_row_and_column_images() {
+    for &(width, height) in [(1, 1), (4, 1), (1, 4)].iter() {
+        let mut settings = RenderSettings::new(width, height);
+        settings.samples_per_pixel = 2;
+        settings.seed = Some(1);
+        let scene = Scene::parse(SCENE, settings.aspect_ratio()).unwrap();
+        let img = render(&scene, settings);
+        assert_eq!(img.dimensions(), (width, height));
+        // 除以 0 的光线方向是 NaN, 颜色会变成黑的
+        for pixel in img.pixels() {
+            assert_ne!(pixel.0, [0, 0, 0]);
+        }
+    }
+}
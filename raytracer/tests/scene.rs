@@ -0,0 +1,113 @@
+use raytracer::{Error, Scene};
+
+fn scene_error(object: &str) -> String {
+    let text = format!(
+        r#"{{"camera": {{"lookfrom": [0, 0, -5], "lookat": [0, 0, 0], "vfov": 40}},
+        "objects": [{}]}}"#,
+        object
+    );
+    match Scene::parse(&text, 1.0) {
+        Err(Error::Scene(message)) => message,
+        Err(e) => panic!("expected a scene error, got {}", e),
+        Ok(_) => panic!("expected a scene error"),
+    }
+}
+
+#[test]
+fn inline_mistakes_report_the_inner_error() {
+    let message = scene_error(
+        r#"{"type": "sphere", "center": [0, 0, 0], "radius": 1,
+            "material": {"type": "lambertian", "albdo": [0.5, 0.5, 0.5]}}"#,
+    );
+    assert!(message.contains("unknown field `albdo`"), "{}", message);
+    assert!(message.contains("line"), "{}", message);
+
+    let message = scene_error(
+        r#"{"type": "sphere", "center": [0, 0, 0], "radius": 1,
+            "material": {"type": "lambertian", "albedo": [0.5, 0.5]}}"#,
+    );
+    assert!(message.contains("invalid length 2"), "{}", message);
+
+    let message =
+        scene_error(r#"{"type": "sphere", "center": [0, 0, 0], "radius": 1, "material": 3}"#);
+    assert!(message.contains("material name"), "{}", message);
+
+    let message = scene_error(
+        r#"{"type": "constant_medium", "albedo": [1, 1, 1],
+            "density": {"type": "noise", "density": 0.5, "scael": 2, "threshold": 0.1},
+            "boundary": {"type": "sphere", "center": [0, 0, 0], "radius": 1, "material": "glass"}}"#,
+    );
+    assert!(message.contains("unknown field `scael`"), "{}", message);
+}
+
+#[test]
+fn unknown_material_names_are_reported() {
+    let message =
+        scene_error(r#"{"type": "sphere", "center": [0, 0, 0], "radius": 1, "material": "glass"}"#);
+    assert!(message.contains("glass"), "{}", message);
+}
+
+#[test]
+fn textures_and_prototypes_may_share_a_name() {
+    let text = r#"{"camera": {"lookfrom": [0, 0, -5], "lookat": [0, 0, 0], "vfov": 40},
+        "textures": {"ball": {"type": "checker", "even": [0.2, 0.3, 0.1], "odd": [0.9, 0.9, 0.9]}},
+        "prototypes": {"ball": {"type": "sphere", "center": [0, 0, 0], "radius": 1,
+                                "material": {"type": "lambertian", "albedo": "ball"}}},
+        "objects": [{"type": "instance", "prototype": "ball"}]}"#;
+    if let Err(e) = Scene::parse(text, 1.0) {
+        panic!("{}", e);
+    }
+
+    // 原型自己引用自己仍然要报错
+    let looping = text.replace(
+        r#""ball": {"type": "sphere", "center": [0, 0, 0], "radius": 1,
+                                "material": {"type": "lambertian", "albedo": "ball"}}"#,
+        r#""ball": {"type": "instance", "prototype": "ball"}"#,
+    );
+    match Scene::parse(&looping, 1.0) {
+        Err(Error::Scene(message)) => {
+            assert!(message.contains("references itself"), "{}", message)
+        }
+        _ => panic!("expected a scene error"),
+    }
+}
+
+#[test]
+fn lights_that_cannot_be_sampled_are_reported() {
+    let parse = |object: &str| {
+        let text = format!(
+            r#"{{"camera": {{"lookfrom": [0, 0, -5], "lookat": [0, 0, 0], "vfov": 40}},
+            "materials": {{"lamp": {{"type": "diffuse_light", "emit": [4, 4, 4]}}}},
+            "objects": [{}]}}"#,
+            object
+        );
+        Scene::parse(&text, 1.0).unwrap()
+    };
+
+    let scene =
+        parse(r#"{"type": "sphere", "center": [0, 0, 0], "radius": 1, "material": "lamp"}"#);
+    assert!(scene.warnings.is_empty());
+    assert_eq!(scene.lights.objects.len(), 1);
+
+    let scene = parse(
+        r#"{"type": "cylinder", "center": [0, 0, 0], "radius": 1, "height": 2, "material": "lamp"}"#,
+    );
+    assert_eq!(scene.warnings.len(), 1);
+    assert!(
+        scene.warnings[0].contains("object #0 (cylinder)"),
+        "{}",
+        scene.warnings[0]
+    );
+    assert!(scene.lights.objects.is_empty());
+
+    let scene = parse(
+        r#"{"type": "animated", "keyframes": [{"time": 0}, {"time": 1, "translate": [1, 0, 0]}],
+            "object": {"type": "sphere", "center": [0, 0, 0], "radius": 1, "material": "lamp"}}"#,
+    );
+    assert_eq!(scene.warnings.len(), 1);
+    assert!(
+        scene.warnings[0].contains("animated"),
+        "{}",
+        scene.warnings[0]
+    );
+}
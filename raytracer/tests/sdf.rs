@@ -0,0 +1,109 @@
+mod common;
+
+use common::hit;
+use raytracer::{Point3, Sdf, Sdfshape, Vec3};
+
+fn sphere(x: f64, radius: f64) -> Box<Sdfshape> {
+    Box::new(Sdfshape::Translate {
+        offset: Vec3::new(x, 0.0, 0.0),
+        shape: Box::new(Sdfshape::Sphere { radius }),
+    })
+}
+
+#[test]
+fn sphere_matches_the_analytic_hit() {
+    let sdf = Sdf::new(*sphere(1.0, 2.0), &None);
+    // 方向不是单位向量, t 也要对
+    let rec = hit(&sdf, Point3::new(1.0, 0.5, 10.0), Vec3::new(0.0, 0.0, -2.0)).unwrap();
+    let z = (4.0_f64 - 0.25).sqrt();
+    assert!((rec.t - (10.0 - z) / 2.0).abs() < 1e-3);
+    assert!((rec.normal - Vec3::new(0.0, 0.5, z) / 2.0).length() < 1e-3);
+    assert!(rec.front_face);
+    assert!(hit(&sdf, Point3::new(3.5, 0.0, 10.0), Vec3::new(0.0, 0.0, -1.0)).is_none());
+
+    // 从里面出发能找到出口
+    let rec = hit(&sdf, Point3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0)).unwrap();
+    assert!((rec.t - 2.0).abs() < 1e-3);
+    assert!(!rec.front_face);
+}
+
+#[test]
+fn bounds_contain_the_surface() {
+    let shapes = vec![
+        Sdfshape::Union {
+            smoothness: 1.0,
+            left: sphere(-1.0, 0.7),
+            right: sphere(1.0, 0.7),
+        },
+        Sdfshape::Twist {
+            rate: 2.0,
+            shape: Box::new(Sdfshape::RoundBox {
+                half_size: Vec3::new(0.3, 1.0, 0.6),
+                radius: 0.1,
+            }),
+        },
+        Sdfshape::Repeat {
+            spacing: Vec3::new(1.0, 0.0, 2.0),
+            count: [2, 5, 1],
+            shape: Box::new(Sdfshape::Torus {
+                major_radius: 0.3,
+                minor_radius: 0.1,
+            }),
+        },
+    ];
+    for shape in &shapes {
+        let (min, max) = shape.bounds();
+        // 在比包围盒大一圈的网格上找, 里面的点都不能在包围盒外
+        let n = 40;
+        for i in 0..=n {
+            for j in 0..=n {
+                for k in 0..=n {
+                    let f = |a: usize, lo: f64, hi: f64| {
+                        let pad = 0.5 * (hi - lo) + 0.5;
+                        lo - pad + (hi - lo + 2.0 * pad) * a as f64 / n as f64
+                    };
+                    let p = Point3::new(
+                        f(i, min.x(), max.x()),
+                        f(j, min.y(), max.y()),
+                        f(k, min.z(), max.z()),
+                    );
+                    if shape.distance(&p) < 0.0 {
+                        for a in 0..3 {
+                            assert!(p[a] >= min[a] - 1e-9 && p[a] <= max[a] + 1e-9);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn twisted_box_is_not_overshot() {
+    // 扭得很厉害的细长方块, 步长不缩短的话会直接穿过去
+    let sdf = Sdf::new(
+        Sdfshape::Twist {
+            rate: 6.0,
+            shape: Box::new(Sdfshape::Box {
+                half_size: Vec3::new(1.0, 1.0, 0.05),
+            }),
+        },
+        &None,
+    );
+    for i in 0..50 {
+        let y = -0.9 + 1.8 * i as f64 / 49.0;
+        let origin = Point3::new(0.0, y, 5.0);
+        let dir = Vec3::new(0.0, 0.0, -1.0);
+        let rec = hit(&sdf, origin, dir);
+        // 沿光线细细地走, 找第一个在里面的点
+        let first_inside = (0..200_000)
+            .map(|s| s as f64 * 0.0001)
+            .find(|&s| sdf.distance(&(origin + dir * s)) < 0.0);
+        match (rec, first_inside) {
+            // 停在离表面 EPSILON 的地方, 擦着表面时会早一点, 但绝不能走到里面去
+            (Some(rec), Some(s)) => assert!(rec.t <= s && s - rec.t < 0.01, "y = {}", y),
+            (None, None) => {}
+            _ => panic!("y = {}", y),
+        }
+    }
+}
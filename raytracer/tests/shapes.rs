@@ -0,0 +1,106 @@
+mod common;
+
+use common::hit;
+use raytracer::{Cone, Cylinder, Disk, Point3, Torus, Vec3};
+
+#[test]
+fn disk_is_bounded_by_radius() {
+    let disk = Disk::new(&Point3::new(0.0, 1.0, 0.0), 2.0, &None);
+    let down = Vec3::new(0.0, -1.0, 0.0);
+    let rec = hit(&disk, Point3::new(1.0, 3.0, 1.0), down).unwrap();
+    assert!((rec.t - 2.0).abs() < 1e-12);
+    assert!((rec.normal.y() - 1.0).abs() < 1e-12);
+    assert!((rec.v - 2.0_f64.sqrt() / 2.0).abs() < 1e-12);
+    assert!(hit(&disk, Point3::new(1.5, 3.0, 1.5), down).is_none());
+}
+
+#[test]
+fn cylinder_side_and_caps() {
+    let capped = Cylinder::new(&Point3::new(0.0, 0.0, 0.0), 1.0, 2.0, true, &None);
+    let open = Cylinder::new(&Point3::new(0.0, 0.0, 0.0), 1.0, 2.0, false, &None);
+
+    let rec = hit(
+        &capped,
+        Point3::new(-5.0, 1.0, 0.0),
+        Vec3::new(1.0, 0.0, 0.0),
+    )
+    .unwrap();
+    assert!((rec.t - 4.0).abs() < 1e-12);
+    assert!((rec.normal.x() + 1.0).abs() < 1e-12);
+    assert!((rec.v - 0.5).abs() < 1e-12);
+
+    // 竖直往下打: 有盖子停在顶面, 没盖子碰不到侧面, 直接穿过
+    let down = Vec3::new(0.0, -1.0, 0.0);
+    let rec = hit(&capped, Point3::new(0.3, 5.0, 0.0), down).unwrap();
+    assert!((rec.t - 3.0).abs() < 1e-12);
+    assert!((rec.normal.y() - 1.0).abs() < 1e-12);
+    assert!(hit(&open, Point3::new(0.3, 5.0, 0.0), down).is_none());
+
+    // 斜着从开口射进去, 打到内壁, 法线朝里
+    let rec = hit(&open, Point3::new(0.0, 3.0, 0.0), Vec3::new(1.0, -1.0, 0.0)).unwrap();
+    assert!((rec.p.x() - 1.0).abs() < 1e-9 && (rec.p.y() - 2.0).abs() < 1e-9);
+    assert!(!rec.front_face);
+}
+
+#[test]
+fn cone_narrows_to_apex() {
+    let cone = Cone::new(&Point3::new(0.0, 0.0, 0.0), 1.0, 2.0, true, &None);
+    let right = Vec3::new(1.0, 0.0, 0.0);
+    // y = 1 处半径是 0.5
+    let rec = hit(&cone, Point3::new(-5.0, 1.0, 0.0), right).unwrap();
+    assert!((rec.p.x() + 0.5).abs() < 1e-9);
+    let expected = Vec3::new(-2.0, 1.0, 0.0).unit_vector();
+    assert!((rec.normal - expected).length() < 1e-9);
+    // 顶点上面和镜像的另一半锥都打不到
+    assert!(hit(&cone, Point3::new(-5.0, 2.5, 0.0), right).is_none());
+    assert!(hit(&cone, Point3::new(0.0, 3.0, -5.0), Vec3::new(0.0, 0.0, 1.0)).is_none());
+    // 底面
+    let rec = hit(&cone, Point3::new(0.5, -3.0, 0.0), Vec3::new(0.0, 1.0, 0.0)).unwrap();
+    assert!((rec.t - 3.0).abs() < 1e-12);
+    assert!((rec.normal.y() + 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn torus_has_a_hole() {
+    let torus = Torus::new(&Point3::new(0.0, 0.0, 0.0), 2.0, 0.5, &None);
+    // 沿 y 轴穿过中间的洞
+    assert!(hit(
+        &torus,
+        Point3::new(0.0, 5.0, 0.0),
+        Vec3::new(0.0, -1.0, 0.0)
+    )
+    .is_none());
+    // 在 xz 平面里横穿, 先后打到 x = -2.5, -1.5
+    let rec = hit(
+        &torus,
+        Point3::new(-5.0, 0.0, 0.0),
+        Vec3::new(2.0, 0.0, 0.0),
+    )
+    .unwrap();
+    assert!((rec.t - 1.25).abs() < 1e-9);
+    assert!((rec.normal.x() + 1.0).abs() < 1e-9);
+    let rec = hit(
+        &torus,
+        Point3::new(-2.0, 0.0, 0.0),
+        Vec3::new(1.0, 0.0, 0.0),
+    )
+    .unwrap();
+    assert!((rec.p.x() + 1.5).abs() < 1e-9);
+    assert!(!rec.front_face);
+    // 从上面打到圆管顶部
+    let rec = hit(
+        &torus,
+        Point3::new(0.0, 5.0, 2.0),
+        Vec3::new(0.0, -1.0, 0.0),
+    )
+    .unwrap();
+    assert!((rec.p.y() - 0.5).abs() < 1e-9);
+    assert!((rec.v - 0.75).abs() < 1e-9);
+    // 擦着外边过去
+    assert!(hit(
+        &torus,
+        Point3::new(-5.0, 0.6, 0.0),
+        Vec3::new(1.0, 0.0, 0.0)
+    )
+    .is_none());
+}
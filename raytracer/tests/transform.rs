@@ -0,0 +1,93 @@
+mod common;
+
+use common::hit;
+use raytracer::{Matrix4, Object, Point3, Sphere, Transform, Vec3};
+
+fn skewed() -> Matrix4 {
+    Matrix4::translate(&Vec3::new(1.0, -2.0, 3.0))
+        * Matrix4::rotate_y(30.0)
+        * Matrix4::rotate_x(-50.0)
+        * Matrix4::scale(&Vec3::new(2.0, 0.5, 3.0))
+}
+
+fn unit_sphere() -> Object {
+    Object::Sphere(Sphere::new(&Point3::new(0.0, 0.0, 0.0), 1.0, &None))
+}
+
+#[test]
+fn singular_matrices_are_rejected() {
+    assert!(Transform::scale(unit_sphere(), &Vec3::new(1.0, 0.0, 1.0)).is_none());
+    assert!(Transform::new(unit_sphere(), &Matrix4::scale(&Vec3::new(2.0, 2.0, 2.0))).is_some());
+    let flat = Matrix4::new([
+        [1.0, 2.0, 3.0, 0.0],
+        [2.0, 4.0, 6.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ]);
+    assert!(Transform::new(unit_sphere(), &flat).is_none());
+}
+
+#[test]
+fn small_scales_stay_invertible() {
+    let tiny = Matrix4::scale(&Vec3::new(1e-4, 1e-4, 1e-4));
+    let inverse = tiny.inverse().unwrap();
+    let p = Point3::new(0.3, -1.7, 2.5);
+    assert!((inverse.point(&tiny.point(&p)) - p).length() < 1e-12);
+    // 两层缩小叠起来, 主元比固定的阈值还小
+    let nested = tiny * Matrix4::scale(&Vec3::new(1e-10, 1e-10, 1e-10));
+    assert!(Transform::new(unit_sphere(), &nested).is_some());
+
+    // 缩小之后的奇异矩阵也还是奇异的
+    let mut flat = tiny;
+    flat.m[1] = [2e-4, 0.0, 0.0, 0.0];
+    assert!(flat.inverse().is_none());
+}
+
+#[test]
+fn inverse_round_trips() {
+    let m = skewed();
+    let inverse = m.inverse().unwrap();
+    let identity = Matrix4::identity();
+    for (a, b) in [m * inverse, inverse * m]
+        .iter()
+        .zip([identity, identity].iter())
+    {
+        for i in 0..4 {
+            for j in 0..4 {
+                assert!((a.m[i][j] - b.m[i][j]).abs() < 1e-12);
+            }
+        }
+    }
+    let p = Point3::new(0.3, -1.7, 2.5);
+    assert!((inverse.point(&m.point(&p)) - p).length() < 1e-12);
+    // 方向不受平移影响
+    let v = Vec3::new(1.0, 2.0, -0.5);
+    assert!((inverse.vector(&m.vector(&v)) - v).length() < 1e-12);
+}
+
+#[test]
+fn normal_matrix_keeps_normals_perpendicular() {
+    let m = skewed();
+    let normal_matrix = m.inverse().unwrap().transpose();
+    // 斜着的平面 x + y = 常数
+    let n = Vec3::new(1.0, 1.0, 0.0);
+    for t in [Vec3::new(1.0, -1.0, 0.0), Vec3::new(0.0, 0.0, 1.0)].iter() {
+        let tangent = m.vector(t);
+        let normal = normal_matrix.vector(&n);
+        assert!(Vec3::dot(&tangent, &normal).abs() < 1e-12);
+    }
+    // 直接用 m 变换法线, 非均匀缩放之后就不垂直了
+    let tangent = m.vector(&Vec3::new(1.0, -1.0, 0.0));
+    assert!(Vec3::dot(&tangent, &m.vector(&n)).abs() > 1e-3);
+
+    // 拉成椭球 x^2 / 4 + y^2 + z^2 = 1, 法线是 (x / 4, y, z) 的方向
+    let ellipsoid = Transform::scale(unit_sphere(), &Vec3::new(2.0, 1.0, 1.0)).unwrap();
+    let rec = hit(
+        &ellipsoid,
+        Point3::new(1.0, 5.0, 0.0),
+        Vec3::new(0.0, -1.0, 0.0),
+    )
+    .unwrap();
+    let expected = Vec3::new(rec.p.x() / 4.0, rec.p.y(), rec.p.z()).unit_vector();
+    assert!((rec.normal - expected).length() < 1e-9);
+}
@@ -0,0 +1,106 @@
+mod common;
+
+use common::hit;
+use raytracer::{Hitrecord, Point3, Triangle, Vec3};
+
+fn triangle(a: [f64; 3], b: [f64; 3], c: [f64; 3]) -> Triangle {
+    Triangle::new(
+        &[
+            Point3::new(a[0], a[1], a[2]),
+            Point3::new(b[0], b[1], b[2]),
+            Point3::new(c[0], c[1], c[2]),
+        ],
+        &None,
+    )
+}
+
+// 从 z = 1 朝 -z 打向 (x, y, 0)
+fn hit_at(triangle: &Triangle, x: f64, y: f64) -> Option<Hitrecord> {
+    hit(triangle, Point3::new(x, y, 1.0), Vec3::new(0.0, 0.0, -1.0))
+}
+
+#[test]
+fn area_uses_all_three_vertices() {
+    let t = triangle([0.0, 0.0, 0.0], [3.0, 0.0, 0.0], [0.0, 4.0, 0.0]);
+    assert!((t.area - 6.0).abs() < 1e-12);
+    // 顶点坐标各分量不相等时, 旧的海伦公式会用错下标
+    let t = triangle([1.0, 2.0, 3.0], [4.0, 6.0, 3.0], [1.0, 2.0, 8.0]);
+    assert!((t.area - 12.5).abs() < 1e-12);
+}
+
+#[test]
+fn hits_interior_with_barycentric_uv() {
+    let t = triangle([0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]);
+    let rec = hit_at(&t, 0.2, 0.3).unwrap();
+    assert!((rec.t - 1.0).abs() < 1e-12);
+    assert!((rec.normal.z() - 1.0).abs() < 1e-12);
+    // 没有纹理坐标时 u, v 是 ver[0], ver[1] 的重心坐标
+    assert!((rec.u - 0.5).abs() < 1e-12);
+    assert!((rec.v - 0.2).abs() < 1e-12);
+    assert!(hit_at(&t, 0.6, 0.6).is_none());
+    assert!(hit_at(&t, -0.1, 0.5).is_none());
+}
+
+#[test]
+fn edges_and_vertices_are_hit() {
+    let t = triangle([0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]);
+    for &(x, y) in [
+        (0.0, 0.0),
+        (1.0, 0.0),
+        (0.0, 1.0),
+        (0.5, 0.0),
+        (0.0, 0.5),
+        (0.5, 0.5),
+    ]
+    .iter()
+    {
+        assert!(hit_at(&t, x, y).is_some(), "missed ({}, {})", x, y);
+    }
+}
+
+#[test]
+fn shared_edges_do_not_leak() {
+    // 一圈共享中心顶点的三角形, 扫过整个圆盘的光线都要打中至少一个
+    let n = 7;
+    let fan: Vec<Triangle> = (0..n)
+        .map(|i| {
+            let a0 = 2.0 * std::f64::consts::PI * i as f64 / n as f64;
+            let a1 = 2.0 * std::f64::consts::PI * (i + 1) as f64 / n as f64;
+            triangle(
+                [0.1, 0.2, 0.0],
+                [0.1 + a0.cos(), 0.2 + a0.sin(), 0.0],
+                [0.1 + a1.cos(), 0.2 + a1.sin(), 0.0],
+            )
+        })
+        .collect();
+    let steps = 200;
+    for i in 0..=steps {
+        for j in 0..=steps {
+            let x = 0.1 - 0.5 + i as f64 / steps as f64;
+            let y = 0.2 - 0.5 + j as f64 / steps as f64;
+            assert!(
+                fan.iter().any(|t| hit_at(t, x, y).is_some()),
+                "leak at ({}, {})",
+                x,
+                y
+            );
+        }
+    }
+}
+
+#[test]
+fn degenerate_triangles_are_never_hit() {
+    let line = triangle([0.0, 0.0, 0.0], [1.0, 1.0, 0.0], [2.0, 2.0, 0.0]);
+    let point = triangle([0.5, 0.5, 0.0], [0.5, 0.5, 0.0], [0.5, 0.5, 0.0]);
+    for t in [&line, &point].iter() {
+        assert!(t.area.abs() < 1e-12);
+        assert!(hit_at(t, 0.5, 0.5).is_none());
+        assert!(hit_at(t, 1.0, 1.0).is_none());
+    }
+}
+
+#[test]
+fn parallel_rays_miss() {
+    let t = triangle([0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]);
+    assert!(hit(&t, Point3::new(-1.0, 0.2, 0.0), Vec3::new(1.0, 0.0, 0.0)).is_none());
+}